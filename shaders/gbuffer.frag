@@ -0,0 +1,14 @@
+#version 460 core
+
+layout(location = 0) in vec3 uv;
+
+layout(location = 0) out vec4 albedo;
+layout(location = 1) out vec3 normal;
+layout(location = 3) out uint materialId;
+
+void main() {
+    albedo = vec4(uv, 1.);
+    normal = normalize(uv);
+    materialId = 1u;
+    gl_FragDepth = uv.z;
+}
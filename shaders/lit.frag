@@ -0,0 +1,19 @@
+#version 460 core
+
+layout(location = 0) in vec3 uv;
+layout(location = 0) out vec4 fragColor;
+
+#ifdef SHADOWS
+layout(location = 0) uniform float shadowStrength;
+#endif
+
+void main() {
+    vec3 color = uv;
+#if QUALITY == QUALITY_HIGH
+    color = pow(color, vec3(2.2));
+#endif
+#ifdef SHADOWS
+    color *= shadowStrength;
+#endif
+    fragColor = vec4(color, 1.);
+}
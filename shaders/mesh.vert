@@ -0,0 +1,14 @@
+#version 460 core
+
+layout(location = 0) in vec3 position;
+layout(location = 1) in vec2 texCoord;
+layout(location = 2) in ivec4 joints;
+layout(location = 3) in mat4 model;
+layout(location = 7) in dmat4 offset;
+
+layout(location = 0) out vec3 uv;
+
+void main() {
+    uv = vec3(texCoord, float(joints.x + gl_VertexID));
+    gl_Position = model * vec4(position, 1.) + vec4(offset[3]);
+}
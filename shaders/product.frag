@@ -0,0 +1,19 @@
+#version 460 core
+
+#ifndef PRODUCT_ID
+#error PRODUCT_ID must be defined
+#endif
+
+layout(location = 0) in vec3 uv;
+layout(location = 0) out vec4 fragColor;
+
+#if PRODUCT_ID == 2
+layout(location = 0) uniform float exposure;
+#endif
+
+void main() {
+    fragColor = vec4(uv, 1.);
+#if PRODUCT_ID == 2
+    fragColor.rgb *= exposure;
+#endif
+}
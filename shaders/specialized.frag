@@ -0,0 +1,17 @@
+#version 460 core
+
+layout(constant_id = 0) const int sampleCount = 4;
+layout(constant_id = 1) const bool useFog = true;
+layout(constant_id = 3) const float fogDensity = 0.5;
+layout(constant_id = 2) const uint maxLights = 8u;
+
+layout(location = 0) in vec3 uv;
+layout(location = 0) out vec4 fragColor;
+
+void main() {
+    vec3 color = uv * float(sampleCount) / float(maxLights);
+    if (useFog) {
+        color *= exp(-fogDensity * uv.z);
+    }
+    fragColor = vec4(color, 1.);
+}
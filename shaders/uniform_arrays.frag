@@ -0,0 +1,22 @@
+#version 460 core
+
+layout(location = 0) in vec2 uv;
+layout(location = 0) out vec4 fragColor;
+
+layout(location = 0) uniform float weights[4];
+layout(location = 4) uniform vec2 offsets[2][3];
+layout(location = 10) uniform bool enabled[2];
+layout(location = 12) uniform mat3 transforms[2];
+layout(location = 14, binding = 2) uniform sampler2D textures[4];
+
+void main() {
+    vec4 color = vec4(0.);
+
+    for (int i = 0; i < 4; ++i) {
+        vec2 offset = offsets[i / 2][i % 3];
+        vec3 coords = transforms[i % 2] * vec3(uv + offset, 1.);
+        color += enabled[i % 2] ? weights[i] * texture(textures[i], coords.xy) : vec4(0.);
+    }
+
+    fragColor = color;
+}
@@ -0,0 +1,19 @@
+#version 460 core
+
+struct Light {
+    vec3 position;
+    float intensity;
+};
+
+layout(std140, binding = 1) uniform Camera {
+    mat4 viewMatrix;
+    vec3 eye;
+    float exposure[3];
+    Light lights[2];
+};
+
+layout(location = 0) out vec4 fragColor;
+
+void main() {
+    fragColor = viewMatrix * vec4(eye * exposure[0] * lights[1].intensity, 1.0);
+}
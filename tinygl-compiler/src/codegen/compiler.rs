@@ -5,6 +5,9 @@ pub trait WrappedItem {
 mod uniform_set;
 pub use uniform_set::*;
 
+#[cfg(feature = "spirv")]
+mod wrapped_permutations;
+
 mod wrapped_program;
 pub use wrapped_program::*;
 
@@ -44,7 +44,7 @@ impl WrappedItem for WrappedUniformSet<'_, '_> {
         // TODO: Some uniforms might have bindings and other might not. How should this be
         // represented in this API?
         for uniform in &unified {
-            let ty = uniform.ty.unwrap();
+            let ty = uniform.ty.clone().unwrap();
             let sc = uniform.name.to_snake_case();
             let type_name: syn::Type = syn::parse_str(&ty.rust_value_type()).unwrap();
 
@@ -52,8 +52,16 @@ impl WrappedItem for WrappedUniformSet<'_, '_> {
                 let ident = format_ident!("get_{}_binding", sc);
 
                 methods.push(quote! {
-                    fn #ident(&self) -> #type_name;
+                    fn #ident(&self) -> u32;
                 });
+
+                if !ty.dimensions().is_empty() {
+                    let ident = format_ident!("get_{}_binding_at", sc);
+
+                    methods.push(quote! {
+                        fn #ident(&self, index: usize) -> u32;
+                    });
+                }
             }
 
             if uniform.format().is_some() {
@@ -69,13 +77,24 @@ impl WrappedItem for WrappedUniformSet<'_, '_> {
 
             if extra.clone().next().is_some() {
                 methods.push(quote! {
-                    fn #ident(&self, gl: &::tinygl::Context, #(#extra),* value: #type_name);
+                    fn #ident(&self, gl: &::tinygl::Context, #(#extra,)* value: #type_name);
                 });
             } else {
                 methods.push(quote! {
                     fn #ident(&self, gl: &::tinygl::Context, value: #type_name);
                 });
             }
+
+            if !ty.dimensions().is_empty() {
+                let element = ty.element_type();
+                let element_name: syn::Type = syn::parse_str(&element.rust_value_type()).unwrap();
+                let ident = format_ident!("set_{}_at", sc);
+                let extra = element.uniform_method_extra_args_with_ty().into_iter();
+
+                methods.push(quote! {
+                    fn #ident(&self, gl: &::tinygl::Context, #(#extra,)* index: usize, value: #element_name);
+                });
+            }
         }
 
         let trait_name = format_ident!("{}UniformSet", self.id().to_camel_case());
@@ -93,7 +112,7 @@ impl WrappedItem for WrappedUniformSet<'_, '_> {
 
             for uniform in &unified {
                 let sc = uniform.name.to_snake_case();
-                let ty = uniform.ty.unwrap();
+                let ty = uniform.ty.clone().unwrap();
                 let type_name: syn::Type = syn::parse_str(&ty.rust_value_type()).unwrap();
                 let struct_name = format_ident!("{}", program.struct_name());
 
@@ -101,10 +120,20 @@ impl WrappedItem for WrappedUniformSet<'_, '_> {
                     let ident = format_ident!("get_{}_binding", sc);
 
                     methods.push(quote! {
-                        fn #ident(&self) -> #type_name {
+                        fn #ident(&self) -> u32 {
                             #struct_name::#ident(self)
                         }
                     });
+
+                    if !ty.dimensions().is_empty() {
+                        let ident = format_ident!("get_{}_binding_at", sc);
+
+                        methods.push(quote! {
+                            fn #ident(&self, index: usize) -> u32 {
+                                #struct_name::#ident(self, index)
+                            }
+                        });
+                    }
                 }
 
                 if uniform.format().is_some() {
@@ -135,6 +164,21 @@ impl WrappedItem for WrappedUniformSet<'_, '_> {
                         }
                     });
                 }
+
+                if !ty.dimensions().is_empty() {
+                    let element = ty.element_type();
+                    let element_name: syn::Type =
+                        syn::parse_str(&element.rust_value_type()).unwrap();
+                    let ident = format_ident!("set_{}_at", sc);
+                    let extra_args = element.uniform_method_extra_args_with_ty().into_iter();
+                    let extra_values = element.uniform_method_extra_args_no_ty().into_iter();
+
+                    methods.push(quote! {
+                        fn #ident(&self, gl: &::tinygl::Context, #(#extra_args,)* index: usize, value: #element_name) {
+                            #struct_name::#ident(self, gl, #(#extra_values,)* index, value)
+                        }
+                    });
+                }
             }
 
             let program_struct_name = format_ident!("{}", program.struct_name());
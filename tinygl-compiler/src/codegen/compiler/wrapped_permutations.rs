@@ -0,0 +1,396 @@
+use heck::{CamelCase, SnakeCase};
+use quote::{format_ident, quote};
+
+use crate::{
+    codegen::types::{block_methods, CodegenExt},
+    FeatureKey, WrappedPermutations, WrappedShaderDetails,
+};
+
+use super::WrappedItem;
+
+impl WrappedItem for WrappedPermutations {
+    fn generate(&self) -> crate::Result<proc_macro2::TokenStream> {
+        let mut parts = Vec::new();
+
+        // Shader types for every distinct permutation
+        for shader in self.shaders() {
+            parts.push(shader.generate()?);
+        }
+
+        // Variant key type
+        let struct_name = format_ident!("{}", self.struct_name());
+        let variant_name = format_ident!("{}", self.variant_struct_name());
+        let programs_name = format_ident!("{}", self.programs_struct_name());
+
+        let mut field_names = Vec::new();
+        let mut field_types = Vec::new();
+        let mut strides = Vec::new();
+        let mut stride = 1usize;
+
+        for key in self.keys() {
+            field_names.push(format_ident!("{}", key.name().to_snake_case()));
+            strides.push(stride);
+            stride *= key.value_count();
+
+            match key {
+                FeatureKey::Bool(_) => {
+                    field_types.push(quote! { bool });
+                }
+                FeatureKey::Enum(name, values) => {
+                    let enum_name = format_ident!("{}{}", self.struct_name(), name.to_camel_case());
+                    let value_names: Vec<_> = values
+                        .iter()
+                        .map(|value| format_ident!("{}", value.to_camel_case()))
+                        .collect();
+                    let default_value = &value_names[0];
+
+                    parts.push(quote! {
+                        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+                        pub enum #enum_name {
+                            #(#value_names),*
+                        }
+
+                        impl Default for #enum_name {
+                            fn default() -> Self {
+                                Self::#default_value
+                            }
+                        }
+                    });
+
+                    field_types.push(quote! { #enum_name });
+                }
+            }
+        }
+
+        let variant_count = self.variant_count();
+
+        parts.push(quote! {
+            #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+            pub struct #variant_name {
+                #(pub #field_names: #field_types,)*
+            }
+
+            impl #variant_name {
+                /// Number of variants
+                pub const COUNT: usize = #variant_count;
+
+                /// Index of this variant among all permutations
+                pub fn index(&self) -> usize {
+                    0 #(+ self.#field_names as usize * #strides)*
+                }
+            }
+        });
+
+        // Program type shared by all variants, with optional uniform handles since shaders
+        // differ between variants
+        let shaders_with_uniforms: Vec<_> = self
+            .shaders()
+            .iter()
+            .enumerate()
+            .filter(|(_, shader)| !shader.uniforms().is_empty())
+            .collect();
+        let uniform_locations_name: Vec<_> = shaders_with_uniforms
+            .iter()
+            .map(|(_, shader)| format_ident!("{}", shader.uniform_locations_name()))
+            .collect();
+        let uniform_struct_name: Vec<_> = shaders_with_uniforms
+            .iter()
+            .map(|(_, shader)| format_ident!("{}", shader.uniform_struct_name()))
+            .collect();
+        let uniform_variants = shaders_with_uniforms.iter().map(|(shader_index, _)| {
+            let variants = (0..variant_count)
+                .filter(|index| self.variant_shaders(*index).contains(shader_index));
+            quote! { [#(#variants),*] }
+        });
+
+        let mut methods = Vec::new();
+
+        // Builders for each variant
+        let build_arms = (0..variant_count).map(|index| {
+            let shaders: Vec<_> = self
+                .variant_shaders(index)
+                .iter()
+                .map(|shader_index| &self.shaders()[*shader_index])
+                .collect();
+            let shader_variable_name: Vec<_> = shaders
+                .iter()
+                .map(|shader| format_ident!("{}", shader.shader_variable_name()))
+                .collect();
+            let shader_struct_name = shaders
+                .iter()
+                .map(|shader| format_ident!("{}", shader.shader_struct_name()));
+
+            quote! {
+                #index => {
+                    #(let #shader_variable_name = ::tinygl::wrappers::GlRefHandle::new(gl, #shader_struct_name::build(gl)?);)*
+                    Self::new(gl, variant, &[#(#shader_variable_name.as_ref()),*])
+                }
+            }
+        });
+
+        methods.push(quote! {
+            /// Build the program for the given variant, returning a handle that deletes it when
+            /// dropped
+            pub fn build(gl: &::tinygl::Context, variant: #variant_name) -> ::tinygl::Result<::tinygl::wrappers::GlRefHandle<'_, Self>> {
+                let program = match variant.index() {
+                    #(#build_arms)*
+                    _ => unreachable!(),
+                }?;
+
+                Ok(::tinygl::wrappers::GlRefHandle::new(gl, program))
+            }
+
+            fn new(
+                gl: &::tinygl::Context,
+                variant: #variant_name,
+                shaders: &[&dyn ::tinygl::wrappers::ShaderCommon],
+            ) -> ::tinygl::Result<Self> {
+                let program_name = shaders
+                    .iter()
+                    .fold(::tinygl::wrappers::RuntimeProgramBuilder::new(gl), |builder, shader| {
+                        builder.shader(*shader)
+                    })
+                    .build()?
+                    .into_inner();
+                let index = variant.index();
+
+                Ok(Self {
+                    name: program_name,
+                    variant,
+                    #(#uniform_locations_name: if #uniform_variants.contains(&index) {
+                        Some(#uniform_struct_name::new(gl, program_name))
+                    } else {
+                        None
+                    }),*
+                })
+            }
+
+            pub fn variant(&self) -> #variant_name {
+                self.variant
+            }
+        });
+
+        // Uniform cache management for the shaders of the current variant
+        let cached_locations_name: Vec<_> = shaders_with_uniforms
+            .iter()
+            .filter(|(_, shader)| shader.cache_uniforms())
+            .map(|(_, shader)| format_ident!("{}", shader.uniform_locations_name()))
+            .collect();
+
+        if !cached_locations_name.is_empty() {
+            methods.push(quote! {
+                /// Number of uniform values uploaded and skipped by the setters
+                pub fn uniform_cache_stats(&self) -> ::tinygl::wrappers::UniformCacheStats {
+                    let mut stats = ::tinygl::wrappers::UniformCacheStats::default();
+
+                    #(if let Some(locations) = &self.#cached_locations_name {
+                        stats = stats + locations.cache_stats();
+                    })*
+
+                    stats
+                }
+
+                pub fn reset_uniform_cache_stats(&self) {
+                    #(if let Some(locations) = &self.#cached_locations_name {
+                        locations.reset_cache_stats();
+                    })*
+                }
+
+                /// Forget the cached uniform values, so the next setter calls upload them
+                ///
+                /// This must be called after relinking the program, since linking resets the
+                /// values of its uniforms.
+                pub fn invalidate_uniform_cache(&self) {
+                    #(if let Some(locations) = &self.#cached_locations_name {
+                        locations.invalidate_cache();
+                    })*
+                }
+            });
+        }
+
+        // Uniform setters, forwarded to the shader of the current variant that declares them
+        let mut known: Vec<(&crate::reflect::FoundUniform, Vec<_>)> = Vec::new();
+
+        for (_, shader) in &shaders_with_uniforms {
+            for uniform in shader.uniforms() {
+                let location_name = format_ident!("{}", shader.uniform_locations_name());
+
+                match known
+                    .iter_mut()
+                    .find(|(other, _)| other.name == uniform.name)
+                {
+                    // Later declarations with a conflicting type are skipped
+                    Some((other, location_names)) if other.ty == uniform.ty => {
+                        location_names.push(location_name);
+                    }
+                    Some(_) => {}
+                    None => known.push((uniform, vec![location_name])),
+                }
+            }
+        }
+
+        for (uniform, location_names) in known {
+            let ty = uniform.ty.clone().unwrap();
+            let sc = uniform.name.to_snake_case();
+            let type_name: syn::Type = syn::parse_str(&ty.rust_value_type()).unwrap();
+            let ident = format_ident!("set_{}", sc);
+            let extra_args = ty.uniform_method_extra_args_with_ty().into_iter();
+            let extra_values = ty.uniform_method_extra_args_no_ty();
+            let calls = location_names.iter().map(|location_name| {
+                let extra_values = extra_values.iter();
+
+                quote! {
+                    if let Some(locations) = &self.#location_name {
+                        locations.#ident(gl, self.name, #(#extra_values,)* value);
+                    }
+                }
+            });
+
+            methods.push(quote! {
+                pub fn #ident(&self, gl: &::tinygl::Context, #(#extra_args,)* value: #type_name) {
+                    #(#calls else)* {}
+                }
+            });
+
+            // Element setters for arrays
+            if !ty.dimensions().is_empty() {
+                let element = ty.element_type();
+                let element_name: syn::Type = syn::parse_str(&element.rust_value_type()).unwrap();
+                let ident = format_ident!("set_{}_at", sc);
+                let extra_args = element.uniform_method_extra_args_with_ty().into_iter();
+                let extra_values = element.uniform_method_extra_args_no_ty();
+                let calls = location_names.iter().map(|location_name| {
+                    let extra_values = extra_values.iter();
+
+                    quote! {
+                        if let Some(locations) = &self.#location_name {
+                            locations.#ident(gl, self.name, #(#extra_values,)* index, value);
+                        }
+                    }
+                });
+
+                methods.push(quote! {
+                    pub fn #ident(&self, gl: &::tinygl::Context, #(#extra_args,)* index: usize, value: #element_name) {
+                        #(#calls else)* {}
+                    }
+                });
+            }
+
+            if let Some(binding) = uniform.binding {
+                let ident = format_ident!("get_{}_binding", sc);
+                let binding = binding as u32;
+
+                methods.push(quote! {
+                    pub fn #ident(&self) -> u32 {
+                        #binding
+                    }
+                });
+
+                // Arrays of samplers are bound to consecutive texture units
+                if !ty.dimensions().is_empty() {
+                    let ident = format_ident!("get_{}_binding_at", sc);
+
+                    methods.push(quote! {
+                        pub fn #ident(&self, index: usize) -> u32 {
+                            #binding + index as u32
+                        }
+                    });
+                }
+            }
+
+            if let Some(format) = uniform.format() {
+                let ident = format_ident!("get_{}_format", sc);
+
+                methods.push(quote! {
+                    pub fn #ident(&self) -> u32 {
+                        #format
+                    }
+                });
+            }
+        }
+
+        // Block binding methods for all the shaders
+        let mut known_blocks = std::collections::HashSet::new();
+
+        for block in self
+            .shaders()
+            .iter()
+            .flat_map(|shader| shader.blocks().iter())
+        {
+            if known_blocks.insert(&block.name) {
+                methods.push(block_methods(block));
+            }
+        }
+
+        Ok(quote! {
+            #(#parts)*
+
+            pub struct #struct_name {
+                // Program name handle
+                name: ::tinygl::gl::Program,
+                // Variant this program was built for
+                variant: #variant_name,
+                // Uniform handles, for the shaders attached to this variant
+                #(#uniform_locations_name: Option<#uniform_struct_name>),*
+            }
+
+            impl #struct_name {
+                #(#methods)*
+            }
+
+            impl ::tinygl::wrappers::ProgramCommon for #struct_name {
+                fn name(&self) -> ::tinygl::gl::Program {
+                    self.name
+                }
+            }
+
+            impl ::tinygl::wrappers::GlDrop for #struct_name {
+                unsafe fn drop(&mut self, gl: &::tinygl::Context) {
+                    use ::tinygl::wrappers::ProgramCommon;
+                    gl.delete_program(self.name());
+                }
+            }
+
+            /// Programs for each variant, built on first use
+            ///
+            /// The cached programs are owned by this object, and deleted by its `GlDrop`
+            /// implementation.
+            #[derive(Default)]
+            pub struct #programs_name {
+                programs: ::std::collections::HashMap<#variant_name, #struct_name>,
+            }
+
+            impl #programs_name {
+                /// Create an empty program cache, returning a handle that deletes the built
+                /// programs when dropped
+                pub fn new(gl: &::tinygl::Context) -> ::tinygl::wrappers::GlRefHandle<'_, Self> {
+                    ::tinygl::wrappers::GlRefHandle::new(gl, Self::default())
+                }
+
+                /// Get the program for `variant`, building it if needed
+                pub fn get(&mut self, gl: &::tinygl::Context, variant: #variant_name) -> ::tinygl::Result<&#struct_name> {
+                    if !self.programs.contains_key(&variant) {
+                        // Ownership moves from the handle to the cache, which deletes it
+                        let program = #struct_name::build(gl, variant)?;
+                        self.programs.insert(variant, program.into_inner());
+                    }
+
+                    Ok(&self.programs[&variant])
+                }
+
+                /// Number of variants built so far
+                pub fn built_count(&self) -> usize {
+                    self.programs.len()
+                }
+            }
+
+            impl ::tinygl::wrappers::GlDrop for #programs_name {
+                unsafe fn drop(&mut self, gl: &::tinygl::Context) {
+                    for (_, mut program) in self.programs.drain() {
+                        ::tinygl::wrappers::GlDrop::drop(&mut program, gl);
+                    }
+                }
+            }
+        })
+    }
+}
@@ -1,7 +1,12 @@
 use heck::SnakeCase;
 use quote::{format_ident, quote};
 
-use crate::{codegen::types::CodegenExt, WrappedProgram};
+use crate::{
+    codegen::types::{
+        attribute_data_type, attribute_description, block_methods, output_description, CodegenExt,
+    },
+    WrappedProgram,
+};
 
 use super::WrappedItem;
 
@@ -18,10 +23,26 @@ impl WrappedItem for WrappedProgram<'_> {
             .map(|shader| format_ident!("{}", shader.uniform_struct_name()))
             .collect();
 
+        // Workgroup sizes given by specialization constants are only known after linking
+        let local_size = self.shaders().find_map(|shader| shader.local_size());
+        let specialized_local_size = local_size.filter(|local_size| local_size.is_specialized());
+        let local_size_field = specialized_local_size.map(|_| {
+            quote! {
+                // Local workgroup size of the specialized compute shader
+                local_size: [u32; 3],
+            }
+        });
+        let local_size_init = specialized_local_size.map(|_| {
+            quote! {
+                local_size: unsafe { gl.get_program_compute_work_group_size(program_name) },
+            }
+        });
+
         let prog_struct = quote! {
             pub struct #struct_name {
                 // Program name handle
                 name: ::tinygl::gl::Program,
+                #local_size_field
                 // Uniform handles
                 #(#uniform_locations_name: #uniform_struct_name),*
             }
@@ -48,6 +69,7 @@ impl WrappedItem for WrappedProgram<'_> {
 
                 Ok(Self {
                     name: program_name,
+                    #local_size_init
                     #(#uniform_locations_name: #uniform_struct_name::new(gl, program_name)),*
                 })
             }
@@ -55,23 +77,55 @@ impl WrappedItem for WrappedProgram<'_> {
 
         // Write builder (constructs shaders and then calls the constructor)
         methods.push(quote! {
-            pub fn build(gl: &::tinygl::Context) -> ::tinygl::Result<Self> {
+            /// Build the program, returning a handle that deletes it when dropped
+            ///
+            /// Use `into_handle` on the result to keep the program around with a shared context.
+            pub fn build(gl: &::tinygl::Context) -> ::tinygl::Result<::tinygl::wrappers::GlRefHandle<'_, Self>> {
                 #(let #shader_variable_name = ::tinygl::wrappers::GlRefHandle::new(gl, #shader_struct_name::build(gl)?);)*
 
-                Ok(Self::new(
+                Ok(::tinygl::wrappers::GlRefHandle::new(gl, Self::new(
                     gl,
                     #(#shader_variable_name.as_ref()),*
-                )?)
+                )?))
             }
         });
 
+        // Uniform cache management for the included shaders
+        let cached_locations_name: Vec<_> = self
+            .shaders_with_uniforms()
+            .filter(|shader| shader.cache_uniforms())
+            .map(|shader| format_ident!("{}", shader.uniform_locations_name()))
+            .collect();
+
+        if !cached_locations_name.is_empty() {
+            methods.push(quote! {
+                /// Number of uniform values uploaded and skipped by the setters
+                pub fn uniform_cache_stats(&self) -> ::tinygl::wrappers::UniformCacheStats {
+                    ::tinygl::wrappers::UniformCacheStats::default()
+                        #(+ self.#cached_locations_name.cache_stats())*
+                }
+
+                pub fn reset_uniform_cache_stats(&self) {
+                    #(self.#cached_locations_name.reset_cache_stats();)*
+                }
+
+                /// Forget the cached uniform values, so the next setter calls upload them
+                ///
+                /// This must be called after relinking the program, since linking resets the
+                /// values of its uniforms.
+                pub fn invalidate_uniform_cache(&self) {
+                    #(self.#cached_locations_name.invalidate_cache();)*
+                }
+            });
+        }
+
         // List of seen uniforms, since uniform names are unique
         let mut known = std::collections::HashSet::new();
 
         // Uniform getters/setters for the included shaders
         for shader in self.shaders_with_uniforms() {
             for uniform in shader.uniforms() {
-                let ty = uniform.ty.unwrap();
+                let ty = uniform.ty.clone().unwrap();
                 let sc = uniform.name.to_snake_case();
                 let type_name: syn::Type = syn::parse_str(&ty.rust_value_type()).unwrap();
 
@@ -89,8 +143,8 @@ impl WrappedItem for WrappedProgram<'_> {
 
                 if extra_args.clone().next().is_some() {
                     methods.push(quote! {
-                        pub fn #ident(&self, gl: &::tinygl::Context, #(#extra_args),* value: #type_name) {
-                            self.#location_name.#ident(gl, self.name, #(#extra_values)*,, value);
+                        pub fn #ident(&self, gl: &::tinygl::Context, #(#extra_args,)* value: #type_name) {
+                            self.#location_name.#ident(gl, self.name, #(#extra_values,)* value);
                         }
                     });
                 } else {
@@ -101,15 +155,41 @@ impl WrappedItem for WrappedProgram<'_> {
                     });
                 }
 
+                // Element setters for arrays
+                if !ty.dimensions().is_empty() {
+                    let element = ty.element_type();
+                    let element_name: syn::Type =
+                        syn::parse_str(&element.rust_value_type()).unwrap();
+                    let ident = format_ident!("set_{}_at", sc);
+                    let extra_args = element.uniform_method_extra_args_with_ty().into_iter();
+                    let extra_values = element.uniform_method_extra_args_no_ty().into_iter();
+
+                    methods.push(quote! {
+                        pub fn #ident(&self, gl: &::tinygl::Context, #(#extra_args,)* index: usize, value: #element_name) {
+                            self.#location_name.#ident(gl, self.name, #(#extra_values,)* index, value);
+                        }
+                    });
+                }
+
                 if let Some(binding) = uniform.binding {
                     let ident = format_ident!("get_{}_binding", sc);
                     let binding = binding as u32;
 
                     methods.push(quote! {
-                        pub fn #ident(&self) -> #type_name {
+                        pub fn #ident(&self) -> u32 {
                             #binding
                         }
                     });
+
+                    if !ty.dimensions().is_empty() {
+                        let ident = format_ident!("get_{}_binding_at", sc);
+
+                        methods.push(quote! {
+                            pub fn #ident(&self, index: usize) -> u32 {
+                                self.#location_name.#ident(index)
+                            }
+                        });
+                    }
                 }
 
                 if let Some(format) = uniform.format() {
@@ -124,9 +204,196 @@ impl WrappedItem for WrappedProgram<'_> {
             }
         }
 
+        // Block binding methods for the included shaders
+        let mut known_blocks = std::collections::HashSet::new();
+
+        for block in self.shaders().flat_map(|shader| shader.blocks().iter()) {
+            // Skip this block if it has been added already
+            if known_blocks.insert(&block.name) {
+                methods.push(block_methods(block));
+            }
+        }
+
+        // Vertex input description and vertex array setup
+        let mut vertex_inputs = None;
+        let attributes: Vec<_> = self.attributes().collect();
+
+        if !attributes.is_empty() {
+            let inputs_struct_name = format_ident!("{}", self.vertex_inputs_struct_name());
+            let field: Vec<_> = attributes
+                .iter()
+                .map(|attribute| format_ident!("{}", attribute.name.to_snake_case()))
+                .collect();
+            let data_type = attributes
+                .iter()
+                .map(|attribute| attribute_data_type(attribute));
+            let description = attributes
+                .iter()
+                .map(|attribute| attribute_description(attribute));
+            let index = 0..attributes.len();
+
+            vertex_inputs = Some(quote! {
+                pub struct #inputs_struct_name<'b> {
+                    #(pub #field: ::tinygl::wrappers::VertexBufferBinding<'b, #data_type>),*
+                }
+            });
+
+            methods.push(quote! {
+                pub const VERTEX_ATTRIBUTES: &'static [::tinygl::wrappers::VertexAttribute] = &[
+                    #(#description),*
+                ];
+
+                /// Configure `vertex_array` to source this program's vertex inputs from `inputs`
+                ///
+                /// The vertex array is left bound when this method returns.
+                pub fn configure_vertex_array(
+                    &self,
+                    gl: &::tinygl::Context,
+                    vertex_array: &::tinygl::wrappers::VertexArray,
+                    inputs: &#inputs_struct_name<'_>,
+                ) -> ::tinygl::Result<()> {
+                    unsafe {
+                        vertex_array.bind(gl);
+                        #(inputs.#field.configure(gl, &Self::VERTEX_ATTRIBUTES[#index])?;)*
+                    }
+
+                    Ok(())
+                }
+            });
+        }
+
+        // Fragment output description and draw buffers setup
+        let outputs: Vec<_> = self.outputs().collect();
+
+        if !outputs.is_empty() {
+            let description = outputs.iter().map(|output| output_description(output));
+            let draw_buffers = (0..=outputs.iter().map(|output| output.location).max().unwrap())
+                .map(|location| {
+                    if outputs.iter().any(|output| output.location == location) {
+                        quote! { ::tinygl::gl::COLOR_ATTACHMENT0 + #location }
+                    } else {
+                        quote! { ::tinygl::gl::NONE }
+                    }
+                });
+
+            methods.push(quote! {
+                pub const FRAGMENT_OUTPUTS: &'static [::tinygl::wrappers::FragmentOutput] = &[
+                    #(#description),*
+                ];
+
+                /// Route this program's fragment outputs to the matching color attachments of the
+                /// currently bound draw framebuffer
+                pub fn configure_draw_buffers(&self, gl: &::tinygl::Context) {
+                    unsafe {
+                        gl.draw_buffers(&[#(#draw_buffers),*]);
+                    }
+                }
+            });
+        }
+
+        // Patch sizes for tessellation programs. The input patch size is not declared by the
+        // shaders, so it is set by the caller.
+        if let Some(output_patch_vertices) = self
+            .shaders()
+            .find_map(|shader| shader.output_patch_vertices())
+        {
+            let output_patch_vertices = output_patch_vertices as i32;
+
+            methods.push(quote! {
+                /// Number of vertices per output patch, declared by the tessellation control shader
+                pub const OUTPUT_PATCH_VERTICES: i32 = #output_patch_vertices;
+            });
+        }
+
+        if self.shaders().any(|shader| shader.kind().is_tessellation()) {
+            methods.push(quote! {
+                /// Set the number of vertices per input patch
+                pub fn set_patch_vertices(&self, gl: &::tinygl::Context, count: i32) {
+                    unsafe {
+                        gl.patch_parameteri(::tinygl::gl::PATCH_VERTICES, count);
+                    }
+                }
+            });
+        }
+
+        // Dispatch helpers for compute programs
+        if let Some(local_size) = local_size {
+            let [x, y, z] = local_size.default_size();
+
+            let local_size_getter = if specialized_local_size.is_some() {
+                quote! {
+                    /// Local workgroup size of the compute shader, as specialized when building it
+                    pub fn local_size(&self) -> [u32; 3] {
+                        self.local_size
+                    }
+                }
+            } else {
+                quote! {
+                    /// Local workgroup size of the compute shader
+                    pub fn local_size(&self) -> [u32; 3] {
+                        Self::LOCAL_SIZE
+                    }
+                }
+            };
+
+            methods.push(quote! {
+                /// Local workgroup size declared by the compute shader, before specialization
+                pub const LOCAL_SIZE: [u32; 3] = [#x, #y, #z];
+
+                #local_size_getter
+
+                /// Make this program current and dispatch workgroups
+                ///
+                /// # Parameters
+                ///
+                /// * `gl`: GL context
+                /// * `groups_x`, `groups_y`, `groups_z`: number of workgroups along each axis
+                pub fn dispatch(&self, gl: &::tinygl::Context, groups_x: u32, groups_y: u32, groups_z: u32) {
+                    unsafe {
+                        gl.use_program(Some(self.name));
+                        gl.dispatch_compute(groups_x, groups_y, groups_z);
+                    }
+                }
+
+                /// Make this program current and dispatch enough workgroups to cover a grid of
+                /// invocations
+                ///
+                /// # Parameters
+                ///
+                /// * `gl`: GL context
+                /// * `width`, `height`, `depth`: number of invocations along each axis
+                pub fn dispatch_for_size(&self, gl: &::tinygl::Context, width: u32, height: u32, depth: u32) {
+                    let [x, y, z] = self.local_size();
+                    self.dispatch(
+                        gl,
+                        (width + x - 1) / x,
+                        (height + y - 1) / y,
+                        (depth + z - 1) / z,
+                    );
+                }
+
+                /// Make this program current and dispatch workgroups with counts read from a buffer
+                ///
+                /// # Parameters
+                ///
+                /// * `gl`: GL context
+                /// * `buffer`: buffer holding the workgroup counts, as three `u32` values
+                /// * `offset`: offset of the counts in the buffer, in bytes
+                pub fn dispatch_indirect<B: ::tinygl::wrappers::Pod>(&self, gl: &::tinygl::Context, buffer: &::tinygl::wrappers::Buffer<B>, offset: isize) {
+                    unsafe {
+                        gl.use_program(Some(self.name));
+                        buffer.bind(gl, ::tinygl::gl::DISPATCH_INDIRECT_BUFFER);
+                        gl.dispatch_compute_indirect(offset);
+                    }
+                }
+            });
+        }
+
         Ok(quote! {
             #prog_struct
 
+            #vertex_inputs
+
             impl #struct_name {
                 #(#methods)*
             }
@@ -2,8 +2,13 @@ use heck::SnakeCase;
 use quote::{format_ident, quote};
 
 use crate::{
-    codegen::types::{CodegenExt, UniformValueExt},
+    codegen::types::{
+        array_conversion, block_methods, cached_value, cached_value_type, generate_struct,
+        CodegenExt, UniformValueExt,
+    },
     model::AsOutputFormat,
+    reflect::BlockKind,
+    types::AtomType,
     Error, WrappedShader, WrappedShaderDetails,
 };
 
@@ -30,34 +35,64 @@ fn filter_src<T: AsOutputFormat>(this: &WrappedShader<T>) -> String {
     output
 }
 
+/// Tokens for the embedded shader, with the original and embedded sizes of SPIR-V binaries
+type ShaderTokens = (proc_macro2::TokenStream, Option<(usize, usize)>);
+
 #[cfg(feature = "spirv")]
-fn get_shader_tokens<T: AsOutputFormat>(
-    this: &WrappedShader<T>,
-) -> crate::Result<proc_macro2::TokenStream> {
+fn get_shader_tokens<T: AsOutputFormat>(this: &WrappedShader<T>) -> crate::Result<ShaderTokens> {
     if this.prefer_spirv() && this.result().as_spirv().is_some() {
+        let spirv = this
+            .result()
+            .object()
+            .as_spirv()
+            .ok_or(Error::SpirVObjectRequired)?;
+
+        // Reflection is done at this point, so debug names are not needed anymore
+        let stripped = if this.strip_debug_info() {
+            Some(spirv.strip_debug_info())
+        } else {
+            None
+        };
+        let binary = stripped.as_ref().unwrap_or(spirv).as_bytes_u8();
+
         // Just write spv file
-        let res = syn::LitByteStr::new(
-            &this
-                .result()
-                .object()
-                .as_spirv()
-                .ok_or(Error::SpirVObjectRequired)?
-                .as_bytes_u8(),
-            proc_macro2::Span::call_site(),
-        );
-        Ok(quote! { #res })
+        let res = syn::LitByteStr::new(binary, proc_macro2::Span::call_site());
+        Ok((
+            quote! { #res },
+            Some((spirv.as_bytes_u8().len(), binary.len())),
+        ))
     } else {
         let out = filter_src(this);
-        Ok(quote! { #out })
+        Ok((quote! { #out }, None))
     }
 }
 
 #[cfg(not(feature = "spirv"))]
-fn get_shader_tokens<T: AsOutputFormat>(
-    this: &WrappedShader<T>,
-) -> crate::Result<proc_macro2::TokenStream> {
+fn get_shader_tokens<T: AsOutputFormat>(this: &WrappedShader<T>) -> crate::Result<ShaderTokens> {
     let out = filter_src(this);
-    Ok(quote! { #out })
+    Ok((quote! { #out }, None))
+}
+
+/// Names of the innermost arrays of a uniform, or the name of the uniform if it is not an array
+///
+/// # Parameters
+///
+/// * `name`: name of the uniform
+/// * `dimensions`: array dimensions of the uniform, from the outermost to the innermost
+fn inner_array_names(name: &str, dimensions: &[u32]) -> Vec<String> {
+    let outer = dimensions
+        .split_last()
+        .map(|(_, outer)| outer)
+        .unwrap_or(&[]);
+
+    outer
+        .iter()
+        .fold(vec![name.to_owned()], |names, dimension| {
+            names
+                .iter()
+                .flat_map(|name| (0..*dimension).map(move |index| format!("{}[{}]", name, index)))
+                .collect()
+        })
 }
 
 fn to_cstr(s: &str) -> proc_macro2::TokenStream {
@@ -70,7 +105,7 @@ fn to_cstr(s: &str) -> proc_macro2::TokenStream {
 
 impl<T: AsOutputFormat> WrappedItem for WrappedShader<T> {
     fn generate(&self) -> crate::Result<proc_macro2::TokenStream> {
-        let shader_tokens = get_shader_tokens(self)?;
+        let (shader_tokens, sizes) = get_shader_tokens(self)?;
         let is_source = !self.prefer_spirv() && self.result().as_source().is_some();
 
         // Shader resource structure
@@ -117,6 +152,130 @@ impl<T: AsOutputFormat> WrappedItem for WrappedShader<T> {
             }
         });
 
+        // Sizes of the embedded binary, before and after stripping
+        if let Some((original_size, size)) = sizes {
+            parts.push(quote! {
+                impl #struct_name {
+                    /// Size in bytes of the compiled SPIR-V binary
+                    pub const SPIRV_ORIGINAL_SIZE: usize = #original_size;
+                    /// Size in bytes of the embedded SPIR-V binary
+                    pub const SPIRV_SIZE: usize = #size;
+                }
+            });
+        }
+
+        // Specialization constants, with their default values
+        if !self.spec_constants().is_empty() {
+            let specialization_name = format_ident!("{}", self.specialization_struct_name());
+            let field_names: Vec<_> = self
+                .spec_constants()
+                .iter()
+                .map(|constant| format_ident!("{}", constant.name.to_snake_case()))
+                .collect();
+            let field_types = self.spec_constants().iter().map(|constant| -> syn::Type {
+                syn::parse_str(constant.ty.rust_primitive_type()).unwrap()
+            });
+            let defaults = self.spec_constants().iter().map(|constant| {
+                let value = constant.default_value;
+                match constant.ty {
+                    AtomType::Bool => {
+                        let value = value != 0;
+                        quote! { #value }
+                    }
+                    AtomType::Int => {
+                        let value = value as i32;
+                        quote! { #value }
+                    }
+                    AtomType::Float if f32::from_bits(value).is_finite() => {
+                        let value = f32::from_bits(value);
+                        quote! { #value }
+                    }
+                    AtomType::Float => quote! { f32::from_bits(#value) },
+                    _ => quote! { #value },
+                }
+            });
+
+            let build_with = if is_source {
+                // Transpiled sources define specialization constants as macros
+                let defines = self.spec_constants().iter().zip(&field_names).map(
+                    |(constant, field)| {
+                        let macro_name = constant.macro_name();
+                        let value = match constant.ty {
+                            AtomType::UInt => quote! { format!("{}u", specialization.#field) },
+                            // GLSL has no literals for NaN and infinities
+                            AtomType::Float => quote! {
+                                if specialization.#field.is_finite() {
+                                    format!("{:?}", specialization.#field)
+                                } else {
+                                    format!("uintBitsToFloat({}u)", specialization.#field.to_bits())
+                                }
+                            },
+                            _ => quote! { format!("{}", specialization.#field) },
+                        };
+
+                        quote! { (#macro_name, #value) }
+                    },
+                );
+
+                quote! {
+                    <Self as #st>::build_with_defines(
+                        gl,
+                        ::tinygl::gl::#kind_constant_name,
+                        &[#(#defines),*],
+                    )?
+                }
+            } else {
+                let spec_ids = self
+                    .spec_constants()
+                    .iter()
+                    .map(|constant| constant.spec_id);
+                let values =
+                    self.spec_constants()
+                        .iter()
+                        .zip(&field_names)
+                        .map(|(constant, field)| match constant.ty {
+                            AtomType::Float => quote! { specialization.#field.to_bits() },
+                            AtomType::UInt => quote! { specialization.#field },
+                            _ => quote! { specialization.#field as u32 },
+                        });
+
+                quote! {
+                    <Self as #st>::build_specialized(
+                        gl,
+                        ::tinygl::gl::#kind_constant_name,
+                        &::tinygl::wrappers::SpecializationInfo {
+                            constant_index: &[#(#spec_ids),*],
+                            constant_value: &[#(#values),*],
+                            ..Default::default()
+                        },
+                    )?
+                }
+            };
+
+            parts.push(quote! {
+                #[derive(Debug, Clone, Copy, PartialEq)]
+                pub struct #specialization_name {
+                    #(pub #field_names: #field_types,)*
+                }
+
+                impl Default for #specialization_name {
+                    fn default() -> Self {
+                        Self {
+                            #(#field_names: #defaults,)*
+                        }
+                    }
+                }
+
+                impl #struct_name {
+                    pub fn build_with(gl: &::tinygl::Context, specialization: #specialization_name) -> ::tinygl::Result<Self> {
+                        Ok(Self {
+                            name: #build_with
+                        })
+                    }
+                }
+            });
+        }
+
         // Implement the right shader trait for the given output type
         if is_source {
             parts.push(quote! {
@@ -136,6 +295,13 @@ impl<T: AsOutputFormat> WrappedItem for WrappedShader<T> {
             });
         }
 
+        // Write the block types introduced by this shader
+        parts.extend(
+            self.structs()
+                .iter()
+                .map(|ty| generate_struct(ty, self.struct_names())),
+        );
+
         // Write struct for holding uniform locations
         let struct_name = format_ident!("{}", self.uniform_struct_name());
         let uniform_location_name: Vec<_> = self
@@ -143,11 +309,60 @@ impl<T: AsOutputFormat> WrappedItem for WrappedShader<T> {
             .iter()
             .map(|u| format_ident!("{}", u.location_name()))
             .collect();
+        // Arrays hold the location of each of their innermost arrays
+        let uniform_location_type = self.uniforms().iter().map(|u| {
+            if u.ty
+                .as_ref()
+                .map(|ty| ty.dimensions().is_empty())
+                .unwrap_or(true)
+            {
+                quote! { Option<::tinygl::gl::UniformLocation> }
+            } else {
+                quote! { Vec<Option<::tinygl::gl::UniformLocation>> }
+            }
+        });
+
+        // Last values written by the setters, if caching is enabled
+        let cache_uniforms = self.cache_uniforms();
+        let cache_name: Vec<_> = self
+            .uniforms()
+            .iter()
+            .filter(|_| cache_uniforms)
+            .map(|u| format_ident!("{}_cache", u.name.to_snake_case()))
+            .collect();
+        let cache_type = self
+            .uniforms()
+            .iter()
+            .filter(|_| cache_uniforms)
+            .map(|u| -> syn::Type {
+                let ty = u.ty.as_ref().unwrap();
+                let cell = if ty.dimensions().is_empty() {
+                    "Cell"
+                } else {
+                    "RefCell"
+                };
+
+                syn::parse_str(&format!(
+                    "::std::cell::{}<Option<{}>>",
+                    cell,
+                    cached_value_type(ty)
+                ))
+                .unwrap()
+            });
+        let cache_stats = if cache_uniforms {
+            Some(quote! {
+                cache_stats: ::std::cell::Cell<::tinygl::wrappers::UniformCacheStats>,
+            })
+        } else {
+            None
+        };
 
         parts.push(quote! {
             #[derive(Default)]
             pub struct #struct_name {
-                #(#uniform_location_name: Option<::tinygl::gl::UniformLocation>,)*
+                #(#uniform_location_name: #uniform_location_type,)*
+                #(#cache_name: #cache_type,)*
+                #cache_stats
             }
         });
 
@@ -159,44 +374,152 @@ impl<T: AsOutputFormat> WrappedItem for WrappedShader<T> {
             .iter()
             .map(|uniform| {
                 let name = format_ident!("{}", uniform.location_name());
+                let dimensions = uniform.ty.as_ref().map(|ty| ty.dimensions()).unwrap_or(&[]);
 
                 if is_source {
-                    // Source shader: find uniform locations from variable names
-                    let uniform_name = to_cstr(uniform.name.as_str());
-                    quote! { #name: unsafe { let loc = gl.get_uniform_location(program, #uniform_name); if loc < 0 { None } else { Some(loc) } } }
+                    // Source shader: find uniform locations from variable names. Locations are
+                    // only consecutive within innermost arrays, so each of them is queried.
+                    let locations = inner_array_names(&uniform.name, dimensions).into_iter().map(|uniform_name| {
+                        let uniform_name = to_cstr(&uniform_name);
+                        quote! { unsafe { let loc = gl.get_uniform_location(program, #uniform_name); if loc < 0 { None } else { Some(loc) } } }
+                    });
+
+                    if dimensions.is_empty() {
+                        quote! { #name: #(#locations)* }
+                    } else {
+                        quote! { #name: vec![#(#locations),*] }
+                    }
                 } else {
-                    // Binary shader: assume locations form reflection on SPIR-V
-                    let location = syn::Lit::Int(syn::LitInt::new(&uniform.location.to_string(),
-                                                                  proc_macro2::Span::call_site()));
-                    quote! { #name: Some(#location) }
+                    // Binary shader: assume locations form reflection on SPIR-V, which assigns
+                    // consecutive locations to all the elements of arrays
+                    let inner_len = dimensions.last().copied().unwrap_or(1);
+                    let count = dimensions.iter().product::<u32>() / inner_len;
+                    let locations = (0..count).map(|index| {
+                        syn::LitInt::new(&(uniform.location + index * inner_len).to_string(), proc_macro2::Span::call_site())
+                    });
+
+                    if dimensions.is_empty() {
+                        quote! { #name: #(Some(#locations))* }
+                    } else {
+                        quote! { #name: vec![#(Some(#locations)),*] }
+                    }
+                }
+            })
+            .collect::<Vec<_>>();
+
+        // Source shaders may not specify block bindings, so assign them explicitly
+        let block_bindings = self
+            .blocks()
+            .iter()
+            .filter(|_| is_source)
+            .map(|block| {
+                let block_name = to_cstr(block.name.as_str());
+                let binding = block.binding.unwrap_or(0) as u32;
+
+                match block.kind {
+                    BlockKind::Uniform => quote! {
+                        unsafe {
+                            let index = gl.get_uniform_block_index(program, #block_name);
+                            if index != ::tinygl::gl::INVALID_INDEX {
+                                gl.uniform_block_binding(program, index, #binding);
+                            }
+                        }
+                    },
+                    BlockKind::Storage => quote! {
+                        unsafe {
+                            let index = gl.get_program_resource_index(
+                                program,
+                                ::tinygl::gl::SHADER_STORAGE_BLOCK,
+                                #block_name,
+                            );
+                            if index != ::tinygl::gl::INVALID_INDEX {
+                                gl.shader_storage_block_binding(program, index, #binding);
+                            }
+                        }
+                    },
                 }
             })
             .collect::<Vec<_>>();
 
+        let defaults = if cache_uniforms {
+            Some(quote! { ..Default::default() })
+        } else {
+            None
+        };
+
         methods.push(quote! {
             pub fn new(gl: &::tinygl::Context, program: ::tinygl::gl::Program) -> Self {
+                #(#block_bindings)*
+
                 Self {
-                    #(#uset),*
+                    #(#uset,)*
+                    #defaults
                 }
             }
         });
 
+        if cache_uniforms {
+            methods.push(quote! {
+                /// Number of values uploaded and skipped by the setters
+                pub fn cache_stats(&self) -> ::tinygl::wrappers::UniformCacheStats {
+                    self.cache_stats.get()
+                }
+
+                pub fn reset_cache_stats(&self) {
+                    self.cache_stats.take();
+                }
+
+                /// Forget the cached values, so the next setter calls upload them
+                ///
+                /// This must be called after relinking the program, since linking resets the
+                /// values of its uniforms.
+                pub fn invalidate_cache(&self) {
+                    #(self.#cache_name.take();)*
+                }
+
+                fn record_cache(&self, elided: bool) {
+                    let mut stats = self.cache_stats.get();
+                    stats.record(elided);
+                    self.cache_stats.set(stats);
+                }
+            });
+        }
+
+        // Write block binding methods
+        methods.extend(self.blocks().iter().map(block_methods));
+
         // Write getter/setter methods
         methods.extend(self.uniforms().iter().flat_map(|uniform| {
             let mut res = Vec::new();
-            let ty = uniform.ty.unwrap();
+            let ty = uniform.ty.clone().unwrap();
             let type_name: syn::Type = syn::parse_str(&ty.rust_value_type()).unwrap();
             let sc = uniform.name.to_snake_case();
+            let is_array = !ty.dimensions().is_empty();
+            let count = ty.element_count() as usize;
+            let length_message = format!("wrong number of elements for {}", uniform.name);
+            let index_message = format!("index out of bounds for {}", uniform.name);
 
             if let Some(binding) = uniform.binding {
                 let meth_ident = format_ident!("get_{}_binding", sc);
                 let binding = binding as u32;
 
                 res.push(quote! {
-                    pub fn #meth_ident(&self) -> #type_name {
+                    pub fn #meth_ident(&self) -> u32 {
                         #binding
                     }
                 });
+
+                // Arrays of samplers are bound to consecutive texture units
+                if is_array {
+                    let meth_ident = format_ident!("get_{}_binding_at", sc);
+
+                    res.push(quote! {
+                        pub fn #meth_ident(&self, index: usize) -> u32 {
+                            debug_assert!(index < #count, #index_message);
+                            #binding + index as u32
+                        }
+                    });
+                }
             }
 
             if let Some(format) = uniform.format() {
@@ -209,39 +532,185 @@ impl<T: AsOutputFormat> WrappedItem for WrappedShader<T> {
                 });
             }
 
-            let meth_ident = format_ident!("set_{}", uniform.name.to_snake_case());
             let location = format_ident!("{}", uniform.location_name());
-            let program_uniform = format_ident!("program_uniform{}", ty.uniform_method_name());
-            let mut meth_args = vec![
-                quote! { value: #type_name }
-            ];
+            let cache = if cache_uniforms {
+                Some(format_ident!("{}_cache", sc))
+            } else {
+                None
+            };
+            let extra_args = ty.uniform_method_extra_args_with_ty().into_iter();
+            let extra_values = ty.uniform_method_extra_args_val().into_iter();
+
+            // Call setting a single value, which is the whole uniform or one of its elements
+            let single_call = |ty: &crate::types::ItemOrArrayType| {
+                let program_uniform = format_ident!("program_uniform{}", ty.uniform_method_name());
+                let mut call_args = Vec::new();
+
+                if let Some(count) = ty.uniform_count_arg() {
+                    let count = syn::LitInt::new(&format!("{}", count), proc_macro2::Span::call_site());
+                    call_args.push(quote! { #count });
+                }
 
-            if let Some(extra) = ty.uniform_method_extra_args_with_ty() {
-                meth_args.insert(0, extra);
-            }
+                if let Some(extra) = ty.uniform_method_extra_args_val() {
+                    call_args.push(extra);
+                }
 
-            let mut call_args = Vec::new();
+                call_args.push(ty.uniform_value(&format_ident!("value")));
+
+                quote! { #program_uniform(program, location, #(#call_args),*) }
+            };
+
+            let meth_ident = format_ident!("set_{}", sc);
+
+            if is_array {
+                let program_uniform = format_ident!("program_uniform{}", ty.uniform_method_name());
+                let inner_len = ty.inner_len() as usize;
+                let conversion = array_conversion(&ty, &format_ident!("value"));
+                let chunk_value = ty.uniform_value(&format_ident!("chunk"));
+
+                // Elements of innermost arrays occupy consecutive locations, so they are
+                // uploaded one innermost array at a time
+                // Skip the upload if all the elements are unchanged
+                let cache_check = cache.as_ref().map(|cache| {
+                    let (pattern, guard, stored) = if ty.uniform_method_extra_args().is_empty() {
+                        (
+                            quote! { Some(values) },
+                            quote! { values.as_slice() == value },
+                            quote! { value.to_vec() },
+                        )
+                    } else {
+                        (
+                            quote! { Some((cached_transpose, values)) },
+                            quote! { *cached_transpose == transpose && values.as_slice() == value },
+                            quote! { (transpose, value.to_vec()) },
+                        )
+                    };
+
+                    quote! {
+                        let mut cached = self.#cache.borrow_mut();
+
+                        if matches!(&*cached, #pattern if #guard) {
+                            self.record_cache(true);
+                            return;
+                        }
 
-            if let Some(count) = ty.uniform_count_arg() {
-                let count = syn::LitInt::new(&format!("{}", count), proc_macro2::Span::call_site());
-                call_args.push(quote! { #count });
-            }
+                        *cached = Some(#stored);
+                        self.record_cache(false);
+                    }
+                });
 
-            if let Some(extra) = ty.uniform_method_extra_args_no_ty() {
-                call_args.push(extra);
-            }
+                res.push(quote! {
+                    pub fn #meth_ident(&self, gl: &::tinygl::Context, program: ::tinygl::gl::ProgramName, #(#extra_args,)* value: #type_name) {
+                        debug_assert_eq!(value.len(), #count, #length_message);
+
+                        if self.#location.iter().any(Option::is_some) {
+                            #cache_check
+                            #conversion
+
+                            for (chunk, location) in value.chunks(#inner_len).zip(self.#location.iter()) {
+                                if let Some(location) = location {
+                                    unsafe {
+                                        gl.#program_uniform(
+                                            program,
+                                            *location,
+                                            chunk.len() as i32,
+                                            #(#extra_values,)*
+                                            #chunk_value
+                                        );
+                                    }
+                                }
+                            }
+                        }
+                    }
+                });
+
+                let element = ty.element_type();
+                let element_name: syn::Type = syn::parse_str(&element.rust_value_type()).unwrap();
+                let extra_args = element.uniform_method_extra_args_with_ty().into_iter();
+                let meth_ident = format_ident!("set_{}_at", sc);
+                let call = single_call(&element);
+
+                // Update the cached element, or forget the array if it was not fully set before
+                let cache_check = cache.as_ref().map(|cache| {
+                    let pattern = if ty.uniform_method_extra_args().is_empty() {
+                        quote! { Some(values) }
+                    } else {
+                        quote! { Some((cached_transpose, values)) if *cached_transpose == transpose }
+                    };
+                    let stored = cached_value(&element, &format_ident!("value"));
+
+                    quote! {
+                        let mut cached = self.#cache.borrow_mut();
+
+                        match &mut *cached {
+                            #pattern => {
+                                let element = #stored;
+
+                                if values[index] == element {
+                                    self.record_cache(true);
+                                    return;
+                                }
+
+                                values[index] = element;
+                            }
+                            _ => *cached = None,
+                        }
 
-            call_args.push(ty.uniform_value(&format_ident!("value")));
+                        self.record_cache(false);
+                    }
+                });
 
-            res.push(quote! {
-                pub fn #meth_ident(&self, gl: &::tinygl::Context, program: ::tinygl::gl::ProgramName, #(#meth_args),*) {
-                    if let Some(location) = self.#location {
-                        unsafe {
-                            gl.#program_uniform(program, location, #(#call_args),*);
+                res.push(quote! {
+                    pub fn #meth_ident(&self, gl: &::tinygl::Context, program: ::tinygl::gl::ProgramName, #(#extra_args,)* index: usize, value: #element_name) {
+                        debug_assert!(index < #count, #index_message);
+
+                        if let Some(location) = self.#location[index / #inner_len] {
+                            #cache_check
+                            let location = location + (index % #inner_len) as i32;
+
+                            unsafe {
+                                gl.#call;
+                            }
                         }
                     }
-                }
-            });
+                });
+            } else {
+                let call = single_call(&ty);
+
+                // Skip the upload if the value is unchanged
+                let cache_check = cache.as_ref().map(|cache| {
+                    let stored = cached_value(&ty, &format_ident!("value"));
+                    let stored = if ty.uniform_method_extra_args().is_empty() {
+                        stored
+                    } else {
+                        quote! { (transpose, #stored) }
+                    };
+
+                    quote! {
+                        let cached = Some(#stored);
+
+                        if self.#cache.get() == cached {
+                            self.record_cache(true);
+                            return;
+                        }
+
+                        self.#cache.set(cached);
+                        self.record_cache(false);
+                    }
+                });
+
+                res.push(quote! {
+                    pub fn #meth_ident(&self, gl: &::tinygl::Context, program: ::tinygl::gl::ProgramName, #(#extra_args,)* value: #type_name) {
+                        if let Some(location) = self.#location {
+                            #cache_check
+
+                            unsafe {
+                                gl.#call;
+                            }
+                        }
+                    }
+                });
+            }
 
             res
         }));
@@ -1,9 +1,15 @@
+mod attribute;
+pub use attribute::*;
+
 mod codegen_ext;
 pub use codegen_ext::*;
 
+mod layout;
+pub use layout::*;
+
 use quote::quote;
 
-use crate::types::{AtomType, GenericType, ItemOrArrayType};
+use crate::types::{AtomType, GenericType, ItemOrArrayType, VectorType};
 
 pub trait UniformValueExt {
     fn uniform_value(&self, name: &syn::Ident) -> proc_macro2::TokenStream;
@@ -12,7 +18,16 @@ pub trait UniformValueExt {
 impl UniformValueExt for GenericType {
     fn uniform_value(&self, name: &syn::Ident) -> proc_macro2::TokenStream {
         match self {
+            Self::Atom(AtomType::Bool) => quote! { #name as i32 },
             Self::Atom(_) => quote! { #name },
+            Self::Vector(VectorType {
+                base_type: AtomType::Bool,
+                components,
+            }) => {
+                // Convert to an int vector before uploading
+                let components = (0..*components as usize).map(syn::Index::from);
+                quote! { { let #name = #name.as_ref(); [#(#name[#components] as i32),*] }.as_ptr() }
+            }
             Self::Vector(_) => quote! { #name.as_ref().as_ptr() },
             Self::Matrix(_) => quote! { #name.as_ref().as_ptr() as *const _ },
         }
@@ -23,8 +38,80 @@ impl UniformValueExt for ItemOrArrayType {
     fn uniform_value(&self, name: &syn::Ident) -> proc_macro2::TokenStream {
         match self {
             Self::Item(inner) => inner.uniform_value(name),
-            Self::Array(_, _) => quote! { #name.as_ref().as_ptr() },
-            Self::Image { .. } => GenericType::Atom(AtomType::UInt).uniform_value(name),
+            Self::Array(..) | Self::ImageArray { .. } => quote! { #name.as_ptr() as *const _ },
+            Self::Image { .. } => quote! { #name as i32 },
+        }
+    }
+}
+
+/// Statement converting a slice of array elements to the values expected by OpenGL, if needed
+///
+/// # Parameters
+///
+/// * `ty`: array type
+/// * `name`: name of the slice, shadowed by the converted values
+pub fn array_conversion(
+    ty: &ItemOrArrayType,
+    name: &syn::Ident,
+) -> Option<proc_macro2::TokenStream> {
+    match ty {
+        // Booleans and texture units are uploaded as integers
+        ItemOrArrayType::Array(GenericType::Atom(AtomType::Bool), _)
+        | ItemOrArrayType::ImageArray { .. } => Some(quote! {
+            let #name: Vec<i32> = #name.iter().map(|value| *value as i32).collect();
+        }),
+        ItemOrArrayType::Array(
+            GenericType::Vector(VectorType {
+                base_type: AtomType::Bool,
+                components,
+            }),
+            _,
+        ) => {
+            let len = *components as usize;
+            let components = (0..len).map(syn::Index::from);
+            Some(quote! {
+                let #name: Vec<[i32; #len]> = #name
+                    .iter()
+                    .map(|value| [#(value[#components] as i32),*])
+                    .collect();
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Rust type of the values kept by uniform caches
+///
+/// # Parameters
+///
+/// * `ty`: type of the uniform
+pub fn cached_value_type(ty: &ItemOrArrayType) -> String {
+    let value = match ty {
+        ItemOrArrayType::Item(item) => array_element_type(item),
+        ItemOrArrayType::Array(item, _) => format!("Vec<{}>", array_element_type(item)),
+        ItemOrArrayType::Image { .. } => "u32".to_owned(),
+        ItemOrArrayType::ImageArray { .. } => "Vec<u32>".to_owned(),
+    };
+
+    // The transpose flag is part of the uploaded value
+    if ty.uniform_method_extra_args().is_empty() {
+        value
+    } else {
+        format!("(bool, {})", value)
+    }
+}
+
+/// Expression for the value kept by uniform caches, for a single item or image
+///
+/// # Parameters
+///
+/// * `ty`: type of the value
+/// * `name`: name of the setter argument
+pub fn cached_value(ty: &ItemOrArrayType, name: &syn::Ident) -> proc_macro2::TokenStream {
+    match ty {
+        ItemOrArrayType::Item(GenericType::Atom(_)) | ItemOrArrayType::Image { .. } => {
+            quote! { #name }
         }
+        _ => quote! { *#name.as_ref() },
     }
 }
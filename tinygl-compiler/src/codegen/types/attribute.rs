@@ -0,0 +1,75 @@
+use quote::{format_ident, quote};
+
+use super::CodegenExt;
+use crate::reflect::{FoundAttribute, FoundOutput};
+use crate::types::{AtomType, GenericType};
+
+/// Rust type used to source a vertex attribute from a buffer
+pub fn attribute_data_type(attribute: &FoundAttribute) -> proc_macro2::TokenStream {
+    let ty: syn::Type = syn::parse_str(attribute.ty.rust_primitive_type()).unwrap();
+
+    match attribute.ty {
+        GenericType::Atom(_) => quote! { #ty },
+        GenericType::Vector(vector_type) => {
+            let components = proc_macro2::Literal::u32_unsuffixed(vector_type.components);
+            quote! { [#ty; #components] }
+        }
+        GenericType::Matrix(matrix_type) => {
+            // Matrices are column-major
+            let rows = proc_macro2::Literal::u32_unsuffixed(matrix_type.rows);
+            let columns = proc_macro2::Literal::u32_unsuffixed(matrix_type.columns);
+            quote! { [[#ty; #rows]; #columns] }
+        }
+    }
+}
+
+/// Runtime description of a vertex attribute
+pub fn attribute_description(attribute: &FoundAttribute) -> proc_macro2::TokenStream {
+    let name = &attribute.name;
+    let location = attribute.location;
+    let components = attribute.components();
+    let locations = attribute.locations();
+    let ty = format_ident!(
+        "{}",
+        match attribute.ty.base_type() {
+            AtomType::Float => "Float",
+            AtomType::Double => "Double",
+            AtomType::Int | AtomType::Bool => "Int",
+            AtomType::UInt => "UInt",
+        }
+    );
+
+    quote! {
+        ::tinygl::wrappers::VertexAttribute {
+            name: #name,
+            location: #location,
+            ty: ::tinygl::wrappers::VertexAttributeType::#ty,
+            components: #components,
+            locations: #locations,
+        }
+    }
+}
+
+/// Runtime description of a fragment output
+pub fn output_description(output: &FoundOutput) -> proc_macro2::TokenStream {
+    let name = &output.name;
+    let location = output.location;
+    let components = output.components();
+    let ty = format_ident!(
+        "{}",
+        match output.ty.base_type() {
+            AtomType::Float | AtomType::Double => "Float",
+            AtomType::Int | AtomType::Bool => "Int",
+            AtomType::UInt => "UInt",
+        }
+    );
+
+    quote! {
+        ::tinygl::wrappers::FragmentOutput {
+            name: #name,
+            location: #location,
+            ty: ::tinygl::wrappers::AttachmentType::#ty,
+            components: #components,
+        }
+    }
+}
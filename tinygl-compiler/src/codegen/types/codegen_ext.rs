@@ -3,8 +3,6 @@ use quote::{format_ident, quote};
 use crate::types::{AtomType, GenericType, ItemOrArrayType, MatrixType, VectorType};
 
 // TODO: Use a formatter
-// TODO: bvec mapping is broken
-// TODO: dvec mapping is broken
 
 pub struct ExtraArg {
     pub name: &'static str,
@@ -15,7 +13,6 @@ pub struct ExtraArg {
 pub trait CodegenExt {
     fn glsl_base_type(&self) -> &'static str;
     fn glsl_vec_name(&self) -> String;
-    fn glsl_mat_name(&self) -> String;
     fn rust_value_type(&self) -> String;
     fn rust_primitive_type(&self) -> &'static str;
     fn uniform_method_name(&self) -> String;
@@ -52,6 +49,22 @@ pub trait CodegenExt {
             #(#name),*
         })
     }
+
+    fn uniform_method_extra_args_val(&self) -> Option<proc_macro2::TokenStream> {
+        let args = self.uniform_method_extra_args();
+
+        if args.is_empty() {
+            return None;
+        }
+
+        let val = args
+            .iter()
+            .map(|e| -> syn::Expr { syn::parse_str(e.val).unwrap() });
+
+        Some(quote! {
+            #(#val),*
+        })
+    }
 }
 
 impl CodegenExt for AtomType {
@@ -63,10 +76,6 @@ impl CodegenExt for AtomType {
         AtomType::glsl_vec_name(self)
     }
 
-    fn glsl_mat_name(&self) -> String {
-        AtomType::glsl_mat_name(self)
-    }
-
     fn rust_value_type(&self) -> String {
         self.rust_primitive_type().into()
     }
@@ -89,7 +98,7 @@ impl CodegenExt for AtomType {
                 Self::Float => "f",
                 Self::Double => "d",
                 Self::UInt => "ui",
-                // TODO: Check how to handle bool
+                // Booleans are uploaded as integers
                 Self::Bool => "i",
             }
         )
@@ -113,10 +122,6 @@ impl CodegenExt for VectorType {
         VectorType::glsl_vec_name(self)
     }
 
-    fn glsl_mat_name(&self) -> String {
-        panic!("cannot format a vector as a matrix")
-    }
-
     fn rust_value_type(&self) -> String {
         format!(
             "impl ::std::convert::AsRef<[{}; {}]>",
@@ -138,8 +143,8 @@ impl CodegenExt for VectorType {
                 AtomType::Float => "f",
                 AtomType::Double => "d",
                 AtomType::UInt => "ui",
-                // TODO: Check how to handle bool
-                AtomType::Bool => "iv",
+                // Booleans are uploaded as integers
+                AtomType::Bool => "i",
             }
         )
     }
@@ -162,15 +167,13 @@ impl CodegenExt for MatrixType {
         panic!("cannot format a matrix as a vector")
     }
 
-    fn glsl_mat_name(&self) -> String {
-        MatrixType::glsl_mat_name(self)
-    }
-
     fn rust_value_type(&self) -> String {
+        // Matrices are column-major
         format!(
-            "impl ::std::convert::AsRef<[[{ty}; {n}]; {n}]>",
+            "impl ::std::convert::AsRef<[[{ty}; {rows}]; {columns}]>",
             ty = self.base_type.rust_value_type(),
-            n = self.n,
+            rows = self.rows,
+            columns = self.columns,
         )
     }
 
@@ -181,7 +184,11 @@ impl CodegenExt for MatrixType {
     fn uniform_method_name(&self) -> String {
         format!(
             "_matrix{}{}v",
-            self.n,
+            if self.is_square() {
+                self.columns.to_string()
+            } else {
+                format!("{}x{}", self.columns, self.rows)
+            },
             match self.base_type {
                 AtomType::Float => "f",
                 AtomType::Double => "d",
@@ -221,14 +228,6 @@ impl CodegenExt for GenericType {
         }
     }
 
-    fn glsl_mat_name(&self) -> String {
-        match self {
-            Self::Atom(atom) => atom.glsl_mat_name(),
-            Self::Vector(vector) => vector.glsl_mat_name(),
-            Self::Matrix(matrix) => matrix.glsl_mat_name(),
-        }
-    }
-
     fn rust_value_type(&self) -> String {
         match self {
             Self::Atom(atom) => atom.rust_value_type(),
@@ -270,67 +269,90 @@ impl CodegenExt for GenericType {
     }
 }
 
+/// Rust type of `item` when stored in an array
+pub fn array_element_type(item: &GenericType) -> String {
+    match item {
+        GenericType::Atom(atom) => atom.rust_primitive_type().to_owned(),
+        GenericType::Vector(vector) => format!(
+            "[{}; {}]",
+            vector.base_type.rust_primitive_type(),
+            vector.components
+        ),
+        GenericType::Matrix(matrix) => format!(
+            "[[{}; {}]; {}]",
+            matrix.base_type.rust_primitive_type(),
+            matrix.rows,
+            matrix.columns
+        ),
+    }
+}
+
 impl CodegenExt for ItemOrArrayType {
     fn glsl_base_type(&self) -> &'static str {
         match self {
-            Self::Item(item) => item.glsl_base_type(),
-            Self::Array(item, _size) => item.glsl_base_type(),
-            Self::Image { .. } => "image",
+            Self::Item(item) | Self::Array(item, _) => item.glsl_base_type(),
+            Self::Image { .. } | Self::ImageArray { .. } => "image",
         }
     }
 
     fn glsl_vec_name(&self) -> String {
         match self {
-            Self::Item(item) => item.glsl_vec_name(),
-            Self::Array(item, _size) => item.glsl_vec_name(),
-            Self::Image { .. } => "image".to_owned(),
-        }
-    }
-
-    fn glsl_mat_name(&self) -> String {
-        match self {
-            Self::Item(item) => item.glsl_mat_name(),
-            Self::Array(item, _size) => item.glsl_mat_name(),
-            Self::Image { .. } => "image".to_owned(),
+            Self::Item(item) | Self::Array(item, _) => item.glsl_vec_name(),
+            Self::Image { .. } | Self::ImageArray { .. } => "image".to_owned(),
         }
     }
 
     fn rust_value_type(&self) -> String {
         match self {
             Self::Item(item) => item.rust_value_type(),
-            Self::Array(item, size) => format!("&[{}; {}]", item.glsl_mat_name(), size),
+            Self::Array(item, _) => format!("&[{}]", array_element_type(item)),
             Self::Image { .. } => GenericType::Atom(AtomType::UInt).rust_value_type(),
+            Self::ImageArray { .. } => {
+                format!("&[{}]", GenericType::Atom(AtomType::UInt).rust_value_type())
+            }
         }
     }
 
     fn rust_primitive_type(&self) -> &'static str {
         match self {
-            Self::Item(item) => item.rust_primitive_type(),
-            Self::Array(item, _size) => item.rust_primitive_type(),
-            Self::Image { .. } => GenericType::Atom(AtomType::UInt).rust_primitive_type(),
+            Self::Item(item) | Self::Array(item, _) => item.rust_primitive_type(),
+            Self::Image { .. } | Self::ImageArray { .. } => {
+                GenericType::Atom(AtomType::UInt).rust_primitive_type()
+            }
         }
     }
 
     fn uniform_method_name(&self) -> String {
         match self {
             Self::Item(item) => item.uniform_method_name(),
-            Self::Array(item, _size) => item.uniform_method_name(),
-            Self::Image { .. } => GenericType::Atom(AtomType::UInt).uniform_method_name(),
+            // Arrays of scalars need the vector variant of the method
+            Self::Array(GenericType::Atom(atom), _) => format!("{}v", atom.uniform_method_name()),
+            Self::Array(item, _) => item.uniform_method_name(),
+            // Samplers and images are set using their texture unit as an int
+            Self::Image { .. } => GenericType::Atom(AtomType::Int).uniform_method_name(),
+            Self::ImageArray { .. } => {
+                format!(
+                    "{}v",
+                    GenericType::Atom(AtomType::Int).uniform_method_name()
+                )
+            }
         }
     }
 
     fn uniform_method_extra_args(&self) -> &[ExtraArg] {
         match self {
-            Self::Item(item) => item.uniform_method_extra_args(),
-            Self::Array(item, _size) => item.uniform_method_extra_args(),
-            Self::Image { .. } => GenericType::Atom(AtomType::UInt).uniform_method_extra_args(),
+            Self::Item(item) | Self::Array(item, _) => item.uniform_method_extra_args(),
+            Self::Image { .. } | Self::ImageArray { .. } => {
+                GenericType::Atom(AtomType::UInt).uniform_method_extra_args()
+            }
         }
     }
 
     fn uniform_count_arg(&self) -> Option<usize> {
         match self {
             Self::Item(item) => item.uniform_count_arg(),
-            Self::Array(_, size) => Some(*size as usize),
+            // Arrays are set one innermost array at a time
+            Self::Array(..) | Self::ImageArray { .. } => Some(self.inner_len() as usize),
             Self::Image { .. } => GenericType::Atom(AtomType::UInt).uniform_count_arg(),
         }
     }
@@ -0,0 +1,238 @@
+use std::collections::HashMap;
+
+use heck::SnakeCase;
+use quote::{format_ident, quote};
+
+use crate::reflect::FoundBlock;
+use crate::types::{AtomType, GenericType, LayoutType, StructType, VectorType};
+
+pub trait LayoutCodegenExt {
+    /// Rust type for a member of this type in a `#[repr(C)]` struct
+    ///
+    /// # Parameters
+    ///
+    /// * `matrix_stride`: stride between matrix columns (or rows), if any
+    /// * `row_major`: true if matrices are stored row by row
+    /// * `struct_names`: Rust names of the nested struct types
+    fn rust_layout_type(
+        &self,
+        matrix_stride: Option<u32>,
+        row_major: bool,
+        struct_names: &HashMap<StructType, String>,
+    ) -> proc_macro2::TokenStream;
+}
+
+fn layout_primitive_type(atom_type: AtomType) -> proc_macro2::TokenStream {
+    match atom_type {
+        AtomType::Int => quote! { i32 },
+        AtomType::Float => quote! { f32 },
+        AtomType::Double => quote! { f64 },
+        // Booleans in blocks are 32-bit values
+        AtomType::UInt | AtomType::Bool => quote! { u32 },
+    }
+}
+
+fn lit(n: u32) -> proc_macro2::Literal {
+    proc_macro2::Literal::u32_unsuffixed(n)
+}
+
+/// Rust type for an array element, padded to the array stride
+fn array_element_type(
+    element: &LayoutType,
+    stride: u32,
+    matrix_stride: Option<u32>,
+    row_major: bool,
+    struct_names: &HashMap<StructType, String>,
+) -> proc_macro2::TokenStream {
+    match element {
+        LayoutType::Item(GenericType::Atom(atom_type))
+        | LayoutType::Item(GenericType::Vector(VectorType {
+            base_type: atom_type,
+            ..
+        })) if element.size(matrix_stride, row_major) != stride => {
+            // Scalars and vectors are padded to the array stride
+            let ty = layout_primitive_type(*atom_type);
+            let components = lit(stride / atom_type.size());
+            quote! { [#ty; #components] }
+        }
+        element => element.rust_layout_type(matrix_stride, row_major, struct_names),
+    }
+}
+
+impl LayoutCodegenExt for LayoutType {
+    fn rust_layout_type(
+        &self,
+        matrix_stride: Option<u32>,
+        row_major: bool,
+        struct_names: &HashMap<StructType, String>,
+    ) -> proc_macro2::TokenStream {
+        match self {
+            Self::Item(GenericType::Atom(atom_type)) => layout_primitive_type(*atom_type),
+            Self::Item(GenericType::Vector(vector_type)) => {
+                let ty = layout_primitive_type(vector_type.base_type);
+                let components = lit(vector_type.components);
+                quote! { [#ty; #components] }
+            }
+            Self::Item(GenericType::Matrix(matrix_type)) => {
+                // Columns (or rows) are padded to the matrix stride
+                let ty = layout_primitive_type(matrix_type.base_type);
+                let size = matrix_type.base_type.size();
+                let (vectors, components) = matrix_type.vectors(row_major);
+                let components = lit(matrix_stride.unwrap_or(size * components) / size);
+                let vectors = lit(vectors);
+                quote! { [[#ty; #components]; #vectors] }
+            }
+            Self::Array {
+                element,
+                len,
+                stride,
+            } => {
+                let ty =
+                    array_element_type(element, *stride, matrix_stride, row_major, struct_names);
+                let len = lit(*len);
+                quote! { [#ty; #len] }
+            }
+            // Runtime arrays are represented by their element type
+            Self::RuntimeArray { element, stride } => {
+                array_element_type(element, *stride, matrix_stride, row_major, struct_names)
+            }
+            Self::Struct(struct_type) => {
+                let name = format_ident!("{}", struct_names[struct_type]);
+                quote! { #name }
+            }
+        }
+    }
+}
+
+/// Generate the `#[repr(C)]` definition of an explicitly laid out struct
+///
+/// # Parameters
+///
+/// * `ty`: struct type to generate
+/// * `struct_names`: Rust names of the struct types
+pub fn generate_struct(
+    ty: &StructType,
+    struct_names: &HashMap<StructType, String>,
+) -> proc_macro2::TokenStream {
+    let name = format_ident!("{}", struct_names[ty]);
+
+    let mut fields = Vec::new();
+    let mut offset = 0;
+    let mut padding = 0u32;
+
+    let mut pad = |size: u32| {
+        let ident = format_ident!("_pad{}", padding);
+        let size = lit(size);
+        padding += 1;
+        quote! { #ident: [u8; #size] }
+    };
+
+    let mut runtime_array = None;
+
+    for member in &ty.members {
+        if let LayoutType::RuntimeArray { stride, .. } = &member.ty {
+            // Runtime arrays are not part of the struct itself
+            runtime_array = Some((member, *stride));
+            break;
+        }
+
+        if member.offset > offset {
+            fields.push(pad(member.offset - offset));
+        }
+
+        let ident = format_ident!("{}", member.name.to_snake_case());
+        let member_ty =
+            member
+                .ty
+                .rust_layout_type(member.matrix_stride, member.row_major, struct_names);
+        fields.push(quote! { pub #ident: #member_ty });
+
+        offset = member.offset + member.size();
+    }
+
+    if ty.size > offset {
+        fields.push(pad(ty.size - offset));
+    }
+
+    let size = proc_macro2::Literal::usize_unsuffixed(ty.size as usize);
+
+    let runtime_array = runtime_array.map(|(member, stride)| {
+        let element_name = format_ident!("{}Element", name);
+        let element_ty =
+            member
+                .ty
+                .rust_layout_type(member.matrix_stride, member.row_major, struct_names);
+        let offset = proc_macro2::Literal::usize_unsuffixed(member.offset as usize);
+        let stride = proc_macro2::Literal::usize_unsuffixed(stride as usize);
+
+        quote! {
+            pub type #element_name = #element_ty;
+
+            impl #name {
+                pub const ELEMENTS_OFFSET: usize = #offset;
+                pub const ELEMENTS_STRIDE: usize = #stride;
+
+                pub fn size_for(count: usize) -> usize {
+                    Self::ELEMENTS_OFFSET + count * Self::ELEMENTS_STRIDE
+                }
+            }
+
+            const _: [(); #stride] = [(); ::std::mem::size_of::<#element_name>()];
+        }
+    });
+
+    quote! {
+        #[repr(C)]
+        #[derive(Debug, Clone, Copy)]
+        pub struct #name {
+            #(#fields),*
+        }
+
+        impl #name {
+            pub fn as_bytes(&self) -> &[u8] {
+                unsafe {
+                    ::std::slice::from_raw_parts(
+                        self as *const Self as *const u8,
+                        ::std::mem::size_of::<Self>(),
+                    )
+                }
+            }
+        }
+
+        impl Default for #name {
+            fn default() -> Self {
+                // All fields are plain numeric types
+                unsafe { ::std::mem::zeroed() }
+            }
+        }
+
+        // Padding is explicit, so the struct can be stored in buffers
+        unsafe impl ::tinygl::wrappers::Pod for #name {}
+
+        // Check that the Rust layout matches the shader layout
+        const _: [(); #size] = [(); ::std::mem::size_of::<#name>()];
+
+        #runtime_array
+    }
+}
+
+/// Generate the binding getter and bind method for a block
+pub fn block_methods(block: &FoundBlock) -> proc_macro2::TokenStream {
+    let sc = block.name.to_snake_case();
+    let bind_ident = format_ident!("bind_{}", sc);
+    let binding_ident = format_ident!("get_{}_binding", sc);
+    let binding = block.binding.unwrap_or(0) as u32;
+    let target = format_ident!("{}", block.kind.target_constant_name());
+
+    quote! {
+        pub fn #binding_ident(&self) -> u32 {
+            #binding
+        }
+
+        pub fn #bind_ident<B: ::tinygl::wrappers::Pod>(&self, gl: &::tinygl::Context, buffer: &::tinygl::wrappers::Buffer<B>) {
+            unsafe {
+                buffer.bind_base(gl, ::tinygl::gl::#target, #binding);
+            }
+        }
+    }
+}
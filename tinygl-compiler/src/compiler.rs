@@ -1,8 +1,30 @@
 use crate::{model::ShaderObject, reflect::ReflectedObject, Error, Result};
 
+mod compile_options;
+pub use compile_options::*;
+
+mod diagnostic;
+pub use diagnostic::*;
+
+mod include_resolver;
+pub use include_resolver::*;
+
+#[cfg(feature = "cache")]
+mod shader_cache;
+#[cfg(feature = "cache")]
+pub use shader_cache::ShaderCache;
+
+#[cfg(feature = "spirv")]
+mod permutations;
+#[cfg(feature = "spirv")]
+pub use permutations::*;
+
 #[cfg(feature = "shaderc")]
 mod shaderc_compiler;
 
+mod struct_registry;
+pub use struct_registry::*;
+
 mod target_type;
 pub use target_type::{GlslVersion, TargetType};
 
@@ -22,6 +44,9 @@ pub struct Compiler {
     pub(crate) skip_cargo: bool,
     output_type: TargetType,
     include_callback: Option<std::rc::Rc<std::cell::RefCell<IncludeCallback>>>,
+    include_resolver: std::rc::Rc<IncludeResolver>,
+    structs: StructRegistry,
+    options: CompileOptions,
 }
 
 impl Compiler {
@@ -78,9 +103,40 @@ impl Compiler {
             output_type,
             include_callback: include_callback
                 .map(|cb| std::rc::Rc::new(std::cell::RefCell::new(cb))),
+            include_resolver: std::rc::Rc::new(IncludeResolver::new()),
+            structs: StructRegistry::new(),
+            options: CompileOptions::default(),
         })
     }
 
+    /// Set the default options for compiling shaders
+    ///
+    /// # Parameters
+    ///
+    /// * `options`: options to use for shader objects which do not override them
+    pub fn with_options(mut self, options: CompileOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Set the resolver for `#include` directives
+    ///
+    /// # Parameters
+    ///
+    /// * `resolver`: resolver providing the search directories and file systems for includes
+    pub fn with_include_resolver(mut self, resolver: IncludeResolver) -> Self {
+        self.include_resolver = std::rc::Rc::new(resolver);
+        self
+    }
+
+    pub fn options(&self) -> &CompileOptions {
+        &self.options
+    }
+
+    pub fn options_mut(&mut self) -> &mut CompileOptions {
+        &mut self.options
+    }
+
     #[cfg(feature = "shaderc")]
     pub fn with_shaderc(self) -> shaderc_compiler::CompilerWithShaderc {
         shaderc_compiler::CompilerWithShaderc::new(self)
@@ -95,7 +151,14 @@ impl Compiler {
         shader: ReflectedObject<ShaderObject<T>>,
         prefer_spirv: bool,
     ) -> Result<WrappedShader<T>> {
-        Ok(WrappedShader::new(shader, prefer_spirv))
+        let options = shader.options().unwrap_or(&self.options).clone();
+
+        Ok(WrappedShader::new(
+            shader,
+            prefer_spirv,
+            &options,
+            &mut self.structs,
+        ))
     }
 
     pub fn wrap_program<'s>(
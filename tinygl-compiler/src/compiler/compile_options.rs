@@ -0,0 +1,184 @@
+use super::GlslVersion;
+
+/// Profile of a forced desktop GLSL version
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum GlslProfile {
+    Core,
+    Compatibility,
+}
+
+/// Optimization level of the generated SPIR-V
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum OptimizationLevel {
+    /// No optimization
+    Zero,
+    /// Optimize for binary size
+    Size,
+    /// Optimize for performance
+    Performance,
+}
+
+/// Options for compiling and preprocessing shaders
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct CompileOptions {
+    macros: Vec<(String, Option<String>)>,
+    warnings_as_errors: bool,
+    forced_version: Option<(GlslVersion, GlslProfile)>,
+    auto_bind_uniforms: bool,
+    auto_map_locations: bool,
+    debug_info: bool,
+    optimization_level: OptimizationLevel,
+    strip_debug_info: bool,
+    cache_uniforms: bool,
+}
+
+impl Default for CompileOptions {
+    fn default() -> Self {
+        Self {
+            macros: vec![(
+                "TINYGL".to_owned(),
+                Some(env!("CARGO_PKG_VERSION_MAJOR").to_owned()),
+            )],
+            warnings_as_errors: false,
+            forced_version: None,
+            auto_bind_uniforms: false,
+            auto_map_locations: false,
+            debug_info: false,
+            optimization_level: OptimizationLevel::Zero,
+            strip_debug_info: false,
+            cache_uniforms: false,
+        }
+    }
+}
+
+impl CompileOptions {
+    /// Create the default options, which only define the `TINYGL` macro
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Define a preprocessor macro, replacing any previous definition
+    ///
+    /// # Parameters
+    ///
+    /// * `name`: name of the macro
+    /// * `value`: value of the macro, if any
+    pub fn define(mut self, name: &str, value: Option<&str>) -> Self {
+        self.macros.retain(|(macro_name, _)| macro_name != name);
+        self.macros
+            .push((name.to_owned(), value.map(|value| value.to_owned())));
+        self
+    }
+
+    /// Remove a previously defined preprocessor macro
+    ///
+    /// # Parameters
+    ///
+    /// * `name`: name of the macro
+    pub fn undefine(mut self, name: &str) -> Self {
+        self.macros.retain(|(macro_name, _)| macro_name != name);
+        self
+    }
+
+    /// Treat compilation warnings as errors
+    pub fn warnings_as_errors(mut self, enabled: bool) -> Self {
+        self.warnings_as_errors = enabled;
+        self
+    }
+
+    /// Compile sources as the given GLSL version, ignoring their `#version` directive
+    ///
+    /// # Parameters
+    ///
+    /// * `version`: GLSL version to compile as
+    /// * `profile`: profile for desktop versions, ignored for ES versions
+    pub fn force_version(mut self, version: GlslVersion, profile: GlslProfile) -> Self {
+        self.forced_version = Some((version, profile));
+        self
+    }
+
+    /// Automatically assign bindings to uniforms without an explicit one
+    pub fn auto_bind_uniforms(mut self, enabled: bool) -> Self {
+        self.auto_bind_uniforms = enabled;
+        self
+    }
+
+    /// Automatically assign locations to inputs and outputs without an explicit one
+    pub fn auto_map_locations(mut self, enabled: bool) -> Self {
+        self.auto_map_locations = enabled;
+        self
+    }
+
+    /// Include debug information in the generated SPIR-V
+    pub fn debug_info(mut self, enabled: bool) -> Self {
+        self.debug_info = enabled;
+        self
+    }
+
+    /// Optimize the generated SPIR-V
+    ///
+    /// Debug names are kept when optimizing, since reflection relies on them. Use
+    /// [`strip_debug_info`](Self::strip_debug_info) to remove them from the embedded binary.
+    ///
+    /// # Parameters
+    ///
+    /// * `level`: optimization level
+    pub fn optimize(mut self, level: OptimizationLevel) -> Self {
+        self.optimization_level = level;
+        self
+    }
+
+    /// Remove debug instructions from the embedded SPIR-V, after reflection
+    pub fn strip_debug_info(mut self, enabled: bool) -> Self {
+        self.strip_debug_info = enabled;
+        self
+    }
+
+    /// Generate uniform setters which skip uploading values that did not change
+    ///
+    /// Cached values are reset when the program is built, and should be invalidated if the
+    /// program is relinked or its uniforms are set by other means.
+    pub fn cache_uniforms(mut self, enabled: bool) -> Self {
+        self.cache_uniforms = enabled;
+        self
+    }
+
+    /// Defined macros, in definition order
+    pub fn macros(&self) -> impl Iterator<Item = (&str, Option<&str>)> {
+        self.macros
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_deref()))
+    }
+
+    pub fn is_warnings_as_errors(&self) -> bool {
+        self.warnings_as_errors
+    }
+
+    pub fn forced_version(&self) -> Option<(GlslVersion, GlslProfile)> {
+        self.forced_version
+    }
+
+    pub fn is_auto_bind_uniforms(&self) -> bool {
+        self.auto_bind_uniforms
+    }
+
+    pub fn is_auto_map_locations(&self) -> bool {
+        self.auto_map_locations
+    }
+
+    pub fn is_debug_info(&self) -> bool {
+        self.debug_info
+    }
+
+    pub fn optimization_level(&self) -> OptimizationLevel {
+        self.optimization_level
+    }
+
+    pub fn is_strip_debug_info(&self) -> bool {
+        self.strip_debug_info
+    }
+
+    pub fn is_cache_uniforms(&self) -> bool {
+        self.cache_uniforms
+    }
+}
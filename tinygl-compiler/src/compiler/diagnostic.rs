@@ -0,0 +1,255 @@
+use std::fmt;
+
+/// Severity of a compiler diagnostic
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Error => write!(f, "error"),
+            Self::Warning => write!(f, "warning"),
+        }
+    }
+}
+
+/// Message reported by the shader compiler
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Diagnostic {
+    pub severity: Severity,
+    /// File the message refers to, as resolved through include directives
+    pub file: Option<String>,
+    /// Line number, starting at 1
+    pub line: Option<usize>,
+    /// Column number, starting at 1
+    pub column: Option<usize>,
+    pub message: String,
+    /// Source line the message refers to, if the source is known
+    pub source_line: Option<String>,
+}
+
+impl Diagnostic {
+    /// Parse the messages output by the compiler
+    ///
+    /// Messages are expected in the `file:line: severity: message` format. Lines which do not
+    /// start a new message are appended to the previous one, and summary lines are ignored.
+    ///
+    /// # Parameters
+    ///
+    /// * `output`: compiler output
+    pub fn parse(output: &str) -> Vec<Self> {
+        let mut diagnostics: Vec<Self> = Vec::new();
+
+        for line in output.lines() {
+            if line.trim().is_empty() || is_summary(line) {
+                continue;
+            }
+
+            if let Some(diagnostic) = Self::parse_line(line) {
+                diagnostics.push(diagnostic);
+            } else if let Some(last) = diagnostics.last_mut() {
+                last.message.push('\n');
+                last.message.push_str(line);
+            } else {
+                diagnostics.push(Self {
+                    severity: Severity::Error,
+                    file: None,
+                    line: None,
+                    column: None,
+                    message: line.to_owned(),
+                    source_line: None,
+                });
+            }
+        }
+
+        diagnostics
+    }
+
+    /// Create a warning which does not refer to a source location
+    ///
+    /// # Parameters
+    ///
+    /// * `message`: warning message
+    pub fn warning(message: impl Into<String>) -> Self {
+        Self {
+            severity: Severity::Warning,
+            file: None,
+            line: None,
+            column: None,
+            message: message.into(),
+            source_line: None,
+        }
+    }
+
+    fn parse_line(line: &str) -> Option<Self> {
+        for (tag, severity) in &[
+            ("fatal error: ", Severity::Error),
+            ("error: ", Severity::Error),
+            ("warning: ", Severity::Warning),
+        ] {
+            let (location, message) = if let Some(message) = line.strip_prefix(tag) {
+                ("", message)
+            } else if let Some(index) = line.find(&format!(": {}", tag)) {
+                (&line[..index], &line[index + tag.len() + 2..])
+            } else {
+                continue;
+            };
+
+            let (file, line, column) = parse_location(location);
+
+            return Some(Self {
+                severity: *severity,
+                file,
+                line,
+                column,
+                message: message.to_owned(),
+                source_line: None,
+            });
+        }
+
+        None
+    }
+
+    /// Attach the source line this diagnostic refers to
+    ///
+    /// # Parameters
+    ///
+    /// * `source`: contents of the file this diagnostic refers to
+    pub fn with_source(mut self, source: &str) -> Self {
+        if let Some(line) = self.line {
+            self.source_line = source
+                .lines()
+                .nth(line.saturating_sub(1))
+                .map(|line| line.to_owned());
+        }
+
+        self
+    }
+
+    pub fn is_error(&self) -> bool {
+        self.severity == Severity::Error
+    }
+
+    /// Render this diagnostic in the style of rustc, including the offending source line
+    pub fn render(&self) -> String {
+        let mut result = format!("{}: {}", self.severity, self.message);
+
+        let location = match (&self.file, self.line, self.column) {
+            (Some(file), Some(line), Some(column)) => format!("{}:{}:{}", file, line, column),
+            (Some(file), Some(line), None) => format!("{}:{}", file, line),
+            (Some(file), None, _) => file.clone(),
+            (None, Some(line), _) => format!("<unknown>:{}", line),
+            (None, None, _) => return result,
+        };
+
+        let gutter = " ".repeat(self.line.map(|line| line.to_string().len()).unwrap_or(0));
+        result.push_str(&format!("\n{}--> {}", gutter, location));
+
+        if let (Some(line), Some(source_line)) = (self.line, &self.source_line) {
+            let (start, width) = match self.column {
+                Some(column) => (column.saturating_sub(1), 1),
+                None => {
+                    let trimmed = source_line.trim_start();
+                    (
+                        source_line.len() - trimmed.len(),
+                        trimmed.trim_end().len().max(1),
+                    )
+                }
+            };
+
+            // Keep tabs so the marker stays aligned with the source line
+            let padding: String = source_line
+                .chars()
+                .take(start)
+                .map(|c| if c == '\t' { '\t' } else { ' ' })
+                .collect();
+
+            result.push_str(&format!(
+                "\n{gutter} |\n{line} | {source}\n{gutter} | {padding}{marker}",
+                gutter = gutter,
+                line = line,
+                source = source_line,
+                padding = padding,
+                marker = "^".repeat(width),
+            ));
+        }
+
+        result
+    }
+
+    /// Print this diagnostic as `cargo:warning` lines, for use in build scripts
+    pub fn print_cargo_warning(&self) {
+        for line in self.render().lines() {
+            println!("cargo:warning={}", line);
+        }
+    }
+
+    /// Render a list of diagnostics, separated by blank lines
+    pub fn render_all(diagnostics: &[Self]) -> String {
+        diagnostics
+            .iter()
+            .map(Self::render)
+            .collect::<Vec<_>>()
+            .join("\n\n")
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.render())
+    }
+}
+
+/// true if `line` is the `N errors generated.` summary output after the messages
+fn is_summary(line: &str) -> bool {
+    let mut words = line.split_whitespace();
+
+    words
+        .next()
+        .map(|count| count.parse::<usize>().is_ok())
+        .unwrap_or(false)
+        && matches!(
+            words.next(),
+            Some("error") | Some("errors") | Some("warning") | Some("warnings")
+        )
+        && words.next() == Some("generated.")
+}
+
+/// Split a `file:line:column` location, starting from the end since file names may contain
+/// colons
+fn parse_location(location: &str) -> (Option<String>, Option<usize>, Option<usize>) {
+    let mut file = location;
+    let mut numbers = Vec::new();
+
+    while numbers.len() < 2 {
+        match file.rfind(':') {
+            Some(index) => match file[index + 1..].parse::<usize>() {
+                Ok(number) => {
+                    numbers.push(number);
+                    file = &file[..index];
+                }
+                Err(_) => break,
+            },
+            None => break,
+        }
+    }
+
+    let (line, column) = match numbers.as_slice() {
+        [column, line] => (Some(*line), Some(*column)),
+        [line] => (Some(*line), None),
+        _ => (None, None),
+    };
+
+    let file = if file.is_empty() {
+        None
+    } else {
+        Some(file.to_owned())
+    };
+
+    (file, line, column)
+}
@@ -0,0 +1,254 @@
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+use crate::{Error, Result};
+
+/// Kind of include directive
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IncludeType {
+    /// `#include "file"`, searched relative to the including file first
+    Relative,
+    /// `#include <file>`, only searched in the system directories
+    Standard,
+}
+
+/// Source of included files
+pub trait IncludeFileSystem {
+    /// Read the contents of a file
+    ///
+    /// # Parameters
+    ///
+    /// * `path`: path to the file
+    ///
+    /// # Returns
+    ///
+    /// `None` if the file does not exist in this file system.
+    fn read(&self, path: &Path) -> Option<std::io::Result<String>>;
+
+    /// true if the files of this file system are on disk, and should be tracked by cargo
+    fn is_disk(&self) -> bool {
+        false
+    }
+}
+
+/// Files read from the disk
+#[derive(Debug, Default)]
+pub struct DiskFileSystem;
+
+impl IncludeFileSystem for DiskFileSystem {
+    fn read(&self, path: &Path) -> Option<std::io::Result<String>> {
+        if path.is_file() {
+            Some(std::fs::read_to_string(path))
+        } else {
+            None
+        }
+    }
+
+    fn is_disk(&self) -> bool {
+        true
+    }
+}
+
+/// In-memory files, for generated or embedded sources
+#[derive(Debug, Default)]
+pub struct MemoryFileSystem {
+    files: HashMap<PathBuf, String>,
+}
+
+impl MemoryFileSystem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a file to this file system
+    ///
+    /// # Parameters
+    ///
+    /// * `path`: path of the file, as it would be resolved from the include directives
+    /// * `content`: GLSL source of the file
+    pub fn with_file(mut self, path: impl AsRef<Path>, content: impl Into<String>) -> Self {
+        self.insert(path, content);
+        self
+    }
+
+    /// Add a file to this file system, replacing any previous file at the same path
+    pub fn insert(&mut self, path: impl AsRef<Path>, content: impl Into<String>) {
+        self.files
+            .insert(normalize_path(path.as_ref()), content.into());
+    }
+}
+
+impl IncludeFileSystem for MemoryFileSystem {
+    fn read(&self, path: &Path) -> Option<std::io::Result<String>> {
+        self.files.get(path).map(|content| Ok(content.clone()))
+    }
+}
+
+/// Result of resolving an include directive
+#[derive(Debug, Clone)]
+pub struct ResolvedInclude {
+    /// Full path of the included file
+    pub path: PathBuf,
+    /// Source of the included file
+    pub content: String,
+    /// true if the file was read from the disk
+    pub is_disk: bool,
+}
+
+/// Resolver for `#include` directives
+pub struct IncludeResolver {
+    quote_dirs: Vec<PathBuf>,
+    system_dirs: Vec<PathBuf>,
+    file_systems: Vec<Box<dyn IncludeFileSystem>>,
+}
+
+impl Default for IncludeResolver {
+    fn default() -> Self {
+        Self {
+            quote_dirs: Vec::new(),
+            system_dirs: Vec::new(),
+            file_systems: vec![Box::new(DiskFileSystem)],
+        }
+    }
+}
+
+impl IncludeResolver {
+    /// Create a resolver which only looks for files relative to the including file, on disk
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a directory searched for `#include "..."` directives, after the directory of the
+    /// including file
+    pub fn quote_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.quote_dirs.push(dir.into());
+        self
+    }
+
+    /// Add a directory searched for all include directives, after the quote directories
+    pub fn system_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.system_dirs.push(dir.into());
+        self
+    }
+
+    /// Add a file system to read included files from
+    ///
+    /// File systems are searched in reverse order of addition, so files added this way take
+    /// precedence over files on disk.
+    pub fn file_system(mut self, file_system: impl IncludeFileSystem + 'static) -> Self {
+        self.file_systems.insert(0, Box::new(file_system));
+        self
+    }
+
+    /// Directories searched for an include directive, in order
+    ///
+    /// # Parameters
+    ///
+    /// * `include_type`: kind of include directive
+    /// * `requesting_source`: path of the file containing the directive
+    pub fn search_dirs(&self, include_type: IncludeType, requesting_source: &str) -> Vec<PathBuf> {
+        let mut dirs = Vec::new();
+
+        if include_type == IncludeType::Relative {
+            dirs.push(
+                Path::new(requesting_source)
+                    .parent()
+                    .map(Path::to_path_buf)
+                    .unwrap_or_default(),
+            );
+            dirs.extend(self.quote_dirs.iter().cloned());
+        }
+
+        dirs.extend(self.system_dirs.iter().cloned());
+        dirs
+    }
+
+    /// Resolve an include directive
+    ///
+    /// # Parameters
+    ///
+    /// * `name`: name of the included file, as written in the directive
+    /// * `include_type`: kind of include directive
+    /// * `requesting_source`: path of the file containing the directive
+    pub fn resolve(
+        &self,
+        name: &str,
+        include_type: IncludeType,
+        requesting_source: &str,
+    ) -> Result<ResolvedInclude> {
+        let searched = self.search_dirs(include_type, requesting_source);
+
+        for dir in &searched {
+            let path = normalize_path(&dir.join(name));
+
+            for file_system in &self.file_systems {
+                if let Some(content) = file_system.read(&path) {
+                    let is_disk = file_system.is_disk();
+
+                    return Ok(ResolvedInclude {
+                        path: if is_disk {
+                            std::fs::canonicalize(&path)?
+                        } else {
+                            path
+                        },
+                        content: content?,
+                        is_disk,
+                    });
+                }
+            }
+        }
+
+        Err(Error::IncludeNotFound(name.to_owned(), searched))
+    }
+}
+
+/// Stack of files being included, to detect include cycles
+#[derive(Debug, Default)]
+pub(crate) struct IncludeStack {
+    chain: Vec<String>,
+}
+
+impl IncludeStack {
+    /// Record an include directive, and check that it does not form a cycle
+    ///
+    /// Include directives are processed depth-first, so the stack is rewound to the depth of the
+    /// requesting source before checking for the included file.
+    ///
+    /// # Parameters
+    ///
+    /// * `requesting_source`: path of the file containing the directive
+    /// * `included`: path of the included file
+    /// * `depth`: include depth of the included file, starting at 1
+    pub fn enter(&mut self, requesting_source: &str, included: &str, depth: usize) -> Result<()> {
+        self.chain.truncate(depth.saturating_sub(1));
+        self.chain.push(requesting_source.to_owned());
+
+        if self.chain.iter().any(|source| source == included) {
+            let mut chain = self.chain.clone();
+            chain.push(included.to_owned());
+            return Err(Error::IncludeCycle(chain));
+        }
+
+        Ok(())
+    }
+}
+
+/// Normalize a path by removing `.` and `..` components, without accessing the file system
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut result = PathBuf::new();
+
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => match result.components().next_back() {
+                Some(Component::Normal(_)) => {
+                    result.pop();
+                }
+                _ => result.push(component),
+            },
+            _ => result.push(component),
+        }
+    }
+
+    result
+}
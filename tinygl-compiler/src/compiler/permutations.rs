@@ -0,0 +1,178 @@
+use std::collections::HashSet;
+
+use heck::{CamelCase, ShoutySnakeCase};
+
+use super::wrapped_shader::*;
+use crate::model::GlslWithSpirVModule;
+use crate::CompileOptions;
+
+/// Key selecting between shader permutations
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum FeatureKey {
+    /// Feature toggled on or off, the macro is only defined when the feature is enabled
+    Bool(String),
+    /// Feature taking one of several values
+    ///
+    /// The macro is defined to the index of the selected value, and one `NAME_VALUE` macro is
+    /// defined for each value so shaders can write `#if QUALITY == QUALITY_HIGH`.
+    Enum(String, Vec<String>),
+}
+
+impl FeatureKey {
+    pub fn name(&self) -> &str {
+        match self {
+            Self::Bool(name) => name,
+            Self::Enum(name, _) => name,
+        }
+    }
+
+    /// Number of values this key can take
+    pub fn value_count(&self) -> usize {
+        match self {
+            Self::Bool(_) => 2,
+            Self::Enum(_, values) => values.len(),
+        }
+    }
+
+    /// Add the macro definitions for a value of this key
+    ///
+    /// # Parameters
+    ///
+    /// * `options`: options to add the definitions to
+    /// * `value`: index of the value of the key
+    pub(crate) fn define(&self, options: CompileOptions, value: usize) -> CompileOptions {
+        match self {
+            Self::Bool(name) => {
+                if value != 0 {
+                    options.define(name, Some("1"))
+                } else {
+                    options.undefine(name)
+                }
+            }
+            Self::Enum(name, values) => values
+                .iter()
+                .enumerate()
+                .fold(options, |options, (index, value_name)| {
+                    options.define(
+                        &format!("{}_{}", name, value_name.to_shouty_snake_case()),
+                        Some(&index.to_string()),
+                    )
+                })
+                .define(name, Some(&value.to_string())),
+        }
+    }
+}
+
+/// Values of the feature keys for a permutation
+///
+/// # Parameters
+///
+/// * `keys`: feature keys
+/// * `index`: index of the permutation, the first key varying the fastest
+pub(crate) fn permutation_values(keys: &[FeatureKey], mut index: usize) -> Vec<usize> {
+    keys.iter()
+        .map(|key| {
+            let value = index % key.value_count();
+            index /= key.value_count();
+            value
+        })
+        .collect()
+}
+
+/// Check that feature keys can be turned into permutations
+///
+/// Enum keys must have at least one value, and key names must be unique.
+///
+/// # Parameters
+///
+/// * `keys`: feature keys
+pub(crate) fn validate_keys(keys: &[FeatureKey]) -> crate::Result<()> {
+    let mut names = HashSet::new();
+
+    for key in keys {
+        if key.value_count() == 0 {
+            return Err(crate::Error::InvalidFeatureKey(
+                key.name().to_owned(),
+                "enum keys need at least one value".to_owned(),
+            ));
+        }
+
+        if !names.insert(key.name()) {
+            return Err(crate::Error::InvalidFeatureKey(
+                key.name().to_owned(),
+                "duplicate key name".to_owned(),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Program compiled for every permutation of a set of feature keys
+pub struct WrappedPermutations {
+    struct_name: String,
+    variant_struct_name: String,
+    programs_struct_name: String,
+    keys: Vec<FeatureKey>,
+    shaders: Vec<WrappedShader<GlslWithSpirVModule<'static, 'static>>>,
+    variants: Vec<Vec<usize>>,
+}
+
+impl WrappedPermutations {
+    pub(crate) fn new(
+        program_name: &str,
+        keys: Vec<FeatureKey>,
+        shaders: Vec<WrappedShader<GlslWithSpirVModule<'static, 'static>>>,
+        variants: Vec<Vec<usize>>,
+    ) -> Self {
+        let struct_name = program_name.to_camel_case() + "Program";
+
+        Self {
+            variant_struct_name: struct_name.clone() + "Variant",
+            programs_struct_name: struct_name.clone() + "s",
+            struct_name,
+            keys,
+            shaders,
+            variants,
+        }
+    }
+
+    /// Name of the program type shared by all variants
+    pub fn struct_name(&self) -> &str {
+        &self.struct_name
+    }
+
+    /// Name of the key type selecting a variant
+    pub fn variant_struct_name(&self) -> &str {
+        &self.variant_struct_name
+    }
+
+    /// Name of the type building the programs for each variant on demand
+    pub fn programs_struct_name(&self) -> &str {
+        &self.programs_struct_name
+    }
+
+    pub fn keys(&self) -> &[FeatureKey] {
+        &self.keys
+    }
+
+    /// Shaders compiled for all permutations, without duplicates
+    pub fn shaders(&self) -> &[WrappedShader<GlslWithSpirVModule<'static, 'static>>] {
+        &self.shaders
+    }
+
+    /// Number of permutations
+    pub fn variant_count(&self) -> usize {
+        self.variants.len()
+    }
+
+    /// Indices in `shaders()` of the shaders attached to a variant
+    pub fn variant_shaders(&self, index: usize) -> &[usize] {
+        &self.variants[index]
+    }
+
+    /// Values of the feature keys for a variant
+    pub fn variant_values(&self, index: usize) -> Vec<usize> {
+        permutation_values(&self.keys, index)
+    }
+}
@@ -0,0 +1,143 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::model::{bytes_to_id, SourcePath};
+use crate::reflect::ReflectionData;
+use crate::{CompileOptions, Diagnostic, Error, GlslVersion, Result, ShaderKind};
+
+/// Cached results of compiling and reflecting on a shader
+#[derive(Debug, Serialize, Deserialize)]
+pub(crate) struct CacheEntry {
+    /// Embedded source, either preprocessed or transpiled
+    pub glsl: String,
+    pub spirv: Vec<u32>,
+    pub warnings: Vec<Diagnostic>,
+    /// Reflection data, empty for shaders which were not reflected on
+    pub reflection: ReflectionData,
+}
+
+/// On-disk cache of compiled shaders
+///
+/// Entries are keyed by the preprocessed source of the shaders, so changes to included files
+/// invalidate the entries of the shaders including them.
+#[derive(Debug)]
+pub struct ShaderCache {
+    dir: PathBuf,
+    hits: usize,
+    misses: usize,
+}
+
+impl ShaderCache {
+    /// Create a cache storing its entries in a directory
+    ///
+    /// # Parameters
+    ///
+    /// * `dir`: directory for the cache entries, created on first write
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self {
+            dir: dir.into(),
+            hits: 0,
+            misses: 0,
+        }
+    }
+
+    /// Create a cache in the `OUT_DIR` of the current build script
+    pub fn in_out_dir() -> Result<Self> {
+        let out_dir = std::env::var_os("OUT_DIR").ok_or(Error::OutDirNotSet)?;
+        Ok(Self::new(Path::new(&out_dir).join("tinygl-cache")))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Number of shaders loaded from the cache
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /// Number of shaders which had to be compiled
+    pub fn misses(&self) -> usize {
+        self.misses
+    }
+
+    /// Compute the key of a shader
+    ///
+    /// # Parameters
+    ///
+    /// * `preprocessed`: preprocessed source of the shader
+    /// * `kind`: shader stage
+    /// * `options`: compile options for the shader
+    /// * `compiler_version`: version of the compiler producing the SPIR-V
+    /// * `reflector`: name of the reflection backend producing the cached reflection data, if any
+    /// * `transpile`: GLSL version the embedded source is transpiled to, if any
+    pub(crate) fn key(
+        preprocessed: &str,
+        kind: ShaderKind,
+        options: &CompileOptions,
+        compiler_version: u32,
+        reflector: Option<&str>,
+        transpile: Option<GlslVersion>,
+    ) -> String {
+        let (spirv_major, spirv_minor) = shaderc::get_spirv_version();
+        let key = format!(
+            "tinygl-compiler {}\nspirv {}.{}\ncompiler {:#x}\nreflector {:?}\ntranspile {:?}\n{:?}\n{}",
+            env!("CARGO_PKG_VERSION"),
+            spirv_major,
+            spirv_minor,
+            compiler_version,
+            reflector,
+            transpile,
+            options,
+            preprocessed
+        );
+
+        match bytes_to_id(key.as_bytes(), kind).source_path {
+            // base64 may contain path separators
+            SourcePath::Generated(id) => id.replace('/', "_").replace('+', "-"),
+            SourcePath::File(path) => path.to_string_lossy().into_owned(),
+        }
+    }
+
+    fn entry_path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", key))
+    }
+
+    /// Load a cache entry
+    ///
+    /// Missing or unreadable entries are reported as misses.
+    ///
+    /// # Parameters
+    ///
+    /// * `key`: key of the entry
+    pub(crate) fn load(&mut self, key: &str) -> Option<CacheEntry> {
+        let entry = std::fs::read(self.entry_path(key))
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok());
+
+        if entry.is_some() {
+            self.hits += 1;
+        } else {
+            self.misses += 1;
+        }
+
+        entry
+    }
+
+    /// Store a cache entry
+    ///
+    /// # Parameters
+    ///
+    /// * `key`: key of the entry
+    /// * `entry`: results to cache
+    pub(crate) fn store(&self, key: &str, entry: &CacheEntry) -> Result<()> {
+        std::fs::create_dir_all(&self.dir)?;
+
+        let bytes = serde_json::to_vec(entry)
+            .map_err(|error| std::io::Error::new(std::io::ErrorKind::InvalidData, error))?;
+        std::fs::write(self.entry_path(key), bytes)?;
+
+        Ok(())
+    }
+}
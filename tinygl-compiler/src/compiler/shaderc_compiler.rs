@@ -1,11 +1,31 @@
-use std::path::Path;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
 
-use crate::model::{GlslCompiler, GlslModule, GlslPreprocessor, SpirVModule};
-use crate::{Compiler, Error, ShaderKind};
+use crate::model::AsOutputFormat;
+use crate::model::{
+    bytes_to_id, GlslCompiler, GlslModule, GlslObject, GlslPreprocessor, GlslWithSpirVModule,
+    ObjectInfo, ShaderObject, SourcePath, SpirVModule,
+};
+use crate::reflect::{ReflectedObject, SpirVReflectBackend};
+use crate::{
+    permutation_values, validate_keys, CompileOptions, Compiler, Diagnostic, Error, FeatureKey,
+    GlslProfile, GlslVersion, IncludeStack, IncludeType, OptimizationLevel, ShaderKind,
+    WrappedPermutations,
+};
+
+/// Contents of the included files, by resolved name
+type IncludedSources = Rc<RefCell<HashMap<String, String>>>;
 
 pub struct CompilerWithShaderc {
     compiler: Compiler,
     shaderc: shaderc::Compiler,
+    #[cfg(feature = "cache")]
+    cache: Option<crate::ShaderCache>,
+    #[cfg(feature = "cache")]
+    compiler_version: Option<u32>,
+    #[cfg(feature = "transpile")]
+    transpile: Option<GlslVersion>,
 }
 
 impl CompilerWithShaderc {
@@ -13,52 +33,533 @@ impl CompilerWithShaderc {
         Self {
             compiler,
             shaderc: shaderc::Compiler::new().unwrap(),
+            #[cfg(feature = "cache")]
+            cache: None,
+            #[cfg(feature = "cache")]
+            compiler_version: None,
+            #[cfg(feature = "transpile")]
+            transpile: None,
+        }
+    }
+
+    /// Embed GLSL code transpiled from the compiled SPIR-V instead of the preprocessed sources
+    ///
+    /// The embedded sources then use the `#version` required by the target instead of the one
+    /// of the original sources.
+    ///
+    /// # Parameters
+    ///
+    /// * `version`: GLSL version of the embedded sources
+    #[cfg(feature = "transpile")]
+    pub fn with_transpile(mut self, version: GlslVersion) -> Self {
+        self.transpile = Some(version);
+        self
+    }
+
+    /// GLSL version the embedded sources are transpiled to, if any
+    pub fn transpile_version(&self) -> Option<GlslVersion> {
+        #[cfg(feature = "transpile")]
+        {
+            self.transpile
+        }
+
+        #[cfg(not(feature = "transpile"))]
+        {
+            None
+        }
+    }
+
+    /// Pair a compiled module with the source to embed for it
+    ///
+    /// # Parameters
+    ///
+    /// * `source_module`: preprocessed source of the module
+    /// * `binary_module`: compiled module
+    fn output_module(
+        &self,
+        source_module: GlslModule<'static>,
+        binary_module: SpirVModule<'static>,
+    ) -> Result<GlslWithSpirVModule<'static, 'static>, Error> {
+        #[cfg(feature = "transpile")]
+        if let Some(version) = self.transpile {
+            let glsl = binary_module.transpile(version)?;
+            return Ok(GlslWithSpirVModule::new(glsl, binary_module));
+        }
+
+        Ok(GlslWithSpirVModule::new(source_module, binary_module))
+    }
+
+    /// Reuse the results of previous builds when compiling shaders
+    ///
+    /// # Parameters
+    ///
+    /// * `cache`: on-disk cache of compiled shaders
+    #[cfg(feature = "cache")]
+    pub fn with_cache(mut self, cache: crate::ShaderCache) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    #[cfg(feature = "cache")]
+    pub fn cache(&self) -> Option<&crate::ShaderCache> {
+        self.cache.as_ref()
+    }
+
+    /// Version of the SPIR-V generator used by shaderc
+    ///
+    /// shaderc doesn't expose the version of glslang it was built with, so this is read from the
+    /// generator word of the header of an empty compiled shader.
+    #[cfg(feature = "cache")]
+    fn compiler_version(&mut self) -> u32 {
+        if let Some(version) = self.compiler_version {
+            return version;
+        }
+
+        let version = self
+            .shaderc
+            .compile_into_spirv(
+                "#version 450\nvoid main() {}\n",
+                shaderc::ShaderKind::Vertex,
+                "version.glsl",
+                "main",
+                None,
+            )
+            .expect("failed to compile an empty shader")
+            .as_binary()[2];
+
+        self.compiler_version = Some(version);
+        version
+    }
+
+    /// Compile and reflect on a shader object
+    ///
+    /// The resulting object holds the preprocessed source, so it can be embedded without
+    /// resolving includes. If a cache is set, the results are loaded from the cache when the
+    /// preprocessed source and options did not change.
+    ///
+    /// # Parameters
+    ///
+    /// * `object`: shader object to compile
+    /// * `reflector`: reflection backend for the compiled shader
+    pub fn compile_reflect(
+        &mut self,
+        object: &GlslObject,
+        reflector: &dyn SpirVReflectBackend,
+    ) -> Result<ReflectedObject<ShaderObject<GlslWithSpirVModule<'static, 'static>>>, Error> {
+        let info = object.info().clone();
+        let source_path = info.source_path.to_string();
+
+        self.compile_reflect_source(
+            object.as_str(),
+            &source_path,
+            info,
+            object.options().cloned(),
+            reflector,
+        )
+    }
+
+    /// Compile and reflect on a shader source, going through the cache if one is set
+    ///
+    /// # Parameters
+    ///
+    /// * `source`: source of the shader
+    /// * `source_path`: path of the source, for diagnostics
+    /// * `info`: info of the resulting object
+    /// * `options`: compile options overriding the defaults of the compiler
+    /// * `reflector`: reflection backend for the compiled shader
+    fn compile_reflect_source(
+        &mut self,
+        source: &str,
+        source_path: &str,
+        info: ObjectInfo,
+        options: Option<CompileOptions>,
+        reflector: &dyn SpirVReflectBackend,
+    ) -> Result<ReflectedObject<ShaderObject<GlslWithSpirVModule<'static, 'static>>>, Error> {
+        let (source_module, preprocess_warnings) =
+            self.preprocess_module(source, source_path, options.as_ref())?;
+
+        #[cfg(feature = "cache")]
+        let key = self.cache_key(
+            source_module.as_str(),
+            info.kind,
+            options.as_ref(),
+            Some(reflector),
+        );
+
+        #[cfg(feature = "cache")]
+        if let Some(entry) = self.cache_load(key.as_deref()) {
+            return Ok(ReflectedObject::new(
+                ShaderObject::new(
+                    GlslWithSpirVModule::new(
+                        GlslModule::from_string(entry.glsl)?,
+                        SpirVModule::from_words(entry.spirv)?,
+                    ),
+                    info,
+                    options,
+                    entry.warnings,
+                ),
+                entry.reflection,
+            ));
+        }
+
+        let (binary_module, compile_warnings) =
+            self.compile_spirv(source, info.kind, source_path, options.as_ref())?;
+
+        let mut warnings = preprocess_warnings;
+        warnings.extend(compile_warnings);
+
+        let object = ShaderObject::new(
+            self.output_module(source_module, binary_module)?,
+            info,
+            options,
+            warnings,
+        )
+        .reflect_spirv(reflector)?;
+
+        #[cfg(feature = "cache")]
+        self.cache_store(
+            key.as_deref(),
+            &crate::compiler::shader_cache::CacheEntry {
+                glsl: object.as_source().unwrap().as_str().to_owned(),
+                spirv: object.as_spirv().unwrap().as_bytes().to_vec(),
+                warnings: object.object().warnings().to_vec(),
+                reflection: object.data().clone(),
+            },
+        )?;
+
+        Ok(object)
+    }
+
+    /// Compute the cache key of a shader, `None` if no cache is set
+    ///
+    /// # Parameters
+    ///
+    /// * `preprocessed`: preprocessed source of the shader
+    /// * `kind`: shader stage
+    /// * `options`: compile options overriding the defaults of the compiler
+    /// * `reflector`: reflection backend for the compiled shader, if it is reflected on
+    #[cfg(feature = "cache")]
+    fn cache_key(
+        &mut self,
+        preprocessed: &str,
+        kind: ShaderKind,
+        options: Option<&CompileOptions>,
+        reflector: Option<&dyn SpirVReflectBackend>,
+    ) -> Option<String> {
+        self.cache.as_ref()?;
+
+        let compiler_version = self.compiler_version();
+        Some(crate::ShaderCache::key(
+            preprocessed,
+            kind,
+            options.unwrap_or(&self.compiler.options),
+            compiler_version,
+            reflector.map(|reflector| reflector.name()),
+            self.transpile_version(),
+        ))
+    }
+
+    #[cfg(feature = "cache")]
+    fn cache_load(
+        &mut self,
+        key: Option<&str>,
+    ) -> Option<crate::compiler::shader_cache::CacheEntry> {
+        self.cache.as_mut()?.load(key?)
+    }
+
+    #[cfg(feature = "cache")]
+    fn cache_store(
+        &self,
+        key: Option<&str>,
+        entry: &crate::compiler::shader_cache::CacheEntry,
+    ) -> Result<(), Error> {
+        match (&self.cache, key) {
+            (Some(cache), Some(key)) => cache.store(key, entry),
+            _ => Ok(()),
+        }
+    }
+
+    /// Compile a shader to SPIR-V, without going through the cache
+    ///
+    /// # Parameters
+    ///
+    /// * `source`: source of the shader
+    /// * `kind`: shader stage
+    /// * `source_path`: path of the source, for diagnostics
+    /// * `options`: compile options overriding the defaults of the compiler
+    fn compile_spirv(
+        &mut self,
+        source: &str,
+        kind: ShaderKind,
+        source_path: &str,
+        options: Option<&CompileOptions>,
+    ) -> Result<(SpirVModule<'static>, Vec<Diagnostic>), crate::Error> {
+        let sources = IncludedSources::default();
+        let options = self.get_options(options, &sources);
+
+        // Compile the requested targets
+        match self.shaderc.compile_into_spirv(
+            source,
+            kind.into(),
+            source_path,
+            "main",
+            Some(&options),
+        ) {
+            Ok(result) => {
+                let warnings = if result.get_num_warnings() > 0 {
+                    diagnostics(
+                        &result.get_warning_messages(),
+                        source,
+                        source_path,
+                        &sources,
+                    )
+                } else {
+                    Vec::new()
+                };
+
+                Ok((
+                    SpirVModule::from_words(result.as_binary().to_vec())?,
+                    warnings,
+                ))
+            }
+            Err(shaderc::Error::CompilationError(_, errors)) => {
+                Err(self.compilation_error(&errors, source, source_path, &sources))
+            }
+            Err(error) => panic!(error.to_string()),
         }
     }
 
-    fn get_options(&self) -> shaderc::CompileOptions<'static> {
+    fn get_options(
+        &self,
+        overrides: Option<&CompileOptions>,
+        sources: &IncludedSources,
+    ) -> shaderc::CompileOptions<'static> {
         let skip_cargo = self.compiler.skip_cargo;
         let cb = self.compiler.include_callback.as_ref().map(|cb| cb.clone());
+        let compile_options = overrides.unwrap_or_else(|| self.compiler.options());
 
         // Set callback
         let mut options = shaderc::CompileOptions::new().unwrap();
 
         // Add definitions
-        // TODO: Let use configure options?
-        options.add_macro_definition("TINYGL", Some(env!("CARGO_PKG_VERSION_MAJOR")));
+        for (name, value) in compile_options.macros() {
+            options.add_macro_definition(name, value);
+        }
+
+        if compile_options.is_warnings_as_errors() {
+            options.set_warnings_as_errors();
+        }
+
+        if let Some((version, profile)) = compile_options.forced_version() {
+            options.set_forced_version_profile(
+                version.number(),
+                if version.is_es() {
+                    shaderc::GlslProfile::Es
+                } else if version.number() < 150 {
+                    shaderc::GlslProfile::None
+                } else {
+                    match profile {
+                        GlslProfile::Core => shaderc::GlslProfile::Core,
+                        GlslProfile::Compatibility => shaderc::GlslProfile::Compatibility,
+                    }
+                },
+            );
+        }
+
+        options.set_auto_bind_uniforms(compile_options.is_auto_bind_uniforms());
+        options.set_auto_map_locations(compile_options.is_auto_map_locations());
+
+        // shaderc strips debug names when optimizing unless debug info is enabled first, but
+        // reflection needs them
+        let optimization_level = compile_options.optimization_level();
+        if compile_options.is_debug_info() || optimization_level != OptimizationLevel::Zero {
+            options.set_generate_debug_info();
+        }
+
+        options.set_optimization_level(match optimization_level {
+            OptimizationLevel::Zero => shaderc::OptimizationLevel::Zero,
+            OptimizationLevel::Size => shaderc::OptimizationLevel::Size,
+            OptimizationLevel::Performance => shaderc::OptimizationLevel::Performance,
+        });
 
         // Default to OpenGL targets
         options.set_target_env(shaderc::TargetEnv::OpenGL, 0);
 
         // Set include callback
-        options.set_include_callback(move |name, _include_type, source, _depth| {
-            // TODO: Circular includes?
-            // TODO: Better include resolver?
-            match std::fs::canonicalize(Path::new(&source).parent().unwrap().join(name)) {
-                Ok(full_path) => {
-                    if !skip_cargo {
-                        // Notify cargo to rerun if included file changed
-                        println!("cargo:rerun-if-changed={}", full_path.display());
-                    }
+        let resolver = self.compiler.include_resolver.clone();
+        let stack = RefCell::new(IncludeStack::default());
+        let sources = sources.clone();
 
-                    if let Some(cb) = &cb {
-                        cb.borrow_mut()(&full_path);
-                    }
+        options.set_include_callback(move |name, include_type, source, depth| {
+            let include_type = match include_type {
+                shaderc::IncludeType::Relative => IncludeType::Relative,
+                shaderc::IncludeType::Standard => IncludeType::Standard,
+            };
 
-                    match std::fs::read_to_string(&full_path) {
-                        Ok(content) => Ok(shaderc::ResolvedInclude {
-                            resolved_name: full_path.to_string_lossy().to_string(),
-                            content,
-                        }),
-                        Err(error) => Err(error.to_string()),
-                    }
+            let resolved = resolver
+                .resolve(name, include_type, source)
+                .map_err(|error| error.to_string())?;
+            let resolved_name = resolved.path.to_string_lossy().to_string();
+
+            stack
+                .borrow_mut()
+                .enter(source, &resolved_name, depth)
+                .map_err(|error| error.to_string())?;
+
+            if resolved.is_disk {
+                if !skip_cargo {
+                    // Notify cargo to rerun if included file changed
+                    println!("cargo:rerun-if-changed={}", resolved.path.display());
+                }
+
+                if let Some(cb) = &cb {
+                    cb.borrow_mut()(&resolved.path);
                 }
-                Err(error) => Err(error.to_string()),
             }
+
+            sources
+                .borrow_mut()
+                .insert(resolved_name.clone(), resolved.content.clone());
+
+            Ok(shaderc::ResolvedInclude {
+                resolved_name,
+                content: resolved.content,
+            })
         });
 
         options
     }
+
+    /// Convert the compiler output to an error, with diagnostics pointing to the source lines
+    fn compilation_error(
+        &self,
+        errors: &str,
+        source: &str,
+        source_path: &str,
+        sources: &IncludedSources,
+    ) -> Error {
+        let diagnostics = diagnostics(errors, source, source_path, sources);
+
+        if !self.compiler.skip_cargo {
+            eprintln!("{}", Diagnostic::render_all(&diagnostics));
+        }
+
+        Error::CompilationError(diagnostics)
+    }
+
+    /// Compile every permutation of a program and wrap them as a single program type
+    ///
+    /// Permutations which compile to the same SPIR-V share the same shader.
+    ///
+    /// # Parameters
+    ///
+    /// * `program_name`: name of the program
+    /// * `shaders`: sources of the program stages
+    /// * `keys`: feature keys, defined as macros when compiling each permutation
+    /// * `reflector`: reflection backend for the compiled shaders
+    /// * `prefer_spirv`: true to embed SPIR-V binaries, false to embed preprocessed sources
+    pub fn wrap_permutations(
+        &mut self,
+        program_name: &str,
+        shaders: &[&GlslObject],
+        keys: &[FeatureKey],
+        reflector: &dyn SpirVReflectBackend,
+        prefer_spirv: bool,
+    ) -> Result<WrappedPermutations, Error> {
+        validate_keys(keys)?;
+
+        let count = keys.iter().map(FeatureKey::value_count).product();
+
+        let mut known: HashMap<ObjectInfo, usize> = HashMap::new();
+        let mut wrapped = Vec::new();
+        let mut variants = Vec::with_capacity(count);
+
+        for index in 0..count {
+            let values = permutation_values(keys, index);
+            let mut variant = Vec::with_capacity(shaders.len());
+
+            for shader in shaders {
+                let kind = shader.info().kind;
+                let source_path = shader.info().source_path.to_string();
+                let options = keys.iter().zip(&values).fold(
+                    shader
+                        .options()
+                        .unwrap_or_else(|| self.compiler.options())
+                        .clone(),
+                    |options, (key, value)| key.define(options, *value),
+                );
+
+                let object = self.compile_reflect_source(
+                    shader.as_str(),
+                    &source_path,
+                    ObjectInfo {
+                        source_path: SourcePath::Generated(format!(
+                            "{}_{}.{}",
+                            program_name,
+                            wrapped.len(),
+                            kind.extension()
+                        )),
+                        kind,
+                    },
+                    Some(options),
+                    reflector,
+                )?;
+
+                // Deduplicate permutations by the hash of their SPIR-V
+                let id = bytes_to_id(object.as_spirv().unwrap().as_bytes_u8(), kind);
+                if let Some(shader_index) = known.get(&id) {
+                    variant.push(*shader_index);
+                    continue;
+                }
+
+                known.insert(id, wrapped.len());
+                variant.push(wrapped.len());
+                wrapped.push(self.compiler.wrap_shader(object, prefer_spirv)?);
+            }
+
+            variants.push(variant);
+        }
+
+        Ok(WrappedPermutations::new(
+            program_name,
+            keys.to_vec(),
+            wrapped,
+            variants,
+        ))
+    }
+}
+
+/// Parse the compiler output into diagnostics, with the source lines they refer to
+///
+/// # Parameters
+///
+/// * `output`: compiler output
+/// * `source`: source of the compiled file
+/// * `source_path`: path of the compiled file
+/// * `sources`: sources of the files it includes
+fn diagnostics(
+    output: &str,
+    source: &str,
+    source_path: &str,
+    sources: &IncludedSources,
+) -> Vec<Diagnostic> {
+    let sources = sources.borrow();
+
+    Diagnostic::parse(output)
+        .into_iter()
+        .map(|diagnostic| {
+            let file_source = match diagnostic.file.as_deref() {
+                Some(file) if file == source_path => Some(source),
+                Some(file) => sources.get(file).map(String::as_str),
+                None => None,
+            };
+
+            match file_source {
+                Some(file_source) => diagnostic.with_source(file_source),
+                None => diagnostic,
+            }
+        })
+        .collect()
 }
 
 impl GlslCompiler for CompilerWithShaderc {
@@ -67,39 +568,34 @@ impl GlslCompiler for CompilerWithShaderc {
         source: &str,
         kind: ShaderKind,
         source_path: &str,
-    ) -> Result<SpirVModule<'static>, crate::Error> {
-        let options = self.get_options();
+        options: Option<&CompileOptions>,
+    ) -> Result<(SpirVModule<'static>, Vec<Diagnostic>), crate::Error> {
+        #[cfg(feature = "cache")]
+        if self.cache.is_some() {
+            let (source_module, _) = self.preprocess_module(source, source_path, options)?;
+            let key = self.cache_key(source_module.as_str(), kind, options, None);
 
-        // Compile the requested targets
-        match self.shaderc.compile_into_spirv(
-            source,
-            kind.into(),
-            source_path,
-            "main",
-            Some(&options),
-        ) {
-            Ok(result) => {
-                if !self.compiler.skip_cargo {
-                    // Print warnings
-                    // TODO: Store warnings in result?
-                    if result.get_num_warnings() > 0 {
-                        for l in result.get_warning_messages().lines() {
-                            println!("cargo:warning={}", l);
-                        }
-                    }
-                }
-
-                Ok(SpirVModule::from_words(result.as_binary().to_vec())?)
+            if let Some(entry) = self.cache_load(key.as_deref()) {
+                return Ok((SpirVModule::from_words(entry.spirv)?, entry.warnings));
             }
-            Err(shaderc::Error::CompilationError(num_errors, errors)) => {
-                if !self.compiler.skip_cargo {
-                    eprintln!("{}", errors);
-                }
 
-                return Err(Error::CompilationError(num_errors as usize, errors.clone()));
-            }
-            Err(error) => panic!(error.to_string()),
+            let (binary_module, warnings) =
+                self.compile_spirv(source, kind, source_path, options)?;
+
+            self.cache_store(
+                key.as_deref(),
+                &crate::compiler::shader_cache::CacheEntry {
+                    glsl: source_module.as_str().to_owned(),
+                    spirv: binary_module.as_bytes().to_vec(),
+                    warnings: warnings.clone(),
+                    reflection: Default::default(),
+                },
+            )?;
+
+            return Ok((binary_module, warnings));
         }
+
+        self.compile_spirv(source, kind, source_path, options)
     }
 }
 
@@ -108,8 +604,10 @@ impl GlslPreprocessor for CompilerWithShaderc {
         &mut self,
         source: &str,
         source_path: &str,
-    ) -> Result<GlslModule<'static>, crate::Error> {
-        let options = self.get_options();
+        options: Option<&CompileOptions>,
+    ) -> Result<(GlslModule<'static>, Vec<Diagnostic>), crate::Error> {
+        let sources = IncludedSources::default();
+        let options = self.get_options(options, &sources);
 
         // Compile the requested targets
         match self
@@ -117,24 +615,21 @@ impl GlslPreprocessor for CompilerWithShaderc {
             .preprocess(source, source_path, "main", Some(&options))
         {
             Ok(result) => {
-                if !self.compiler.skip_cargo {
-                    // Print warnings
-                    // TODO: Store warnings in result?
-                    if result.get_num_warnings() > 0 {
-                        for l in result.get_warning_messages().lines() {
-                            println!("cargo:warning={}", l);
-                        }
-                    }
-                }
+                let warnings = if result.get_num_warnings() > 0 {
+                    diagnostics(
+                        &result.get_warning_messages(),
+                        source,
+                        source_path,
+                        &sources,
+                    )
+                } else {
+                    Vec::new()
+                };
 
-                Ok(GlslModule::from_string(result.as_text())?)
+                Ok((GlslModule::from_string(result.as_text())?, warnings))
             }
-            Err(shaderc::Error::CompilationError(num_errors, errors)) => {
-                if !self.compiler.skip_cargo {
-                    eprintln!("{}", errors);
-                }
-
-                return Err(Error::CompilationError(num_errors as usize, errors.clone()));
+            Err(shaderc::Error::CompilationError(_, errors)) => {
+                Err(self.compilation_error(&errors, source, source_path, &sources))
             }
             Err(error) => panic!(error.to_string()),
         }
@@ -0,0 +1,75 @@
+use std::collections::{HashMap, HashSet};
+
+use heck::CamelCase;
+
+use crate::types::{LayoutType, StructType};
+
+/// Registry of the struct types found in uniform blocks
+///
+/// Identical layouts across shaders are mapped to the same Rust type, which is only generated by
+/// the first shader that registered it.
+#[derive(Debug, Default)]
+pub struct StructRegistry {
+    names: HashMap<StructType, String>,
+    used_names: HashSet<String>,
+}
+
+impl StructRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a struct type and the struct types it contains
+    ///
+    /// # Parameters
+    ///
+    /// * `ty`: struct type to register
+    /// * `names`: map of struct types to Rust type names to update
+    /// * `new_structs`: list of struct types that were registered for the first time, nested
+    ///   types first
+    pub fn register(
+        &mut self,
+        ty: &StructType,
+        names: &mut HashMap<StructType, String>,
+        new_structs: &mut Vec<StructType>,
+    ) -> String {
+        if let Some(name) = self.names.get(ty) {
+            names.insert(ty.clone(), name.clone());
+            return name.clone();
+        }
+
+        for member in &ty.members {
+            let mut member_ty = &member.ty;
+            while let LayoutType::Array { element, .. } | LayoutType::RuntimeArray { element, .. } =
+                member_ty
+            {
+                member_ty = element;
+            }
+
+            if let LayoutType::Struct(inner) = member_ty {
+                self.register(inner, names, new_structs);
+            }
+        }
+
+        // Pick a unique name for this layout
+        let base_name = if ty.name.is_empty() {
+            "Block".to_owned()
+        } else {
+            ty.name.to_camel_case()
+        };
+
+        let mut name = base_name.clone();
+        let mut i = 2;
+        while self.used_names.contains(&name) {
+            name = format!("{}{}", base_name, i);
+            i += 1;
+        }
+
+        self.used_names.insert(name.clone());
+        self.names.insert(ty.clone(), name.clone());
+        names.insert(ty.clone(), name.clone());
+        new_structs.push(ty.clone());
+
+        name
+    }
+}
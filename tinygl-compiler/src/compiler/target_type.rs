@@ -20,6 +20,66 @@ pub enum GlslVersion {
     V3_00Es,
 }
 
+impl GlslVersion {
+    /// Version number, as written in a `#version` directive
+    pub fn number(self) -> u32 {
+        match self {
+            Self::V1_10 => 110,
+            Self::V1_20 => 120,
+            Self::V1_30 => 130,
+            Self::V1_40 => 140,
+            Self::V1_50 => 150,
+            Self::V3_30 => 330,
+            Self::V4_00 => 400,
+            Self::V4_10 => 410,
+            Self::V4_20 => 420,
+            Self::V4_30 => 430,
+            Self::V4_40 => 440,
+            Self::V4_50 => 450,
+            Self::V4_60 => 460,
+            Self::V1_00Es => 100,
+            Self::V3_00Es => 300,
+        }
+    }
+
+    /// Find a version from its number, as written in a `#version` directive
+    ///
+    /// # Parameters
+    ///
+    /// * `number`: version number
+    /// * `es`: true for OpenGL ES versions
+    pub fn from_number(number: u32, es: bool) -> Option<Self> {
+        if es {
+            match number {
+                100 => Some(Self::V1_00Es),
+                300 => Some(Self::V3_00Es),
+                _ => None,
+            }
+        } else {
+            match number {
+                110 => Some(Self::V1_10),
+                120 => Some(Self::V1_20),
+                130 => Some(Self::V1_30),
+                140 => Some(Self::V1_40),
+                150 => Some(Self::V1_50),
+                330 => Some(Self::V3_30),
+                400 => Some(Self::V4_00),
+                410 => Some(Self::V4_10),
+                420 => Some(Self::V4_20),
+                430 => Some(Self::V4_30),
+                440 => Some(Self::V4_40),
+                450 => Some(Self::V4_50),
+                460 => Some(Self::V4_60),
+                _ => None,
+            }
+        }
+    }
+
+    pub fn is_es(self) -> bool {
+        matches!(self, Self::V1_00Es | Self::V3_00Es)
+    }
+}
+
 impl fmt::Display for GlslVersion {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -66,24 +126,24 @@ impl From<spirv_cross::glsl::Version> for GlslVersion {
 }
 
 #[cfg(feature = "spirv_cross")]
-impl Into<spirv_cross::glsl::Version> for GlslVersion {
-    fn into(self) -> spirv_cross::glsl::Version {
-        match self {
-            Self::V1_10 => spirv_cross::glsl::Version::V1_10,
-            Self::V1_20 => spirv_cross::glsl::Version::V1_20,
-            Self::V1_30 => spirv_cross::glsl::Version::V1_30,
-            Self::V1_40 => spirv_cross::glsl::Version::V1_40,
-            Self::V1_50 => spirv_cross::glsl::Version::V1_50,
-            Self::V3_30 => spirv_cross::glsl::Version::V3_30,
-            Self::V4_00 => spirv_cross::glsl::Version::V4_00,
-            Self::V4_10 => spirv_cross::glsl::Version::V4_10,
-            Self::V4_20 => spirv_cross::glsl::Version::V4_20,
-            Self::V4_30 => spirv_cross::glsl::Version::V4_30,
-            Self::V4_40 => spirv_cross::glsl::Version::V4_40,
-            Self::V4_50 => spirv_cross::glsl::Version::V4_50,
-            Self::V4_60 => spirv_cross::glsl::Version::V4_60,
-            Self::V1_00Es => spirv_cross::glsl::Version::V1_00Es,
-            Self::V3_00Es => spirv_cross::glsl::Version::V3_00Es,
+impl From<GlslVersion> for spirv_cross::glsl::Version {
+    fn from(version: GlslVersion) -> Self {
+        match version {
+            GlslVersion::V1_10 => Self::V1_10,
+            GlslVersion::V1_20 => Self::V1_20,
+            GlslVersion::V1_30 => Self::V1_30,
+            GlslVersion::V1_40 => Self::V1_40,
+            GlslVersion::V1_50 => Self::V1_50,
+            GlslVersion::V3_30 => Self::V3_30,
+            GlslVersion::V4_00 => Self::V4_00,
+            GlslVersion::V4_10 => Self::V4_10,
+            GlslVersion::V4_20 => Self::V4_20,
+            GlslVersion::V4_30 => Self::V4_30,
+            GlslVersion::V4_40 => Self::V4_40,
+            GlslVersion::V4_50 => Self::V4_50,
+            GlslVersion::V4_60 => Self::V4_60,
+            GlslVersion::V1_00Es => Self::V1_00Es,
+            GlslVersion::V3_00Es => Self::V3_00Es,
         }
     }
 }
@@ -102,3 +162,28 @@ impl TargetType {
         }
     }
 }
+
+impl std::str::FromStr for TargetType {
+    type Err = crate::Error;
+
+    /// Parse a target type, either `spirv` or a GLSL version such as `glsl 330` or `glsl 300 es`
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let words: Vec<_> = s.split_whitespace().collect();
+
+        match words.as_slice() {
+            ["spirv"] => Some(TargetType::SpirV),
+            ["glsl", number] => number
+                .parse()
+                .ok()
+                .and_then(|number| GlslVersion::from_number(number, false))
+                .map(TargetType::Glsl),
+            ["glsl", number, "es"] => number
+                .parse()
+                .ok()
+                .and_then(|number| GlslVersion::from_number(number, true))
+                .map(TargetType::Glsl),
+            _ => None,
+        }
+        .ok_or_else(|| crate::Error::UnknownTargetType(s.to_owned()))
+    }
+}
@@ -4,6 +4,7 @@ use super::wrapped_shader::*;
 
 pub struct WrappedProgram<'s> {
     struct_name: String,
+    vertex_inputs_struct_name: String,
     attached_shaders: Vec<&'s dyn WrappedShaderDetails>,
 }
 
@@ -12,6 +13,7 @@ impl<'s> WrappedProgram<'s> {
         let struct_name = program_name.to_camel_case() + "Program";
 
         Self {
+            vertex_inputs_struct_name: struct_name.clone() + "VertexInputs",
             struct_name,
             attached_shaders: attached_shaders.to_vec(),
         }
@@ -21,6 +23,11 @@ impl<'s> WrappedProgram<'s> {
         &self.struct_name
     }
 
+    /// Name of the struct holding the vertex buffer bindings for this program
+    pub fn vertex_inputs_struct_name(&self) -> &str {
+        &self.vertex_inputs_struct_name
+    }
+
     pub fn shaders(&self) -> impl Iterator<Item = &&'s dyn WrappedShaderDetails> {
         self.attached_shaders.iter()
     }
@@ -30,4 +37,18 @@ impl<'s> WrappedProgram<'s> {
             .iter()
             .filter(|s| !s.uniforms().is_empty())
     }
+
+    /// Vertex shader inputs of this program
+    pub fn attributes(&self) -> impl Iterator<Item = &crate::reflect::FoundAttribute> {
+        self.attached_shaders
+            .iter()
+            .flat_map(|s| s.attributes().iter())
+    }
+
+    /// Fragment shader outputs of this program
+    pub fn outputs(&self) -> impl Iterator<Item = &crate::reflect::FoundOutput> {
+        self.attached_shaders
+            .iter()
+            .flat_map(|s| s.outputs().iter())
+    }
 }
@@ -1,7 +1,11 @@
+use std::collections::HashMap;
+
 use heck::{CamelCase, SnakeCase};
 
+use super::{CompileOptions, StructRegistry};
 use crate::model::ShaderObject;
 use crate::reflect::ReflectedObject;
+use crate::types::StructType;
 
 #[derive(Debug)]
 pub struct WrappedShader<T> {
@@ -12,13 +16,24 @@ pub struct WrappedShader<T> {
     shader_variable_name: String,
     uniform_struct_name: String,
     uniform_locations_name: String,
+    specialization_struct_name: String,
+
+    struct_names: HashMap<StructType, String>,
+    structs: Vec<StructType>,
 
     result: ReflectedObject<ShaderObject<T>>,
     prefer_spirv: bool,
+    strip_debug_info: bool,
+    cache_uniforms: bool,
 }
 
 impl<T> WrappedShader<T> {
-    pub fn new(result: ReflectedObject<ShaderObject<T>>, prefer_spirv: bool) -> Self {
+    pub fn new(
+        result: ReflectedObject<ShaderObject<T>>,
+        prefer_spirv: bool,
+        options: &CompileOptions,
+        registry: &mut StructRegistry,
+    ) -> Self {
         let shader: String = result
             .info()
             .source_path
@@ -28,19 +43,49 @@ impl<T> WrappedShader<T> {
             .to_string_lossy()
             .into();
 
-        let base_name = shader.replace(".", "_");
+        let mut struct_names = HashMap::new();
+        let mut structs = Vec::new();
+        for block in result.blocks() {
+            registry.register(&block.ty, &mut struct_names, &mut structs);
+        }
+
+        let name = shader.clone();
+        Self {
+            shader,
+            rs_file_name: String::new(),
+            shader_struct_name: String::new(),
+            shader_variable_name: String::new(),
+            uniform_struct_name: String::new(),
+            uniform_locations_name: String::new(),
+            specialization_struct_name: String::new(),
+            struct_names,
+            structs,
+            result,
+            prefer_spirv,
+            strip_debug_info: options.is_strip_debug_info(),
+            cache_uniforms: options.is_cache_uniforms(),
+        }
+        .with_name(&name)
+    }
+
+    /// Derive the names of the generated items from `name` instead of the shader file name
+    ///
+    /// # Parameters
+    ///
+    /// * `name`: base name of the generated items, e.g. `scene_vert` for `SceneVertShader`
+    pub fn with_name(self, name: &str) -> Self {
+        let base_name = name.replace(".", "_");
         let shader_struct_name = (base_name.to_owned() + "_shader").to_camel_case();
         let shader_variable_name = shader_struct_name.to_snake_case();
 
         Self {
-            shader,
             rs_file_name: base_name.to_owned() + ".rs",
             shader_struct_name,
             shader_variable_name,
             uniform_struct_name: (base_name.to_owned() + "_uniforms").to_camel_case(),
-            uniform_locations_name: (base_name + "_locations").to_snake_case(),
-            result,
-            prefer_spirv,
+            uniform_locations_name: (base_name.to_owned() + "_locations").to_snake_case(),
+            specialization_struct_name: (base_name + "_specialization").to_camel_case(),
+            ..self
         }
     }
 
@@ -48,24 +93,85 @@ impl<T> WrappedShader<T> {
         self.prefer_spirv
     }
 
+    /// true if debug instructions should be removed from the embedded binary
+    pub fn strip_debug_info(&self) -> bool {
+        self.strip_debug_info
+    }
+
+    /// true if generated uniform setters should skip unchanged values
+    pub fn cache_uniforms(&self) -> bool {
+        self.cache_uniforms
+    }
+
     pub fn result(&self) -> &ReflectedObject<ShaderObject<T>> {
         &self.result
     }
+
+    /// Struct types that should be generated along with this shader
+    pub fn structs(&self) -> &[StructType] {
+        &self.structs
+    }
+
+    /// Rust names of the struct types used by this shader
+    pub fn struct_names(&self) -> &HashMap<StructType, String> {
+        &self.struct_names
+    }
 }
 
 pub trait WrappedShaderDetails {
+    fn kind(&self) -> crate::ShaderKind;
+    fn output_patch_vertices(&self) -> Option<u32>;
+    fn local_size(&self) -> Option<&crate::reflect::FoundLocalSize>;
     fn uniforms(&self) -> &[crate::reflect::FoundUniform];
+    fn cache_uniforms(&self) -> bool;
+    fn blocks(&self) -> &[crate::reflect::FoundBlock];
+    fn attributes(&self) -> &[crate::reflect::FoundAttribute];
+    fn outputs(&self) -> &[crate::reflect::FoundOutput];
+    fn spec_constants(&self) -> &[crate::reflect::FoundSpecConstant];
     fn shader_variable_name(&self) -> &str;
     fn shader_struct_name(&self) -> &str;
     fn uniform_struct_name(&self) -> &str;
     fn uniform_locations_name(&self) -> &str;
+    fn specialization_struct_name(&self) -> &str;
 }
 
 impl<T> WrappedShaderDetails for WrappedShader<T> {
+    fn kind(&self) -> crate::ShaderKind {
+        self.result().info().kind
+    }
+
+    fn output_patch_vertices(&self) -> Option<u32> {
+        self.result().output_patch_vertices()
+    }
+
+    fn local_size(&self) -> Option<&crate::reflect::FoundLocalSize> {
+        self.result().local_size()
+    }
+
     fn uniforms(&self) -> &[crate::reflect::FoundUniform] {
         self.result().uniforms()
     }
 
+    fn cache_uniforms(&self) -> bool {
+        self.cache_uniforms
+    }
+
+    fn blocks(&self) -> &[crate::reflect::FoundBlock] {
+        self.result().blocks()
+    }
+
+    fn attributes(&self) -> &[crate::reflect::FoundAttribute] {
+        self.result().attributes()
+    }
+
+    fn outputs(&self) -> &[crate::reflect::FoundOutput] {
+        self.result().outputs()
+    }
+
+    fn spec_constants(&self) -> &[crate::reflect::FoundSpecConstant] {
+        self.result().spec_constants()
+    }
+
     fn shader_variable_name(&self) -> &str {
         &self.shader_variable_name
     }
@@ -81,4 +187,8 @@ impl<T> WrappedShaderDetails for WrappedShader<T> {
     fn uniform_locations_name(&self) -> &str {
         &self.uniform_locations_name
     }
+
+    fn specialization_struct_name(&self) -> &str {
+        &self.specialization_struct_name
+    }
 }
@@ -2,14 +2,14 @@ use thiserror::Error;
 
 use super::TargetType;
 
-use crate::GlslVersion;
+use crate::{Diagnostic, GlslVersion};
 
 #[derive(Debug, Error)]
 pub enum Error {
     #[error("i/o error: {0}")]
     Io(#[from] std::io::Error),
-    #[error("compilation error: {1}")]
-    CompilationError(usize, String),
+    #[error("compilation failed:\n{}", Diagnostic::render_all(.0))]
+    CompilationError(Vec<Diagnostic>),
     #[error("invalid target type for current arch: {0:?}")]
     InvalidTargetType(TargetType),
     #[error("cannot skip SPIR-V generation when the target is explicitely SPIR-V")]
@@ -32,12 +32,28 @@ pub enum Error {
     GlslParseError(#[from] glsl::parser::ParseError),
     #[error("could not determine type of shader object based on extension")]
     CouldNotDetermineType,
+    #[error("{0}: unknown shader type")]
+    UnknownShaderExtension(String),
     #[error("a SPIR-V object is required for this operation")]
     SpirVObjectRequired,
     #[error("a source object is required for this operation")]
     SourceObjectRequired,
     #[error("SPIR-V is not supported, enable the spirv feature")]
     SpirVDisabled,
+    #[error("{0}: included file not found, searched in: {}", .1.iter().map(|dir| dir.display().to_string()).collect::<Vec<_>>().join(", "))]
+    IncludeNotFound(String, Vec<std::path::PathBuf>),
+    #[error("include cycle: {}", .0.join(" -> "))]
+    IncludeCycle(Vec<String>),
+    #[error("unknown target type: {0}, expected spirv, glsl <version> or glsl <version> es")]
+    UnknownTargetType(String),
+    #[error("OUT_DIR is not set, this must be called from a build script")]
+    OutDirNotSet,
+    #[error("invalid feature key {0}: {1}")]
+    InvalidFeatureKey(String, String),
+    #[error("invalid manifest {}: {1}", .0.display())]
+    InvalidManifest(std::path::PathBuf, String),
+    #[error("{} item(s) failed to build:\n{}", .0.len(), .0.iter().map(|(name, error)| format!("{}: {}", name, error)).collect::<Vec<_>>().join("\n"))]
+    BuildFailed(Vec<(String, Error)>),
 }
 
 #[cfg(feature = "spirv_cross")]
@@ -3,6 +3,8 @@ pub mod codegen;
 mod compiler;
 mod errors;
 pub mod gl;
+#[cfg(feature = "manifest")]
+pub mod manifest;
 pub mod model;
 pub mod reflect;
 mod shader_kind;
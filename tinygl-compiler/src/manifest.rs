@@ -0,0 +1,339 @@
+//! Build script driver for shader manifests
+//!
+//! A manifest lists the shaders to compile, the programs composed from them and the uniform
+//! sets shared between programs:
+//!
+//! ```toml
+//! output = "shaders.rs"
+//! include_dirs = ["shaders/include"]
+//!
+//! [defines]
+//! MAX_LIGHTS = "8"
+//!
+//! [shaders]
+//! scene_vert = "shaders/scene.vert"
+//! scene_frag = { path = "shaders/scene.frag", defines = { USE_FOG = "1" } }
+//!
+//! [programs]
+//! scene = ["scene_vert", "scene_frag"]
+//!
+//! [uniform_sets]
+//! global = ["scene"]
+//! ```
+//!
+//! Generated types are named after the shader entries, so the same file can be listed several
+//! times with different defines. For GLSL targets, the embedded sources are transpiled from the
+//! compiled SPIR-V to the target version. Paths are relative to the directory of the manifest.
+//! The generated code is written to `OUT_DIR` by [`build`], and can be included with
+//! `include!(concat!(env!("OUT_DIR"), "/shaders.rs"))`.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+
+use crate::codegen::WrappedItem;
+use crate::model::GlslObject;
+use crate::{
+    reflect, CompileOptions, Compiler, Error, IncludeResolver, Result, ShaderCache, ShaderKind,
+    TargetType, WrappedShaderDetails,
+};
+
+/// Shader entry of a manifest, either a path or a table with a path and options
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum ShaderEntry {
+    Path(PathBuf),
+    Table {
+        path: PathBuf,
+        /// Shader stage, as a file extension (`vert`, `frag`, etc.)
+        kind: Option<String>,
+        /// Macros defined for this shader only
+        #[serde(default)]
+        defines: BTreeMap<String, String>,
+    },
+}
+
+impl ShaderEntry {
+    pub fn path(&self) -> &Path {
+        match self {
+            Self::Path(path) => path,
+            Self::Table { path, .. } => path,
+        }
+    }
+
+    /// Shader stage, if it should not be determined from the extension of the path
+    pub fn kind(&self) -> Result<Option<ShaderKind>> {
+        match self {
+            Self::Table {
+                kind: Some(kind), ..
+            } => ShaderKind::from_extension(kind)
+                .map(Some)
+                .ok_or_else(|| Error::UnknownShaderExtension(kind.clone())),
+            _ => Ok(None),
+        }
+    }
+
+    pub fn defines(&self) -> impl Iterator<Item = (&str, &str)> {
+        let defines = match self {
+            Self::Path(_) => None,
+            Self::Table { defines, .. } => Some(defines),
+        };
+
+        defines
+            .into_iter()
+            .flatten()
+            .map(|(name, value)| (name.as_str(), value.as_str()))
+    }
+}
+
+/// Manifest of the shaders to build
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    /// Path to the manifest, relative paths are resolved from its directory
+    #[serde(skip)]
+    path: PathBuf,
+    /// Directory of the compilation cache, if any
+    #[serde(skip)]
+    cache_dir: Option<PathBuf>,
+    /// Name of the generated file
+    #[serde(default = "default_output")]
+    pub output: String,
+    /// Target type, `spirv`, `glsl <version>` or `glsl <version> es`. The default depends on
+    /// the target architecture.
+    pub target: Option<String>,
+    /// Directories searched for included files
+    #[serde(default)]
+    pub include_dirs: Vec<PathBuf>,
+    /// Macros defined for all shaders
+    #[serde(default)]
+    pub defines: BTreeMap<String, String>,
+    #[serde(default)]
+    pub shaders: BTreeMap<String, ShaderEntry>,
+    /// Programs, as lists of shader names
+    #[serde(default)]
+    pub programs: BTreeMap<String, Vec<String>>,
+    /// Uniform sets, as lists of program names
+    #[serde(default)]
+    pub uniform_sets: BTreeMap<String, Vec<String>>,
+}
+
+fn default_output() -> String {
+    "shaders.rs".to_owned()
+}
+
+impl Manifest {
+    /// Load a manifest from a file
+    ///
+    /// # Parameters
+    ///
+    /// * `path`: path to the manifest
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        Self::parse(&std::fs::read_to_string(path)?, path)
+    }
+
+    /// Parse a manifest
+    ///
+    /// # Parameters
+    ///
+    /// * `source`: TOML source of the manifest
+    /// * `path`: path to the manifest, used to resolve relative paths
+    pub fn parse(source: &str, path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let mut manifest: Self = toml::from_str(source)
+            .map_err(|error| Error::InvalidManifest(path.clone(), error.to_string()))?;
+        manifest.path = path;
+        Ok(manifest)
+    }
+
+    /// Directory relative paths are resolved from
+    pub fn root(&self) -> &Path {
+        self.path.parent().unwrap_or_else(|| Path::new(""))
+    }
+
+    /// Target type requested by the manifest
+    pub fn target_type(&self) -> Result<Option<TargetType>> {
+        self.target.as_deref().map(str::parse).transpose()
+    }
+
+    /// Reuse the compiled shaders of previous builds
+    ///
+    /// # Parameters
+    ///
+    /// * `dir`: directory of the compilation cache
+    pub fn with_cache_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.cache_dir = Some(dir.into());
+        self
+    }
+
+    fn invalid(&self, message: String) -> Error {
+        Error::InvalidManifest(self.path.clone(), message)
+    }
+
+    /// Compile, reflect and wrap all the items of the manifest, and write the generated code
+    ///
+    /// Errors are collected for all items before returning, so a single build reports all the
+    /// shaders that failed to compile.
+    ///
+    /// # Parameters
+    ///
+    /// * `dest`: path to the generated file
+    /// * `skip_cargo`: true to skip printing cargo directives and warnings
+    pub fn write(&self, dest: impl AsRef<Path>, skip_cargo: bool) -> Result<()> {
+        let root = self.root();
+        let resolver = self
+            .include_dirs
+            .iter()
+            .fold(IncludeResolver::new(), |resolver, dir| {
+                resolver.system_dir(root.join(dir))
+            });
+        let options = self
+            .defines
+            .iter()
+            .fold(CompileOptions::new(), |options, (name, value)| {
+                options.define(name, Some(value))
+            });
+
+        let mut compiler = Compiler::new(skip_cargo, self.target_type()?)?
+            .with_options(options)
+            .with_include_resolver(resolver)
+            .with_shaderc();
+        if let Some(cache_dir) = &self.cache_dir {
+            compiler = compiler.with_cache(ShaderCache::new(cache_dir));
+        }
+
+        // Embedded sources must use the target version, whatever the version of the original
+        if let TargetType::Glsl(version) = compiler.output_type() {
+            compiler = compiler.with_transpile(version);
+        }
+
+        let prefer_spirv = !compiler.output_type().is_source();
+        let reflector = reflect::SpirVBackend::new();
+
+        let mut errors = Vec::new();
+
+        // Shaders
+        let mut shaders = BTreeMap::new();
+        for (name, entry) in &self.shaders {
+            let mut wrap = || -> Result<_> {
+                let mut object = GlslObject::from_path(root.join(entry.path()), entry.kind()?)?;
+                if !skip_cargo {
+                    object = object.track_cargo();
+                }
+
+                let options = entry
+                    .defines()
+                    .fold(compiler.options().clone(), |options, (name, value)| {
+                        options.define(name, Some(value))
+                    });
+                let object = compiler.compile_reflect(&object.with_options(options), &reflector)?;
+
+                if !skip_cargo {
+                    for warning in object.warnings() {
+                        warning.print_cargo_warning();
+                    }
+                }
+
+                // Named after the entry, since entries may share a file with different defines
+                Ok(compiler.wrap_shader(object, prefer_spirv)?.with_name(name))
+            };
+
+            match wrap() {
+                Ok(shader) => {
+                    shaders.insert(name.as_str(), shader);
+                }
+                Err(error) => errors.push((name.clone(), error)),
+            }
+        }
+
+        // Programs, skipping the ones with shaders that failed to build
+        let mut programs = BTreeMap::new();
+        for (name, shader_names) in &self.programs {
+            let mut attached: Vec<&dyn WrappedShaderDetails> = Vec::new();
+
+            for shader_name in shader_names {
+                match shaders.get(shader_name.as_str()) {
+                    Some(shader) => attached.push(shader),
+                    None if self.shaders.contains_key(shader_name) => {}
+                    None => errors.push((
+                        name.clone(),
+                        self.invalid(format!("unknown shader {}", shader_name)),
+                    )),
+                }
+            }
+
+            if attached.len() == shader_names.len() {
+                match compiler.wrap_program(&attached, name) {
+                    Ok(program) => {
+                        programs.insert(name.as_str(), program);
+                    }
+                    Err(error) => errors.push((name.clone(), error)),
+                }
+            }
+        }
+
+        // Uniform sets
+        let mut uniform_sets = Vec::new();
+        for (name, program_names) in &self.uniform_sets {
+            let mut attached = Vec::new();
+
+            for program_name in program_names {
+                match programs.get(program_name.as_str()) {
+                    Some(program) => attached.push(program),
+                    None if self.programs.contains_key(program_name) => {}
+                    None => errors.push((
+                        name.clone(),
+                        self.invalid(format!("unknown program {}", program_name)),
+                    )),
+                }
+            }
+
+            if attached.len() == program_names.len() {
+                match compiler.wrap_uniforms(&attached, name) {
+                    Ok(set) => uniform_sets.push(set),
+                    Err(error) => errors.push((name.clone(), error)),
+                }
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(Error::BuildFailed(errors));
+        }
+
+        let items: Vec<&dyn WrappedItem> = shaders
+            .values()
+            .map(|shader| shader as &dyn WrappedItem)
+            .chain(programs.values().map(|program| program as &dyn WrappedItem))
+            .chain(uniform_sets.iter().map(|set| set as &dyn WrappedItem))
+            .collect();
+
+        crate::codegen::write(dest, &items)
+    }
+}
+
+/// Build the shaders of a manifest from a build script
+///
+/// The generated code is written to `OUT_DIR`, in the file named by the `output` field of the
+/// manifest. Compiled shaders are cached in `OUT_DIR` so unchanged shaders are not compiled
+/// again.
+///
+/// # Parameters
+///
+/// * `manifest_path`: path to the manifest, usually relative to the crate root
+///
+/// # Returns
+///
+/// Path to the generated file.
+pub fn build(manifest_path: impl AsRef<Path>) -> Result<PathBuf> {
+    let manifest_path = manifest_path.as_ref();
+    println!("cargo:rerun-if-changed={}", manifest_path.display());
+
+    let out_dir = PathBuf::from(std::env::var_os("OUT_DIR").ok_or(Error::OutDirNotSet)?);
+    let manifest = Manifest::from_path(manifest_path)?.with_cache_dir(out_dir.join("tinygl-cache"));
+
+    let dest = out_dir.join(&manifest.output);
+    manifest.write(&dest, false)?;
+    Ok(dest)
+}
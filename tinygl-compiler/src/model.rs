@@ -1,8 +1,8 @@
 use std::path::{Path, PathBuf};
 
 use crate::{
-    reflect::{GlslReflectBackend, ReflectedObject},
-    Result, ShaderKind,
+    reflect::{GlslReflectBackend, ReflectedObject, ReflectionData},
+    CompileOptions, Diagnostic, Result, ShaderKind,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -45,13 +45,63 @@ pub struct ShaderObject<T> {
     module: T,
     /// Metadata for the module
     info: ObjectInfo,
+    /// Compile options overriding the ones of the compiler
+    options: Option<CompileOptions>,
+    /// Warnings reported while processing this object
+    warnings: Vec<Diagnostic>,
 }
 
 impl<T> ShaderObject<T> {
+    pub(crate) fn new(
+        module: T,
+        info: ObjectInfo,
+        options: Option<CompileOptions>,
+        warnings: Vec<Diagnostic>,
+    ) -> Self {
+        Self {
+            module,
+            info,
+            options,
+            warnings,
+        }
+    }
+
     pub fn info(&self) -> &ObjectInfo {
         &self.info
     }
 
+    /// Override the compile options of the compiler for this object
+    ///
+    /// # Parameters
+    ///
+    /// * `options`: options to use instead of the ones of the compiler
+    pub fn with_options(mut self, options: CompileOptions) -> Self {
+        self.options = Some(options);
+        self
+    }
+
+    pub fn options(&self) -> Option<&CompileOptions> {
+        self.options.as_ref()
+    }
+
+    /// Warnings reported by the compiler while processing this object
+    pub fn warnings(&self) -> &[Diagnostic] {
+        &self.warnings
+    }
+
+    /// Attach the results of reflection to this object
+    ///
+    /// Reflection warnings which do not refer to a file are attributed to this object.
+    fn reflected(self, mut data: ReflectionData) -> ReflectedObject<Self> {
+        let source_path = self.info.source_path.to_string();
+
+        for warning in &mut data.warnings {
+            warning.file.get_or_insert_with(|| source_path.clone());
+        }
+
+        ReflectedObject::new(self, data)
+    }
+
     pub fn track_cargo(self) -> Self {
         if let SourcePath::File(p) = &self.info.source_path {
             // Notify cargo to rerun if the source changes
@@ -77,15 +127,20 @@ pub trait GlslCompiler {
         source: &str,
         kind: ShaderKind,
         source_path: &str,
-    ) -> Result<SpirVModule<'static>>;
+        options: Option<&CompileOptions>,
+    ) -> Result<(SpirVModule<'static>, Vec<Diagnostic>)>;
 }
 
 pub trait GlslPreprocessor {
-    fn preprocess_module(&mut self, source: &str, source_path: &str)
-        -> Result<GlslModule<'static>>;
+    fn preprocess_module(
+        &mut self,
+        source: &str,
+        source_path: &str,
+        options: Option<&CompileOptions>,
+    ) -> Result<(GlslModule<'static>, Vec<Diagnostic>)>;
 }
 
-fn bytes_to_id(src: &[u8], kind: ShaderKind) -> ObjectInfo {
+pub(crate) fn bytes_to_id(src: &[u8], kind: ShaderKind) -> ObjectInfo {
     use sha2::Digest;
 
     let mut source_path = base64::encode(sha2::Sha256::digest(src));
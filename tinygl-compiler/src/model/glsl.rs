@@ -72,29 +72,10 @@ impl<'s> GlslModule<'s> {
 
             for decl in &ast.0 {
                 if let ExternalDeclaration::Preprocessor(Preprocessor::Version(version)) = decl {
-                    self.version = match version.profile {
-                        Some(PreprocessorVersionProfile::ES) => match version.version {
-                            100 => Some(GlslVersion::V1_00Es),
-                            300 => Some(GlslVersion::V3_00Es),
-                            _ => None,
-                        },
-                        _ => match version.version {
-                            110 => Some(GlslVersion::V1_10),
-                            120 => Some(GlslVersion::V1_20),
-                            130 => Some(GlslVersion::V1_30),
-                            140 => Some(GlslVersion::V1_40),
-                            150 => Some(GlslVersion::V1_50),
-                            330 => Some(GlslVersion::V3_30),
-                            400 => Some(GlslVersion::V4_00),
-                            410 => Some(GlslVersion::V4_10),
-                            420 => Some(GlslVersion::V4_20),
-                            430 => Some(GlslVersion::V4_30),
-                            440 => Some(GlslVersion::V4_40),
-                            450 => Some(GlslVersion::V4_50),
-                            460 => Some(GlslVersion::V4_60),
-                            _ => None,
-                        },
-                    };
+                    self.version = GlslVersion::from_number(
+                        version.version as u32,
+                        matches!(version.profile, Some(PreprocessorVersionProfile::ES)),
+                    );
                 }
             }
 
@@ -129,7 +110,7 @@ impl<'s> ShaderObject<GlslModule<'s>> {
 
         let kind = match kind {
             Some(kind) => kind.into(),
-            None => ShaderKind::from_path(&p).ok_or(crate::Error::CouldNotDetermineType)?,
+            None => ShaderKind::from_path(&p)?,
         };
 
         Ok(Self {
@@ -138,6 +119,8 @@ impl<'s> ShaderObject<GlslModule<'s>> {
                 kind,
                 source_path: SourcePath::File(p),
             },
+            options: None,
+            warnings: Vec::new(),
         })
     }
 
@@ -156,6 +139,8 @@ impl<'s> ShaderObject<GlslModule<'s>> {
         Ok(ShaderObject {
             module: GlslModule::from_string(source)?,
             info,
+            options: None,
+            warnings: Vec::new(),
         })
     }
 
@@ -171,6 +156,8 @@ impl<'s> ShaderObject<GlslModule<'s>> {
         Ok(Self {
             module: GlslModule::from_str(source)?,
             info,
+            options: None,
+            warnings: Vec::new(),
         })
     }
 
@@ -178,15 +165,21 @@ impl<'s> ShaderObject<GlslModule<'s>> {
         self,
         preprocessor: &mut dyn GlslPreprocessor,
     ) -> Result<ShaderObject<GlslModule<'static>>> {
-        let source_module = preprocessor.preprocess_module(
+        let (source_module, warnings) = preprocessor.preprocess_module(
             self.module.as_str(),
             self.info.source_path.to_string().as_str(),
+            self.options.as_ref(),
         )?;
 
-        Ok(ShaderObject {
+        let mut object = ShaderObject {
             module: source_module,
             info: self.info,
-        })
+            options: self.options,
+            warnings: self.warnings,
+        };
+
+        object.warnings.extend(warnings);
+        Ok(object)
     }
 
     #[cfg(feature = "spirv")]
@@ -194,21 +187,27 @@ impl<'s> ShaderObject<GlslModule<'s>> {
         self,
         compiler: &mut dyn GlslCompiler,
     ) -> Result<ShaderObject<GlslWithSpirVModule<'s, 'static>>> {
-        let binary_module = compiler.compile_module(
+        let (binary_module, warnings) = compiler.compile_module(
             self.module.as_str(),
             self.info.kind,
             self.info.source_path.to_string().as_str(),
+            self.options.as_ref(),
         )?;
 
-        Ok(ShaderObject {
+        let mut object = ShaderObject {
             module: GlslWithSpirVModule::new(self.module, binary_module),
             info: self.info,
-        })
+            options: self.options,
+            warnings: self.warnings,
+        };
+
+        object.warnings.extend(warnings);
+        Ok(object)
     }
 
     pub fn reflect(self, reflector: &dyn GlslReflectBackend) -> Result<ReflectedObject<Self>> {
-        let uniforms = reflector.reflect(self.module.as_str())?;
-        Ok(ReflectedObject::new(self, uniforms))
+        let data = reflector.reflect(self.module.as_str())?;
+        Ok(self.reflected(data))
     }
 }
 
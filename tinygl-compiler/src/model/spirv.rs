@@ -83,6 +83,59 @@ impl<'s> SpirVModule<'s> {
     pub fn module(&self) -> &rspirv::dr::Module {
         &self.module
     }
+
+    /// Create a copy of this module without debug instructions
+    ///
+    /// Names, sources and line information are removed, so the stripped module can't be
+    /// reflected on.
+    pub fn strip_debug_info(&self) -> SpirVModule<'static> {
+        use rspirv::binary::Assemble;
+        use rspirv::spirv::Op;
+
+        let is_line =
+            |inst: &rspirv::dr::Instruction| matches!(inst.class.opcode, Op::Line | Op::NoLine);
+
+        let mut module = self.module.clone();
+        module.debug_string_source.clear();
+        module.debug_names.clear();
+        module.debug_module_processed.clear();
+        module.types_global_values.retain(|inst| !is_line(inst));
+
+        for function in &mut module.functions {
+            for block in &mut function.blocks {
+                block.instructions.retain(|inst| !is_line(inst));
+            }
+        }
+
+        SpirVModule {
+            binary: Cow::Owned(module.assemble()),
+            module,
+        }
+    }
+
+    /// Write GLSL code equivalent to this module
+    ///
+    /// # Parameters
+    ///
+    /// * `version`: GLSL version of the generated code
+    #[cfg(feature = "transpile")]
+    pub fn transpile(
+        &self,
+        version: crate::GlslVersion,
+    ) -> crate::Result<glsl::GlslModule<'static>> {
+        // Use spirv_cross to write valid code
+        let module = spirv_cross::spirv::Module::from_words(self.as_bytes());
+        let mut ast = spirv_cross::spirv::Ast::<spirv_cross::glsl::Target>::parse(&module)?;
+
+        // Target the right GLSL version
+        ast.set_compiler_options(&spirv_cross::glsl::CompilerOptions {
+            version: version.into(),
+            ..Default::default()
+        })
+        .unwrap();
+
+        glsl::GlslModule::from_string(ast.compile()?)
+    }
 }
 
 impl<'s> ShaderObject<SpirVModule<'s>> {
@@ -97,7 +150,7 @@ impl<'s> ShaderObject<SpirVModule<'s>> {
 
         let kind = match kind {
             Some(kind) => kind.into(),
-            None => ShaderKind::from_path(&p).ok_or(crate::Error::CouldNotDetermineType)?,
+            None => ShaderKind::from_path(&p)?,
         };
 
         Ok(Self {
@@ -106,6 +159,8 @@ impl<'s> ShaderObject<SpirVModule<'s>> {
                 kind,
                 source_path: SourcePath::File(p),
             },
+            options: None,
+            warnings: Vec::new(),
         })
     }
 
@@ -124,12 +179,14 @@ impl<'s> ShaderObject<SpirVModule<'s>> {
         Ok(ShaderObject {
             module: SpirVModule::from_slice(binary)?,
             info,
+            options: None,
+            warnings: Vec::new(),
         })
     }
 
     pub fn reflect(self, reflector: &dyn SpirVReflectBackend) -> Result<ReflectedObject<Self>> {
-        let uniforms = reflector.reflect(&self.module.module)?;
-        Ok(ReflectedObject::new(self, uniforms))
+        let data = reflector.reflect(&self.module.module)?;
+        Ok(self.reflected(data))
     }
 
     #[cfg(feature = "transpile")]
@@ -137,19 +194,8 @@ impl<'s> ShaderObject<SpirVModule<'s>> {
         self,
         version: crate::GlslVersion,
     ) -> crate::Result<GlslWithSpirVModule<'static, 's>> {
-        // Use spirv_cross to write valid code
-        let module = spirv_cross::spirv::Module::from_words(self.module.as_bytes());
-        let mut ast = spirv_cross::spirv::Ast::<spirv_cross::glsl::Target>::parse(&module)?;
-
-        // Target the right GLSL version
-        ast.set_compiler_options(&spirv_cross::glsl::CompilerOptions {
-            version: version.into(),
-            ..Default::default()
-        })
-        .unwrap();
-
         Ok(GlslWithSpirVModule {
-            glsl: glsl::GlslModule::from_string(ast.compile()?)?,
+            glsl: self.module.transpile(version)?,
             spirv: self.module,
         })
     }
@@ -175,16 +221,16 @@ impl<'s, 't> GlslWithSpirVModule<'s, 't> {
 
 impl<'s, 't> ShaderObject<GlslWithSpirVModule<'s, 't>> {
     pub fn reflect_glsl(self, reflector: &dyn GlslReflectBackend) -> Result<ReflectedObject<Self>> {
-        let uniforms = reflector.reflect(&self.glsl.as_str())?;
-        Ok(ReflectedObject::new(self, uniforms))
+        let data = reflector.reflect(&self.glsl.as_str())?;
+        Ok(self.reflected(data))
     }
 
     pub fn reflect_spirv(
         self,
         reflector: &dyn SpirVReflectBackend,
     ) -> Result<ReflectedObject<Self>> {
-        let uniforms = reflector.reflect(&self.spirv.module)?;
-        Ok(ReflectedObject::new(self, uniforms))
+        let data = reflector.reflect(&self.spirv.module)?;
+        Ok(self.reflected(data))
     }
 }
 
@@ -1,6 +1,24 @@
+mod found_attribute;
+pub use found_attribute::*;
+
+mod found_block;
+pub use found_block::*;
+
+mod found_local_size;
+pub use found_local_size::*;
+
+mod found_output;
+pub use found_output::*;
+
+mod found_spec_constant;
+pub use found_spec_constant::*;
+
 mod found_uniform;
 pub use found_uniform::*;
 
+mod glsl;
+pub use self::glsl::*;
+
 mod null;
 pub use null::*;
 
@@ -10,25 +28,53 @@ mod spirv;
 pub use spirv::*;
 
 use crate::model::{ObjectInfo, ShaderObject};
+use crate::Diagnostic;
+
+/// Result of reflecting on a shader module
+#[derive(Debug, Default, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ReflectionData {
+    pub uniforms: Vec<FoundUniform>,
+    pub blocks: Vec<FoundBlock>,
+    pub attributes: Vec<FoundAttribute>,
+    pub outputs: Vec<FoundOutput>,
+    pub spec_constants: Vec<FoundSpecConstant>,
+    /// Number of vertices per output patch, for tessellation control shaders
+    pub output_patch_vertices: Option<u32>,
+    /// Local workgroup size, for compute shaders
+    pub local_size: Option<FoundLocalSize>,
+    /// Parts of the module which could not be reflected
+    pub warnings: Vec<Diagnostic>,
+}
 
 pub trait GlslReflectBackend {
-    fn reflect<'s>(&self, input: &str) -> crate::Result<Vec<FoundUniform>>;
+    fn reflect<'s>(&self, input: &str) -> crate::Result<ReflectionData>;
 }
 
 #[cfg(feature = "spirv")]
 pub trait SpirVReflectBackend {
-    fn reflect<'s>(&self, input: &rspirv::dr::Module) -> crate::Result<Vec<FoundUniform>>;
+    fn reflect<'s>(&self, input: &rspirv::dr::Module) -> crate::Result<ReflectionData>;
+
+    /// Name of the backend, which tells apart cached reflection data from different backends
+    fn name(&self) -> &'static str {
+        std::any::type_name::<Self>()
+    }
 }
 
 #[derive(Debug)]
 pub struct ReflectedObject<T> {
     object: T,
-    uniforms: Vec<FoundUniform>,
+    data: ReflectionData,
 }
 
 impl<T> ReflectedObject<T> {
-    pub fn new(object: T, uniforms: Vec<FoundUniform>) -> Self {
-        Self { object, uniforms }
+    pub fn new(object: T, data: ReflectionData) -> Self {
+        Self { object, data }
+    }
+
+    /// All the reflected data
+    pub fn data(&self) -> &ReflectionData {
+        &self.data
     }
 
     pub fn object(&self) -> &T {
@@ -36,7 +82,36 @@ impl<T> ReflectedObject<T> {
     }
 
     pub fn uniforms(&self) -> &[FoundUniform] {
-        &self.uniforms
+        &self.data.uniforms
+    }
+
+    pub fn blocks(&self) -> &[FoundBlock] {
+        &self.data.blocks
+    }
+
+    pub fn attributes(&self) -> &[FoundAttribute] {
+        &self.data.attributes
+    }
+
+    pub fn outputs(&self) -> &[FoundOutput] {
+        &self.data.outputs
+    }
+
+    pub fn output_patch_vertices(&self) -> Option<u32> {
+        self.data.output_patch_vertices
+    }
+
+    pub fn spec_constants(&self) -> &[FoundSpecConstant] {
+        &self.data.spec_constants
+    }
+
+    pub fn local_size(&self) -> Option<&FoundLocalSize> {
+        self.data.local_size.as_ref()
+    }
+
+    /// Warnings reported while reflecting on the object
+    pub fn reflection_warnings(&self) -> &[Diagnostic] {
+        &self.data.warnings
     }
 }
 
@@ -44,6 +119,11 @@ impl<T> ReflectedObject<ShaderObject<T>> {
     pub fn info(&self) -> &ObjectInfo {
         &self.object.info()
     }
+
+    /// Warnings reported while compiling and reflecting on the object
+    pub fn warnings(&self) -> impl Iterator<Item = &Diagnostic> {
+        self.object.warnings().iter().chain(&self.data.warnings)
+    }
 }
 
 impl<T> std::ops::Deref for ReflectedObject<T> {
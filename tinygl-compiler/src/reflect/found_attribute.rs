@@ -0,0 +1,37 @@
+use crate::types::{AtomType, GenericType};
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FoundAttribute {
+    /// Name of the vertex input, as declared in the shader
+    pub name: String,
+    /// Location of the first column of the input
+    pub location: u32,
+    pub ty: GenericType,
+}
+
+impl FoundAttribute {
+    /// Number of components per location
+    pub fn components(&self) -> u32 {
+        match self.ty {
+            GenericType::Atom(_) => 1,
+            GenericType::Vector(vector_type) => vector_type.components,
+            GenericType::Matrix(matrix_type) => matrix_type.rows,
+        }
+    }
+
+    /// Number of consecutive locations used by this input
+    pub fn locations(&self) -> u32 {
+        let columns = match self.ty {
+            GenericType::Matrix(matrix_type) => matrix_type.columns,
+            _ => 1,
+        };
+
+        // Double vectors of more than 2 components take two locations
+        if self.ty.base_type() == AtomType::Double && self.components() > 2 {
+            columns * 2
+        } else {
+            columns
+        }
+    }
+}
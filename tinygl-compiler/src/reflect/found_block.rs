@@ -0,0 +1,33 @@
+use crate::types::StructType;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum BlockKind {
+    /// Uniform block, laid out using std140
+    Uniform,
+    /// Shader storage block, laid out using std430
+    Storage,
+}
+
+impl BlockKind {
+    /// Name of the GL buffer target for this kind of block
+    pub fn target_constant_name(&self) -> &'static str {
+        match self {
+            Self::Uniform => "UNIFORM_BUFFER",
+            Self::Storage => "SHADER_STORAGE_BUFFER",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FoundBlock {
+    /// Name of the block, as declared in the shader
+    pub name: String,
+    /// Name of the block instance, empty if the block is anonymous
+    pub instance_name: String,
+    pub kind: BlockKind,
+    pub binding: Option<i32>,
+    /// Layout of the block contents
+    pub ty: StructType,
+}
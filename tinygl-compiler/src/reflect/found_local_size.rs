@@ -0,0 +1,52 @@
+/// Size of a compute workgroup along one axis
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LocalSizeDimension {
+    /// Size fixed at compile time
+    Literal(u32),
+    /// Size given by a specialization constant
+    Specialized { spec_id: u32, default_value: u32 },
+}
+
+impl LocalSizeDimension {
+    /// Size when the shader is not specialized
+    pub fn default_value(&self) -> u32 {
+        match self {
+            Self::Literal(value) => *value,
+            Self::Specialized { default_value, .. } => *default_value,
+        }
+    }
+
+    pub fn spec_id(&self) -> Option<u32> {
+        match self {
+            Self::Literal(_) => None,
+            Self::Specialized { spec_id, .. } => Some(*spec_id),
+        }
+    }
+}
+
+/// Local workgroup size of a compute shader
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FoundLocalSize {
+    /// Size along the x, y and z axes
+    pub dimensions: [LocalSizeDimension; 3],
+}
+
+impl FoundLocalSize {
+    /// Workgroup size when the shader is not specialized
+    pub fn default_size(&self) -> [u32; 3] {
+        [
+            self.dimensions[0].default_value(),
+            self.dimensions[1].default_value(),
+            self.dimensions[2].default_value(),
+        ]
+    }
+
+    /// true if the size depends on specialization constants
+    pub fn is_specialized(&self) -> bool {
+        self.dimensions
+            .iter()
+            .any(|dimension| dimension.spec_id().is_some())
+    }
+}
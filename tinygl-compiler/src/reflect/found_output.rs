@@ -0,0 +1,21 @@
+use crate::types::GenericType;
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FoundOutput {
+    /// Name of the fragment output, as declared in the shader
+    pub name: String,
+    /// Location of the output, i.e. its index in the draw buffers
+    pub location: u32,
+    pub ty: GenericType,
+}
+
+impl FoundOutput {
+    /// Number of components written by this output
+    pub fn components(&self) -> u32 {
+        match self.ty {
+            GenericType::Vector(vector_type) => vector_type.components,
+            _ => 1,
+        }
+    }
+}
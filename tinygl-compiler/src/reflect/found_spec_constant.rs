@@ -0,0 +1,21 @@
+use crate::types::AtomType;
+
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FoundSpecConstant {
+    /// Name of the specialization constant, as declared in the shader
+    pub name: String,
+    /// Constant id used to specialize the shader
+    pub spec_id: u32,
+    /// Scalar type of the constant
+    pub ty: AtomType,
+    /// Default value, as the bit pattern of a 32-bit scalar
+    pub default_value: u32,
+}
+
+impl FoundSpecConstant {
+    /// Name of the macro defining this constant in GLSL code transpiled by spirv_cross
+    pub fn macro_name(&self) -> String {
+        format!("SPIRV_CROSS_CONSTANT_ID_{}", self.spec_id)
+    }
+}
@@ -1,6 +1,7 @@
 use crate::types::ItemOrArrayType;
 
 #[derive(Debug, Default, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct FoundUniform {
     pub name: String,
     pub location: u32,
@@ -14,7 +15,8 @@ pub struct FoundUniform {
 impl FoundUniform {
     pub fn format(&self) -> Option<u32> {
         match self.ty {
-            Some(ItemOrArrayType::Image { format }) => format,
+            Some(ItemOrArrayType::Image { format })
+            | Some(ItemOrArrayType::ImageArray { format, .. }) => format,
             _ => None,
         }
     }
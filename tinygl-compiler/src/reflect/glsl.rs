@@ -0,0 +1,359 @@
+use heck::SnakeCase;
+
+use ::glsl::parser::Parse;
+use ::glsl::syntax::{
+    ArraySpecifier, ArraySpecifierDimension, Declaration, Expr, ExternalDeclaration,
+    LayoutQualifierSpec, StorageQualifier, TranslationUnit, TypeQualifier, TypeQualifierSpec,
+    TypeSpecifierNonArray,
+};
+
+use super::*;
+use crate::types::*;
+
+/// Reflection backend working on the GLSL source of a shader
+#[derive(Default)]
+pub struct GlslBackend {}
+
+impl GlslBackend {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn find_uniforms(
+        &self,
+        ast: &TranslationUnit,
+        warnings: &mut Vec<Diagnostic>,
+    ) -> crate::Result<Vec<FoundUniform>> {
+        let mut uniforms = Vec::new();
+
+        for external_declaration in &ast.0 {
+            let list = match external_declaration {
+                ExternalDeclaration::Declaration(Declaration::InitDeclaratorList(list)) => list,
+                _ => continue,
+            };
+
+            let qualifier = match &list.head.ty.qualifier {
+                Some(qualifier) if is_uniform(qualifier) => qualifier,
+                _ => continue,
+            };
+
+            let location = layout_value(qualifier, "location");
+            let binding = layout_value(qualifier, "binding");
+            let ty = &list.head.ty.ty;
+
+            // Each declared name may have its own array specifier
+            let names = list
+                .head
+                .name
+                .iter()
+                .map(|name| (name, list.head.array_specifier.as_ref()))
+                .chain(
+                    list.tail
+                        .iter()
+                        .map(|decl| (&decl.ident.ident, decl.ident.array_spec.as_ref())),
+                );
+
+            for (name, array_specifier) in names {
+                let name = name.as_str().to_owned();
+
+                // `float[3] a[2]` is an array of 2 arrays of 3 floats, so the dimensions of
+                // the declared name come first
+                let dimensions = array_specifier
+                    .into_iter()
+                    .chain(ty.array_specifier.as_ref())
+                    .map(array_dimensions)
+                    .collect::<Option<Vec<_>>>();
+
+                let found_ty = dimensions.and_then(|dimensions| {
+                    let item = item_type(&ty.ty, qualifier)?;
+                    Some(
+                        dimensions
+                            .into_iter()
+                            .flatten()
+                            .rev()
+                            .fold(item, ItemOrArrayType::array),
+                    )
+                });
+
+                match found_ty {
+                    Some(found_ty) => uniforms.push(FoundUniform {
+                        location_name: (name.clone() + "_location").to_snake_case(),
+                        name,
+                        location: location.unwrap_or(0) as u32,
+                        ty: Some(found_ty),
+                        binding,
+                    }),
+                    None => {
+                        warnings.push(Diagnostic::warning(format!(
+                            "{}: unsupported type, it will not be wrapped",
+                            name
+                        )));
+                    }
+                }
+            }
+        }
+
+        uniforms.sort_by_key(|item| item.location);
+        Ok(uniforms)
+    }
+}
+
+/// true if the qualifier declares a uniform variable
+fn is_uniform(qualifier: &TypeQualifier) -> bool {
+    qualifier
+        .qualifiers
+        .iter()
+        .any(|spec| matches!(spec, TypeQualifierSpec::Storage(StorageQualifier::Uniform)))
+}
+
+/// Find an identifier in the layout qualifiers of a declaration
+///
+/// # Parameters
+///
+/// * `qualifier`: type qualifier of the declaration
+/// * `name`: identifier to look for
+fn layout_qualifier<'q>(qualifier: &'q TypeQualifier, name: &str) -> Option<Option<&'q Expr>> {
+    qualifier
+        .qualifiers
+        .iter()
+        .filter_map(|spec| match spec {
+            TypeQualifierSpec::Layout(layout) => Some(layout),
+            _ => None,
+        })
+        .flat_map(|layout| layout.ids.iter())
+        .find_map(|id| match id {
+            LayoutQualifierSpec::Identifier(ident, value) if ident.as_str() == name => {
+                Some(value.as_deref())
+            }
+            _ => None,
+        })
+}
+
+/// Integer value of a layout qualifier, such as `location = 2`
+fn layout_value(qualifier: &TypeQualifier, name: &str) -> Option<i32> {
+    layout_qualifier(qualifier, name)?.and_then(const_value)
+}
+
+fn const_value(expr: &Expr) -> Option<i32> {
+    match expr {
+        Expr::IntConst(value) => Some(*value),
+        Expr::UIntConst(value) => Some(*value as i32),
+        _ => None,
+    }
+}
+
+/// Length of a one-dimensional array with a literal size
+fn array_dimensions(array: &ArraySpecifier) -> Option<Vec<u32>> {
+    array
+        .dimensions
+        .0
+        .iter()
+        .map(|dimension| match dimension {
+            ArraySpecifierDimension::ExplicitlySized(expr) => {
+                const_value(expr).map(|value| value as u32)
+            }
+            ArraySpecifierDimension::Unsized => None,
+        })
+        .collect()
+}
+
+/// Format of an image uniform, from its layout qualifiers
+fn image_format(qualifier: &TypeQualifier) -> Option<u32> {
+    const FORMATS: &[(&str, u32)] = &[
+        ("rgba32f", crate::gl::RGBA32F),
+        ("rgba16f", crate::gl::RGBA16F),
+        ("rg32f", crate::gl::RG32F),
+        ("rg16f", crate::gl::RG16F),
+        ("r11f_g11f_b10f", crate::gl::R11F_G11F_B10F),
+        ("r32f", crate::gl::R32F),
+        ("r16f", crate::gl::R16F),
+        ("rgba32ui", crate::gl::RGBA32UI),
+        ("rgba16ui", crate::gl::RGBA16UI),
+        ("rgb10_a2ui", crate::gl::RGB10_A2UI),
+        ("rgba8ui", crate::gl::RGBA8UI),
+        ("rg32ui", crate::gl::RG32UI),
+        ("rg16ui", crate::gl::RG16UI),
+        ("rg8ui", crate::gl::RG8UI),
+        ("r32ui", crate::gl::R32UI),
+        ("r16ui", crate::gl::R16UI),
+        ("r8ui", crate::gl::R8UI),
+        ("rgba32i", crate::gl::RGBA32I),
+        ("rgba16i", crate::gl::RGBA16I),
+        ("rgba8i", crate::gl::RGBA8I),
+        ("rg32i", crate::gl::RG32I),
+        ("rg16i", crate::gl::RG16I),
+        ("rg8i", crate::gl::RG8I),
+        ("r32i", crate::gl::R32I),
+        ("r16i", crate::gl::R16I),
+        ("r8i", crate::gl::R8I),
+        ("rgba16", crate::gl::RGBA16),
+        ("rgb10_a2", crate::gl::RGB10_A2),
+        ("rgba8", crate::gl::RGBA8),
+        ("rg16", crate::gl::RG16),
+        ("rg8", crate::gl::RG8),
+        ("r16", crate::gl::R16),
+        ("r8", crate::gl::R8),
+        ("rgba16_snorm", crate::gl::RGBA16_SNORM),
+        ("rgba8_snorm", crate::gl::RGBA8_SNORM),
+        ("rg16_snorm", crate::gl::RG16_SNORM),
+        ("rg8_snorm", crate::gl::RG8_SNORM),
+        ("r16_snorm", crate::gl::R16_SNORM),
+        ("r8_snorm", crate::gl::R8_SNORM),
+    ];
+
+    FORMATS
+        .iter()
+        .find(|(name, _)| layout_qualifier(qualifier, name).is_some())
+        .map(|(_, format)| *format)
+}
+
+/// Type of a non-array uniform
+fn item_type(ty: &TypeSpecifierNonArray, qualifier: &TypeQualifier) -> Option<ItemOrArrayType> {
+    use TypeSpecifierNonArray as T;
+
+    let atom = |atom_type| Some(ItemOrArrayType::atom(atom_type));
+    let vector = |atom_type, components| {
+        Some(ItemOrArrayType::Item(GenericType::Vector(VectorType {
+            base_type: atom_type,
+            components,
+        })))
+    };
+    let matrix = |atom_type, columns, rows| {
+        Some(ItemOrArrayType::Item(GenericType::Matrix(MatrixType {
+            base_type: atom_type,
+            columns,
+            rows,
+        })))
+    };
+
+    match ty {
+        T::Bool => atom(AtomType::Bool),
+        T::Int => atom(AtomType::Int),
+        T::UInt => atom(AtomType::UInt),
+        T::Float => atom(AtomType::Float),
+        T::Double => atom(AtomType::Double),
+        T::Vec2 => vector(AtomType::Float, 2),
+        T::Vec3 => vector(AtomType::Float, 3),
+        T::Vec4 => vector(AtomType::Float, 4),
+        T::DVec2 => vector(AtomType::Double, 2),
+        T::DVec3 => vector(AtomType::Double, 3),
+        T::DVec4 => vector(AtomType::Double, 4),
+        T::BVec2 => vector(AtomType::Bool, 2),
+        T::BVec3 => vector(AtomType::Bool, 3),
+        T::BVec4 => vector(AtomType::Bool, 4),
+        T::IVec2 => vector(AtomType::Int, 2),
+        T::IVec3 => vector(AtomType::Int, 3),
+        T::IVec4 => vector(AtomType::Int, 4),
+        T::UVec2 => vector(AtomType::UInt, 2),
+        T::UVec3 => vector(AtomType::UInt, 3),
+        T::UVec4 => vector(AtomType::UInt, 4),
+        T::Mat2 => matrix(AtomType::Float, 2, 2),
+        T::Mat3 => matrix(AtomType::Float, 3, 3),
+        T::Mat4 => matrix(AtomType::Float, 4, 4),
+        T::Mat23 => matrix(AtomType::Float, 2, 3),
+        T::Mat24 => matrix(AtomType::Float, 2, 4),
+        T::Mat32 => matrix(AtomType::Float, 3, 2),
+        T::Mat34 => matrix(AtomType::Float, 3, 4),
+        T::Mat42 => matrix(AtomType::Float, 4, 2),
+        T::Mat43 => matrix(AtomType::Float, 4, 3),
+        T::DMat2 => matrix(AtomType::Double, 2, 2),
+        T::DMat3 => matrix(AtomType::Double, 3, 3),
+        T::DMat4 => matrix(AtomType::Double, 4, 4),
+        T::DMat23 => matrix(AtomType::Double, 2, 3),
+        T::DMat24 => matrix(AtomType::Double, 2, 4),
+        T::DMat32 => matrix(AtomType::Double, 3, 2),
+        T::DMat34 => matrix(AtomType::Double, 3, 4),
+        T::DMat42 => matrix(AtomType::Double, 4, 2),
+        T::DMat43 => matrix(AtomType::Double, 4, 3),
+        T::Sampler1D
+        | T::Sampler2D
+        | T::Sampler3D
+        | T::SamplerCube
+        | T::Sampler2DRect
+        | T::Sampler1DArray
+        | T::Sampler2DArray
+        | T::SamplerBuffer
+        | T::Sampler2DMS
+        | T::Sampler2DMSArray
+        | T::SamplerCubeArray
+        | T::Sampler1DShadow
+        | T::Sampler2DShadow
+        | T::Sampler2DRectShadow
+        | T::Sampler1DArrayShadow
+        | T::Sampler2DArrayShadow
+        | T::SamplerCubeShadow
+        | T::SamplerCubeArrayShadow
+        | T::ISampler1D
+        | T::ISampler2D
+        | T::ISampler3D
+        | T::ISamplerCube
+        | T::ISampler2DRect
+        | T::ISampler1DArray
+        | T::ISampler2DArray
+        | T::ISamplerBuffer
+        | T::ISampler2DMS
+        | T::ISampler2DMSArray
+        | T::ISamplerCubeArray
+        | T::USampler1D
+        | T::USampler2D
+        | T::USampler3D
+        | T::USamplerCube
+        | T::USampler2DRect
+        | T::USampler1DArray
+        | T::USampler2DArray
+        | T::USamplerBuffer
+        | T::USampler2DMS
+        | T::USampler2DMSArray
+        | T::USamplerCubeArray => Some(ItemOrArrayType::Image { format: None }),
+        T::Image1D
+        | T::Image2D
+        | T::Image3D
+        | T::ImageCube
+        | T::Image2DRect
+        | T::Image1DArray
+        | T::Image2DArray
+        | T::ImageBuffer
+        | T::Image2DMS
+        | T::Image2DMSArray
+        | T::ImageCubeArray
+        | T::IImage1D
+        | T::IImage2D
+        | T::IImage3D
+        | T::IImageCube
+        | T::IImage2DRect
+        | T::IImage1DArray
+        | T::IImage2DArray
+        | T::IImageBuffer
+        | T::IImage2DMS
+        | T::IImage2DMSArray
+        | T::IImageCubeArray
+        | T::UImage1D
+        | T::UImage2D
+        | T::UImage3D
+        | T::UImageCube
+        | T::UImage2DRect
+        | T::UImage1DArray
+        | T::UImage2DArray
+        | T::UImageBuffer
+        | T::UImage2DMS
+        | T::UImage2DMSArray
+        | T::UImageCubeArray => Some(ItemOrArrayType::Image {
+            format: image_format(qualifier),
+        }),
+        _ => None,
+    }
+}
+
+impl GlslReflectBackend for GlslBackend {
+    fn reflect<'s>(&self, input: &str) -> crate::Result<ReflectionData> {
+        let ast = TranslationUnit::parse(input)?;
+
+        let mut warnings = Vec::new();
+
+        Ok(ReflectionData {
+            uniforms: self.find_uniforms(&ast, &mut warnings)?,
+            warnings,
+            ..Default::default()
+        })
+    }
+}
@@ -10,14 +10,14 @@ impl NullBackend {
 }
 
 impl GlslReflectBackend for NullBackend {
-    fn reflect<'s>(&self, _input: &str) -> crate::Result<Vec<FoundUniform>> {
-        Ok(vec![])
+    fn reflect<'s>(&self, _input: &str) -> crate::Result<ReflectionData> {
+        Ok(ReflectionData::default())
     }
 }
 
 #[cfg(feature = "spirv")]
 impl SpirVReflectBackend for NullBackend {
-    fn reflect<'s>(&self, _input: &rspirv::dr::Module) -> crate::Result<Vec<FoundUniform>> {
-        Ok(vec![])
+    fn reflect<'s>(&self, _input: &rspirv::dr::Module) -> crate::Result<ReflectionData> {
+        Ok(ReflectionData::default())
     }
 }
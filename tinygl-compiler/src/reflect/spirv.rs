@@ -1,8 +1,12 @@
+use std::collections::{HashMap, HashSet};
+
 use heck::SnakeCase;
 use rspirv::dr as rr;
+use rspirv::spirv::{Decoration, ExecutionMode, ExecutionModel, Op, StorageClass, Word};
 
 use super::*;
 use crate::types::*;
+use crate::Diagnostic;
 
 #[derive(Default)]
 pub struct SpirVBackend {}
@@ -12,7 +16,11 @@ impl SpirVBackend {
         Self::default()
     }
 
-    pub fn find_uniforms(&self, module: &rspirv::dr::Module) -> crate::Result<Vec<FoundUniform>> {
+    pub fn find_uniforms(
+        &self,
+        module: &rspirv::dr::Module,
+        warnings: &mut Vec<Diagnostic>,
+    ) -> crate::Result<Vec<FoundUniform>> {
         // Find names and locations
         let mut names: std::collections::HashMap<rspirv::spirv::Word, FoundUniform> =
             std::collections::HashMap::new();
@@ -78,30 +86,26 @@ impl SpirVBackend {
                     if let rr::Operand::IdRef(type_id) = type_global_value.operands[0] {
                         if let rr::Operand::LiteralInt32(components) = type_global_value.operands[1]
                         {
-                            types.insert(id, ItemOrArrayType::vector(types[&type_id], components));
+                            types.insert(
+                                id,
+                                ItemOrArrayType::vector(types[&type_id].clone(), components),
+                            );
                         }
                     }
                 }
                 rspirv::spirv::Op::TypeMatrix => {
                     if let rr::Operand::IdRef(type_id) = type_global_value.operands[0] {
-                        if let rr::Operand::LiteralInt32(n) = type_global_value.operands[1] {
+                        if let rr::Operand::LiteralInt32(columns) = type_global_value.operands[1] {
                             if let ItemOrArrayType::Item(GenericType::Vector(VectorType {
                                 base_type,
-                                components,
+                                ..
                             })) = types[&type_id]
                             {
                                 if base_type.is_float_type() {
-                                    if components == n {
-                                        types.insert(
-                                            id,
-                                            ItemOrArrayType::matrix(
-                                                ItemOrArrayType::atom(base_type),
-                                                components,
-                                            ),
-                                        );
-                                    } else {
-                                        // TODO: Support rectangular matrices
-                                    }
+                                    types.insert(
+                                        id,
+                                        ItemOrArrayType::matrix(types[&type_id].clone(), columns),
+                                    );
                                 }
                             }
                         }
@@ -110,19 +114,15 @@ impl SpirVBackend {
                 rspirv::spirv::Op::TypeArray => {
                     if let rr::Operand::IdRef(type_id) = type_global_value.operands[0] {
                         if let rr::Operand::IdRef(constant_id) = type_global_value.operands[1] {
-                            if types.get(&type_id).is_some() {
+                            // Arrays of structs are only supported in blocks
+                            if let Some(inner_type) = types.get(&type_id) {
                                 types.insert(
                                     id,
                                     ItemOrArrayType::array(
-                                        types[&type_id],
+                                        inner_type.clone(),
                                         constants[&constant_id],
                                     ),
                                 );
-                            } else {
-                                println!(
-                                    "cargo:warning=failed to discover array element type for {}",
-                                    type_id
-                                );
                             }
                         } else {
                             panic!("failed to get components");
@@ -237,15 +237,15 @@ impl SpirVBackend {
 
                             match types.get(&type_pointers[&tp]) {
                                 Some(ty) => {
-                                    v.ty = Some(*ty);
+                                    v.ty = Some(ty.clone());
                                     v.location_name =
                                         (v.name.clone() + "_location").to_snake_case();
                                 }
                                 None => {
-                                    println!(
-                                    "cargo:warning={}: unsupported type, it will not be wrapped",
-                                    v.name
-                                );
+                                    warnings.push(Diagnostic::warning(format!(
+                                        "{}: unsupported type, it will not be wrapped",
+                                        v.name
+                                    )));
                                 }
                             }
                         } else {
@@ -266,10 +266,708 @@ impl SpirVBackend {
         v.sort_by_key(|item| item.location);
         Ok(v)
     }
+
+    pub fn find_blocks(
+        &self,
+        module: &rspirv::dr::Module,
+        warnings: &mut Vec<Diagnostic>,
+    ) -> crate::Result<Vec<FoundBlock>> {
+        let layout = LayoutContext::new(module);
+
+        // Enumerate block decorations
+        let mut block_types = HashSet::new();
+        let mut buffer_block_types = HashSet::new();
+        let mut bindings = HashMap::new();
+
+        for annotation in &module.annotations {
+            if let (
+                Op::Decorate,
+                [rr::Operand::IdRef(id), rr::Operand::Decoration(decoration), rest @ ..],
+            ) = (annotation.class.opcode, annotation.operands.as_slice())
+            {
+                match (decoration, rest) {
+                    (Decoration::Block, _) => {
+                        block_types.insert(*id);
+                    }
+                    (Decoration::BufferBlock, _) => {
+                        buffer_block_types.insert(*id);
+                    }
+                    (Decoration::Binding, [rr::Operand::LiteralInt32(binding)]) => {
+                        bindings.insert(*id, *binding as i32);
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        let type_pointers = type_pointers(module);
+
+        // Find uniform and storage block variables
+        let mut blocks = Vec::new();
+
+        for type_global_value in &module.types_global_values {
+            if let (Op::Variable, Some(rr::Operand::StorageClass(storage_class))) = (
+                type_global_value.class.opcode,
+                type_global_value.operands.first(),
+            ) {
+                let storage_buffer = match storage_class {
+                    StorageClass::Uniform => false,
+                    StorageClass::StorageBuffer => true,
+                    _ => continue,
+                };
+
+                let id = type_global_value.result_id.unwrap();
+                let instance_name = layout.names.get(&id).cloned().unwrap_or_default();
+                let type_id = type_global_value
+                    .result_type
+                    .and_then(|tp| type_pointers.get(&tp))
+                    .copied()
+                    .unwrap_or(0);
+
+                // Storage blocks are either Uniform + BufferBlock (SPIR-V < 1.3), or
+                // StorageBuffer + Block
+                let kind = if block_types.contains(&type_id) {
+                    Some(if storage_buffer {
+                        BlockKind::Storage
+                    } else {
+                        BlockKind::Uniform
+                    })
+                } else if buffer_block_types.contains(&type_id) {
+                    Some(BlockKind::Storage)
+                } else {
+                    None
+                };
+
+                match kind.and_then(|kind| {
+                    layout
+                        .struct_type(type_id, kind == BlockKind::Storage)
+                        .map(|ty| (kind, ty))
+                }) {
+                    Some((kind, ty)) => blocks.push(FoundBlock {
+                        name: ty.name.clone(),
+                        instance_name,
+                        kind,
+                        binding: bindings.get(&id).copied(),
+                        ty,
+                    }),
+                    None => {
+                        warnings.push(Diagnostic::warning(format!(
+                            "{}: unsupported block type, it will not be wrapped",
+                            layout.names.get(&type_id).unwrap_or(&instance_name)
+                        )));
+                    }
+                }
+            }
+        }
+
+        blocks.sort_by_key(|block| (block.kind == BlockKind::Storage, block.binding));
+        Ok(blocks)
+    }
+
+    pub fn find_attributes(
+        &self,
+        module: &rspirv::dr::Module,
+        warnings: &mut Vec<Diagnostic>,
+    ) -> crate::Result<Vec<FoundAttribute>> {
+        // Only the inputs of vertex shaders are attributes
+        Ok(interface_variables(
+            module,
+            ExecutionModel::Vertex,
+            StorageClass::Input,
+            "vertex input",
+            warnings,
+        )
+        .into_iter()
+        .map(|(name, location, ty)| FoundAttribute { name, location, ty })
+        .collect())
+    }
+
+    pub fn find_outputs(
+        &self,
+        module: &rspirv::dr::Module,
+        warnings: &mut Vec<Diagnostic>,
+    ) -> crate::Result<Vec<FoundOutput>> {
+        Ok(interface_variables(
+            module,
+            ExecutionModel::Fragment,
+            StorageClass::Output,
+            "fragment output",
+            warnings,
+        )
+        .into_iter()
+        .map(|(name, location, ty)| FoundOutput { name, location, ty })
+        .collect())
+    }
+
+    pub fn find_output_patch_vertices(
+        &self,
+        module: &rspirv::dr::Module,
+    ) -> crate::Result<Option<u32>> {
+        // The output patch size is declared by the tessellation control stage
+        let entry_points: HashSet<_> = module
+            .entry_points
+            .iter()
+            .filter_map(|entry_point| match entry_point.operands.as_slice() {
+                [rr::Operand::ExecutionModel(ExecutionModel::TessellationControl), rr::Operand::IdRef(id), ..] => {
+                    Some(*id)
+                }
+                _ => None,
+            })
+            .collect();
+
+        Ok(module.execution_modes.iter().find_map(|execution_mode| {
+            match execution_mode.operands.as_slice() {
+                [rr::Operand::IdRef(id), rr::Operand::ExecutionMode(ExecutionMode::OutputVertices), rr::Operand::LiteralInt32(count)]
+                    if entry_points.contains(id) =>
+                {
+                    Some(*count)
+                }
+                _ => None,
+            }
+        }))
+    }
+
+    pub fn find_spec_constants(
+        &self,
+        module: &rspirv::dr::Module,
+        warnings: &mut Vec<Diagnostic>,
+    ) -> crate::Result<Vec<FoundSpecConstant>> {
+        let layout = LayoutContext::new(module);
+
+        // Only constants decorated with a SpecId can be specialized
+        let spec_ids = spec_ids(module);
+
+        let mut constants = Vec::new();
+
+        for type_global_value in &module.types_global_values {
+            let spec_id = match type_global_value.result_id.and_then(|id| spec_ids.get(&id)) {
+                Some(spec_id) => *spec_id,
+                None => continue,
+            };
+
+            let name = type_global_value
+                .result_id
+                .and_then(|id| layout.names.get(&id))
+                .cloned()
+                .unwrap_or_else(|| format!("spec_constant_{}", spec_id));
+
+            let ty = match type_global_value
+                .result_type
+                .and_then(|type_id| layout.layout_type(type_id, false))
+            {
+                Some(LayoutType::Item(GenericType::Atom(ty))) if ty != AtomType::Double => Some(ty),
+                _ => None,
+            };
+
+            // Only 32-bit scalars can be passed to glSpecializeShader
+            let default_value = match (
+                type_global_value.class.opcode,
+                type_global_value.operands.as_slice(),
+            ) {
+                (Op::SpecConstantTrue, _) => Some(1),
+                (Op::SpecConstantFalse, _) => Some(0),
+                (Op::SpecConstant, [rr::Operand::LiteralInt32(value)]) => Some(*value),
+                (Op::SpecConstant, [rr::Operand::LiteralFloat32(value)]) => Some(value.to_bits()),
+                _ => None,
+            };
+
+            match (ty, default_value) {
+                (Some(ty), Some(default_value)) => {
+                    constants.push(FoundSpecConstant {
+                        name,
+                        spec_id,
+                        ty,
+                        default_value,
+                    });
+                }
+                _ => {
+                    warnings.push(Diagnostic::warning(format!(
+                        "{}: unsupported specialization constant, it will not be wrapped",
+                        name
+                    )));
+                }
+            }
+        }
+
+        constants.sort_by_key(|constant| constant.spec_id);
+        Ok(constants)
+    }
+
+    pub fn find_local_size(
+        &self,
+        module: &rspirv::dr::Module,
+    ) -> crate::Result<Option<FoundLocalSize>> {
+        let entry_points: HashSet<_> = module
+            .entry_points
+            .iter()
+            .filter_map(|entry_point| match entry_point.operands.as_slice() {
+                [rr::Operand::ExecutionModel(ExecutionModel::GLCompute), rr::Operand::IdRef(id), ..] => {
+                    Some(*id)
+                }
+                _ => None,
+            })
+            .collect();
+
+        if entry_points.is_empty() {
+            return Ok(None);
+        }
+
+        let spec_ids = spec_ids(module);
+        let constants: HashMap<_, _> = module
+            .types_global_values
+            .iter()
+            .filter_map(|inst| inst.result_id.map(|id| (id, inst)))
+            .collect();
+
+        // Resolve a size given by the id of a constant
+        let dimension = |id: &Word| -> Option<LocalSizeDimension> {
+            let constant = constants.get(id)?;
+
+            match (constant.class.opcode, constant.operands.as_slice()) {
+                (Op::Constant, [rr::Operand::LiteralInt32(value)]) => {
+                    Some(LocalSizeDimension::Literal(*value))
+                }
+                (Op::SpecConstant, [rr::Operand::LiteralInt32(value)]) => {
+                    Some(match spec_ids.get(id) {
+                        Some(spec_id) => LocalSizeDimension::Specialized {
+                            spec_id: *spec_id,
+                            default_value: *value,
+                        },
+                        None => LocalSizeDimension::Literal(*value),
+                    })
+                }
+                _ => None,
+            }
+        };
+
+        let dimensions = |ids: &[Word]| -> Option<FoundLocalSize> {
+            match ids {
+                [x, y, z] => Some(FoundLocalSize {
+                    dimensions: [dimension(x)?, dimension(y)?, dimension(z)?],
+                }),
+                _ => None,
+            }
+        };
+
+        // The WorkgroupSize built-in overrides the execution mode, this is how glslang declares
+        // sizes given by specialization constants for SPIR-V 1.0
+        let workgroup_size = module.annotations.iter().find_map(|annotation| {
+            match (annotation.class.opcode, annotation.operands.as_slice()) {
+                (
+                    Op::Decorate,
+                    [rr::Operand::IdRef(id), rr::Operand::Decoration(Decoration::BuiltIn), rr::Operand::BuiltIn(rspirv::spirv::BuiltIn::WorkgroupSize)],
+                ) => {
+                    let ids: Vec<_> = constants
+                        .get(id)?
+                        .operands
+                        .iter()
+                        .filter_map(|operand| match operand {
+                            rr::Operand::IdRef(id) => Some(*id),
+                            _ => None,
+                        })
+                        .collect();
+
+                    dimensions(&ids)
+                }
+                _ => None,
+            }
+        });
+
+        if workgroup_size.is_some() {
+            return Ok(workgroup_size);
+        }
+
+        Ok(module.execution_modes.iter().find_map(|execution_mode| {
+            match execution_mode.operands.as_slice() {
+                [rr::Operand::IdRef(id), rr::Operand::ExecutionMode(ExecutionMode::LocalSize), rr::Operand::LiteralInt32(x), rr::Operand::LiteralInt32(y), rr::Operand::LiteralInt32(z)]
+                    if entry_points.contains(id) =>
+                {
+                    Some(FoundLocalSize {
+                        dimensions: [
+                            LocalSizeDimension::Literal(*x),
+                            LocalSizeDimension::Literal(*y),
+                            LocalSizeDimension::Literal(*z),
+                        ],
+                    })
+                }
+                [rr::Operand::IdRef(id), rr::Operand::ExecutionMode(ExecutionMode::LocalSizeId), sizes @ ..]
+                    if entry_points.contains(id) =>
+                {
+                    let ids: Vec<_> = sizes
+                        .iter()
+                        .filter_map(|operand| match operand {
+                            rr::Operand::IdRef(id) | rr::Operand::LiteralInt32(id) => Some(*id),
+                            _ => None,
+                        })
+                        .collect();
+
+                    dimensions(&ids)
+                }
+                _ => None,
+            }
+        }))
+    }
+}
+
+/// Map the ids of specialization constants to their SpecId decoration
+fn spec_ids(module: &rr::Module) -> HashMap<Word, u32> {
+    module
+        .annotations
+        .iter()
+        .filter_map(|annotation| {
+            match (annotation.class.opcode, annotation.operands.as_slice()) {
+                (
+                    Op::Decorate,
+                    [rr::Operand::IdRef(id), rr::Operand::Decoration(Decoration::SpecId), rr::Operand::LiteralInt32(spec_id)],
+                ) => Some((*id, *spec_id)),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Find the user-defined interface variables of an entry point
+///
+/// # Parameters
+///
+/// * `module`: module to reflect on
+/// * `execution_model`: stage the variables belong to, nothing is returned for other stages
+/// * `storage_class`: storage class of the variables (input or output)
+/// * `kind`: description of the variables for warnings
+/// * `warnings`: list to add warnings about unsupported variables to
+fn interface_variables(
+    module: &rr::Module,
+    execution_model: ExecutionModel,
+    storage_class: StorageClass,
+    kind: &str,
+    warnings: &mut Vec<Diagnostic>,
+) -> Vec<(String, u32, GenericType)> {
+    let has_stage = module.entry_points.iter().any(|entry_point| {
+        entry_point.operands.first() == Some(&rr::Operand::ExecutionModel(execution_model))
+    });
+
+    if !has_stage {
+        return Vec::new();
+    }
+
+    let layout = LayoutContext::new(module);
+
+    // Enumerate locations, skipping built-in variables such as gl_VertexID
+    let mut locations = HashMap::new();
+    let mut builtins = HashSet::new();
+
+    for annotation in &module.annotations {
+        if let (
+            Op::Decorate,
+            [rr::Operand::IdRef(id), rr::Operand::Decoration(decoration), rest @ ..],
+        ) = (annotation.class.opcode, annotation.operands.as_slice())
+        {
+            match (decoration, rest) {
+                (Decoration::Location, [rr::Operand::LiteralInt32(location)]) => {
+                    locations.insert(*id, *location);
+                }
+                (Decoration::BuiltIn, _) => {
+                    builtins.insert(*id);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let type_pointers = type_pointers(module);
+    let mut variables = Vec::new();
+
+    for type_global_value in &module.types_global_values {
+        if type_global_value.class.opcode != Op::Variable
+            || type_global_value.operands.first() != Some(&rr::Operand::StorageClass(storage_class))
+        {
+            continue;
+        }
+
+        let id = type_global_value.result_id.unwrap();
+        let type_id = type_global_value
+            .result_type
+            .and_then(|tp| type_pointers.get(&tp))
+            .copied()
+            .unwrap_or(0);
+
+        // Built-in blocks such as gl_PerVertex are decorated on their members
+        if builtins.contains(&id) || layout.members.contains_key(&(type_id, 0)) {
+            continue;
+        }
+
+        let name = layout.names.get(&id).cloned().unwrap_or_default();
+
+        match (locations.get(&id), layout.layout_type(type_id, false)) {
+            (Some(location), Some(LayoutType::Item(ty))) => {
+                variables.push((name, *location, ty));
+            }
+            _ => {
+                warnings.push(Diagnostic::warning(format!(
+                    "{}: unsupported {}, it will not be wrapped",
+                    name, kind
+                )));
+            }
+        }
+    }
+
+    variables.sort_by_key(|(_, location, _)| *location);
+    variables
+}
+
+/// Map pointer type ids to the id of their pointee type
+fn type_pointers(module: &rr::Module) -> HashMap<Word, Word> {
+    module
+        .types_global_values
+        .iter()
+        .filter(|instruction| instruction.class.opcode == Op::TypePointer)
+        .filter_map(|instruction| match instruction.operands.get(1) {
+            Some(rr::Operand::IdRef(type_id)) => Some((instruction.result_id?, *type_id)),
+            _ => None,
+        })
+        .collect()
+}
+
+#[derive(Default)]
+struct MemberDecorations {
+    offset: Option<u32>,
+    matrix_stride: Option<u32>,
+    row_major: bool,
+}
+
+/// Type information required to compute the layout of blocks
+#[derive(Default)]
+struct LayoutContext<'m> {
+    names: HashMap<Word, String>,
+    member_names: HashMap<(Word, u32), String>,
+    members: HashMap<(Word, u32), MemberDecorations>,
+    array_strides: HashMap<Word, u32>,
+    constants: HashMap<Word, u32>,
+    types: HashMap<Word, &'m rr::Instruction>,
+}
+
+impl<'m> LayoutContext<'m> {
+    fn new(module: &'m rr::Module) -> Self {
+        let mut layout = Self::default();
+
+        // Enumerate names of types, variables and struct members
+        for debug in &module.debug_names {
+            match (debug.class.opcode, debug.operands.as_slice()) {
+                (Op::Name, [rr::Operand::IdRef(id), rr::Operand::LiteralString(name)]) => {
+                    layout.names.insert(*id, name.to_owned());
+                }
+                (
+                    Op::MemberName,
+                    [rr::Operand::IdRef(id), rr::Operand::LiteralInt32(member), rr::Operand::LiteralString(name)],
+                ) => {
+                    layout.member_names.insert((*id, *member), name.to_owned());
+                }
+                _ => {}
+            }
+        }
+
+        // Enumerate layout decorations
+        for annotation in &module.annotations {
+            match (annotation.class.opcode, annotation.operands.as_slice()) {
+                (
+                    Op::Decorate,
+                    [rr::Operand::IdRef(id), rr::Operand::Decoration(Decoration::ArrayStride), rr::Operand::LiteralInt32(stride)],
+                ) => {
+                    layout.array_strides.insert(*id, *stride);
+                }
+                (
+                    Op::MemberDecorate,
+                    [rr::Operand::IdRef(id), rr::Operand::LiteralInt32(member), rr::Operand::Decoration(decoration), rest @ ..],
+                ) => {
+                    let entry = layout.members.entry((*id, *member)).or_default();
+
+                    match (decoration, rest) {
+                        (Decoration::Offset, [rr::Operand::LiteralInt32(offset)]) => {
+                            entry.offset = Some(*offset);
+                        }
+                        (Decoration::MatrixStride, [rr::Operand::LiteralInt32(stride)]) => {
+                            entry.matrix_stride = Some(*stride);
+                        }
+                        (Decoration::RowMajor, _) => {
+                            entry.row_major = true;
+                        }
+                        _ => {}
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        // Find types and constants
+        for type_global_value in &module.types_global_values {
+            let id = type_global_value.result_id.unwrap_or(0);
+
+            match type_global_value.class.opcode {
+                Op::Constant => {
+                    if let Some(rr::Operand::LiteralInt32(value)) =
+                        type_global_value.operands.first()
+                    {
+                        layout.constants.insert(id, *value);
+                    }
+                }
+                Op::TypePointer | Op::Variable => {}
+                _ => {
+                    layout.types.insert(id, type_global_value);
+                }
+            }
+        }
+
+        layout
+    }
+
+    fn layout_type(&self, id: Word, std430: bool) -> Option<LayoutType> {
+        let instruction = self.types.get(&id)?;
+
+        match (instruction.class.opcode, instruction.operands.as_slice()) {
+            (
+                Op::TypeInt,
+                [rr::Operand::LiteralInt32(32), rr::Operand::LiteralInt32(signedness)],
+            ) => Some(LayoutType::Item(GenericType::Atom(if *signedness == 0 {
+                AtomType::UInt
+            } else {
+                AtomType::Int
+            }))),
+            (Op::TypeFloat, [rr::Operand::LiteralInt32(32)]) => {
+                Some(LayoutType::Item(GenericType::Atom(AtomType::Float)))
+            }
+            (Op::TypeFloat, [rr::Operand::LiteralInt32(64)]) => {
+                Some(LayoutType::Item(GenericType::Atom(AtomType::Double)))
+            }
+            (Op::TypeBool, _) => Some(LayoutType::Item(GenericType::Atom(AtomType::Bool))),
+            (
+                Op::TypeVector,
+                [rr::Operand::IdRef(type_id), rr::Operand::LiteralInt32(components)],
+            ) => match self.layout_type(*type_id, std430)? {
+                LayoutType::Item(GenericType::Atom(base_type)) => {
+                    Some(LayoutType::Item(GenericType::Vector(VectorType {
+                        base_type,
+                        components: *components,
+                    })))
+                }
+                _ => None,
+            },
+            (Op::TypeMatrix, [rr::Operand::IdRef(type_id), rr::Operand::LiteralInt32(columns)]) => {
+                match self.layout_type(*type_id, std430)? {
+                    LayoutType::Item(GenericType::Vector(VectorType {
+                        base_type,
+                        components,
+                    })) if base_type.is_float_type() => {
+                        Some(LayoutType::Item(GenericType::Matrix(MatrixType {
+                            base_type,
+                            columns: *columns,
+                            rows: components,
+                        })))
+                    }
+                    _ => None,
+                }
+            }
+            (Op::TypeArray, [rr::Operand::IdRef(type_id), rr::Operand::IdRef(constant_id)]) => {
+                Some(LayoutType::Array {
+                    element: Box::new(self.element_type(*type_id, id, std430)?),
+                    len: *self.constants.get(constant_id)?,
+                    stride: *self.array_strides.get(&id)?,
+                })
+            }
+            (Op::TypeRuntimeArray, [rr::Operand::IdRef(type_id)]) => {
+                Some(LayoutType::RuntimeArray {
+                    element: Box::new(self.element_type(*type_id, id, std430)?),
+                    stride: *self.array_strides.get(&id)?,
+                })
+            }
+            (Op::TypeStruct, _) => Some(LayoutType::Struct(self.struct_type(id, std430)?)),
+            _ => None,
+        }
+    }
+
+    fn element_type(&self, type_id: Word, array_id: Word, std430: bool) -> Option<LayoutType> {
+        let element = self.layout_type(type_id, std430)?;
+
+        // Only scalars and vectors can be padded to the array stride, other elements must have
+        // a size that matches it
+        match element {
+            LayoutType::Item(GenericType::Atom(_)) | LayoutType::Item(GenericType::Vector(_)) => {
+                Some(element)
+            }
+            LayoutType::Struct(ref struct_type)
+                if struct_type.size != *self.array_strides.get(&array_id)? =>
+            {
+                None
+            }
+            element => Some(element),
+        }
+    }
+
+    fn struct_type(&self, id: Word, std430: bool) -> Option<StructType> {
+        let instruction = self.types.get(&id)?;
+        if instruction.class.opcode != Op::TypeStruct {
+            return None;
+        }
+
+        let mut members = Vec::with_capacity(instruction.operands.len());
+
+        for (i, operand) in instruction.operands.iter().enumerate() {
+            let i = i as u32;
+            let decorations = self.members.get(&(id, i))?;
+
+            members.push(StructMember {
+                name: self
+                    .member_names
+                    .get(&(id, i))
+                    .cloned()
+                    .unwrap_or_else(|| format!("member{}", i)),
+                offset: decorations.offset?,
+                ty: self.layout_type(operand.unwrap_id_ref(), std430)?,
+                matrix_stride: decorations.matrix_stride,
+                row_major: decorations.row_major,
+            });
+        }
+
+        let mut ty = StructType {
+            name: self.names.get(&id).cloned().unwrap_or_default(),
+            members,
+            size: 0,
+        };
+
+        if let Some(runtime_array) = ty.runtime_array() {
+            // The fixed part of the block ends where the runtime array starts
+            ty.size = runtime_array.offset;
+        } else {
+            // The size of a struct is rounded up to its base alignment
+            let alignment = LayoutType::Struct(ty.clone())
+                .alignment(std430, false)
+                .max(1);
+            let size = ty
+                .members
+                .iter()
+                .map(|member| member.offset + member.size())
+                .max()
+                .unwrap_or(0);
+
+            ty.size = (size + alignment - 1) & !(alignment - 1);
+        }
+
+        Some(ty)
+    }
 }
 
 impl SpirVReflectBackend for SpirVBackend {
-    fn reflect<'s>(&self, input: &rspirv::dr::Module) -> crate::Result<Vec<FoundUniform>> {
-        Ok(self.find_uniforms(input)?)
+    fn reflect<'s>(&self, input: &rspirv::dr::Module) -> crate::Result<ReflectionData> {
+        let mut warnings = Vec::new();
+
+        Ok(ReflectionData {
+            uniforms: self.find_uniforms(input, &mut warnings)?,
+            blocks: self.find_blocks(input, &mut warnings)?,
+            attributes: self.find_attributes(input, &mut warnings)?,
+            outputs: self.find_outputs(input, &mut warnings)?,
+            output_patch_vertices: self.find_output_patch_vertices(input)?,
+            local_size: self.find_local_size(input)?,
+            spec_constants: self.find_spec_constants(input, &mut warnings)?,
+            warnings,
+        })
     }
 }
@@ -1,8 +1,13 @@
 use std::path::Path;
 
+use crate::{Error, Result};
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum ShaderKind {
     Vertex,
+    TessControl,
+    TessEvaluation,
+    Geometry,
     Fragment,
     Compute,
 }
@@ -12,6 +17,9 @@ impl Into<shaderc::ShaderKind> for ShaderKind {
     fn into(self) -> shaderc::ShaderKind {
         match self {
             Self::Vertex => shaderc::ShaderKind::Vertex,
+            Self::TessControl => shaderc::ShaderKind::TessControl,
+            Self::TessEvaluation => shaderc::ShaderKind::TessEvaluation,
+            Self::Geometry => shaderc::ShaderKind::Geometry,
             Self::Fragment => shaderc::ShaderKind::Fragment,
             Self::Compute => shaderc::ShaderKind::Compute,
         }
@@ -19,24 +27,51 @@ impl Into<shaderc::ShaderKind> for ShaderKind {
 }
 
 impl ShaderKind {
-    pub fn from_path(p: impl AsRef<Path>) -> Option<Self> {
-        if let Some(ext) = p.as_ref().extension() {
-            return Some(match ext.to_str() {
-                Some("vert") => Self::Vertex,
-                Some("comp") => Self::Compute,
-                Some("frag") => Self::Fragment,
-
-                // TODO: Add other shader types
-                _ => panic!("{}: unknown shader type", p.as_ref().to_string_lossy()),
-            });
+    /// Determine the shader stage from the extension of a path
+    ///
+    /// The `.spv` extension is skipped, so `shader.vert.spv` is a vertex shader.
+    ///
+    /// # Parameters
+    ///
+    /// * `p`: path to the shader
+    pub fn from_path(p: impl AsRef<Path>) -> Result<Self> {
+        let p = p.as_ref();
+        let ext = p.extension().ok_or(Error::CouldNotDetermineType)?;
+
+        if ext == "spv" {
+            if let Some(stem) = p.file_stem() {
+                return Self::from_path(stem);
+            }
         }
 
-        None
+        ext.to_str()
+            .and_then(Self::from_extension)
+            .ok_or_else(|| Error::UnknownShaderExtension(p.to_string_lossy().into_owned()))
+    }
+
+    /// Determine the shader stage from a file extension
+    ///
+    /// # Parameters
+    ///
+    /// * `ext`: file extension, without the leading dot
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        match ext {
+            "vert" => Some(Self::Vertex),
+            "tesc" => Some(Self::TessControl),
+            "tese" => Some(Self::TessEvaluation),
+            "geom" => Some(Self::Geometry),
+            "frag" => Some(Self::Fragment),
+            "comp" => Some(Self::Compute),
+            _ => None,
+        }
     }
 
     pub fn constant_name(&self) -> &'static str {
         match self {
             Self::Vertex => "VERTEX_SHADER",
+            Self::TessControl => "TESS_CONTROL_SHADER",
+            Self::TessEvaluation => "TESS_EVALUATION_SHADER",
+            Self::Geometry => "GEOMETRY_SHADER",
             Self::Fragment => "FRAGMENT_SHADER",
             Self::Compute => "COMPUTE_SHADER",
         }
@@ -45,8 +80,16 @@ impl ShaderKind {
     pub fn extension(&self) -> &'static str {
         match self {
             Self::Vertex => "vert",
-            Self::Fragment => "comp",
-            Self::Compute => "frag",
+            Self::TessControl => "tesc",
+            Self::TessEvaluation => "tese",
+            Self::Geometry => "geom",
+            Self::Fragment => "frag",
+            Self::Compute => "comp",
         }
     }
+
+    /// true if this is a tessellation control or evaluation stage
+    pub fn is_tessellation(&self) -> bool {
+        matches!(self, Self::TessControl | Self::TessEvaluation)
+    }
 }
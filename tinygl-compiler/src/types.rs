@@ -1,6 +1,7 @@
 use std::fmt;
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AtomType {
     Int,
     Float,
@@ -17,6 +18,14 @@ impl AtomType {
         }
     }
 
+    /// Size in bytes of this type, in an explicitly laid out block
+    pub fn size(self) -> u32 {
+        match self {
+            Self::Double => 8,
+            _ => 4,
+        }
+    }
+
     pub fn glsl_base_type(&self) -> &'static str {
         match self {
             Self::Int => "int",
@@ -55,6 +64,7 @@ impl fmt::Display for AtomType {
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct VectorType {
     pub base_type: AtomType,
     pub components: u32,
@@ -80,18 +90,50 @@ impl fmt::Display for VectorType {
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MatrixType {
     pub base_type: AtomType,
-    pub n: u32,
+    pub columns: u32,
+    pub rows: u32,
 }
 
 impl MatrixType {
-    fn new(base_type: AtomType, n: u32) -> Self {
-        Self { base_type, n }
+    fn new(base_type: AtomType, columns: u32, rows: u32) -> Self {
+        Self {
+            base_type,
+            columns,
+            rows,
+        }
+    }
+
+    pub fn is_square(&self) -> bool {
+        self.columns == self.rows
+    }
+
+    /// Number of vectors and components per vector in memory
+    ///
+    /// # Parameters
+    ///
+    /// * `row_major`: true if the matrix is stored row by row
+    pub fn vectors(&self, row_major: bool) -> (u32, u32) {
+        if row_major {
+            (self.rows, self.columns)
+        } else {
+            (self.columns, self.rows)
+        }
     }
 
     pub fn glsl_mat_name(&self) -> String {
-        format!("{}{}", self.base_type.glsl_mat_name(), self.n)
+        if self.is_square() {
+            format!("{}{}", self.base_type.glsl_mat_name(), self.columns)
+        } else {
+            format!(
+                "{}{}x{}",
+                self.base_type.glsl_mat_name(),
+                self.columns,
+                self.rows
+            )
+        }
     }
 }
 
@@ -102,6 +144,7 @@ impl fmt::Display for MatrixType {
 }
 
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GenericType {
     Atom(AtomType),
     Vector(VectorType),
@@ -119,6 +162,15 @@ impl fmt::Display for GenericType {
 }
 
 impl GenericType {
+    /// Type of the scalar components of this type
+    pub fn base_type(&self) -> AtomType {
+        match self {
+            Self::Atom(atom_type) => *atom_type,
+            Self::Vector(vector_type) => vector_type.base_type,
+            Self::Matrix(matrix_type) => matrix_type.base_type,
+        }
+    }
+
     fn vector(inner_type: Self, components: u32) -> Self {
         match inner_type {
             Self::Atom(atom_type) if components > 1 => {
@@ -128,30 +180,57 @@ impl GenericType {
         }
     }
 
-    fn matrix(inner_type: Self, n: u32) -> Self {
-        match inner_type {
-            Self::Atom(atom_type) if n > 1 && atom_type.is_float_type() => {
-                Self::Matrix(MatrixType::new(atom_type, n))
+    fn matrix(column_type: Self, columns: u32) -> Self {
+        match column_type {
+            Self::Vector(VectorType {
+                base_type,
+                components,
+            }) if columns > 1 && base_type.is_float_type() => {
+                Self::Matrix(MatrixType::new(base_type, columns, components))
             }
             _ => panic!("unsupported type combination"),
         }
     }
 }
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ItemOrArrayType {
     Item(GenericType),
-    Array(GenericType, u32),
-    Image { format: Option<u32> },
+    /// Array of items, with the lengths of its dimensions from the outermost to the innermost
+    Array(GenericType, Vec<u32>),
+    Image {
+        format: Option<u32>,
+    },
+    /// Array of samplers or images, bound to consecutive units
+    ImageArray {
+        format: Option<u32>,
+        dimensions: Vec<u32>,
+    },
+}
+
+fn fmt_dimensions(f: &mut fmt::Formatter, dimensions: &[u32]) -> fmt::Result {
+    for len in dimensions {
+        write!(f, "[{}]", len)?;
+    }
+
+    Ok(())
 }
 
 impl fmt::Display for ItemOrArrayType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
             Self::Item(item_type) => fmt::Display::fmt(item_type, f),
-            Self::Array(item_type, components) => write!(f, "{}[{}]", item_type, components),
+            Self::Array(item_type, dimensions) => {
+                fmt::Display::fmt(item_type, f)?;
+                fmt_dimensions(f, dimensions)
+            }
             Self::Image { format: Some(fmt) } => write!(f, "image({})", fmt),
             Self::Image { format: None } => write!(f, "image(unknown format)"),
+            Self::ImageArray { format, dimensions } => {
+                fmt::Display::fmt(&Self::Image { format: *format }, f)?;
+                fmt_dimensions(f, dimensions)
+            }
         }
     }
 }
@@ -181,13 +260,179 @@ impl ItemOrArrayType {
         }
     }
 
-    pub fn array(inner_type: Self, components: u32) -> Self {
+    /// Array of `len` elements of `inner_type`, which may itself be an array
+    pub fn array(inner_type: Self, len: u32) -> Self {
+        let prepend = |dimensions: Vec<u32>| std::iter::once(len).chain(dimensions).collect();
+
         match inner_type {
-            Self::Item(inner) => Self::Array(inner, components),
-            _ => panic!(
-                "unsupported type combination: {:?}[{}]",
-                inner_type, components
+            Self::Item(inner) => Self::Array(inner, vec![len]),
+            Self::Array(inner, dimensions) => Self::Array(inner, prepend(dimensions)),
+            Self::Image { format } => Self::ImageArray {
+                format,
+                dimensions: vec![len],
+            },
+            Self::ImageArray { format, dimensions } => Self::ImageArray {
+                format,
+                dimensions: prepend(dimensions),
+            },
+        }
+    }
+
+    /// Lengths of the dimensions of this type, empty if it is not an array
+    pub fn dimensions(&self) -> &[u32] {
+        match self {
+            Self::Item(_) | Self::Image { .. } => &[],
+            Self::Array(_, dimensions) | Self::ImageArray { dimensions, .. } => dimensions,
+        }
+    }
+
+    /// Total number of elements of this type, 1 if it is not an array
+    pub fn element_count(&self) -> u32 {
+        self.dimensions().iter().product()
+    }
+
+    /// Number of elements in the innermost dimension of this type, 1 if it is not an array
+    pub fn inner_len(&self) -> u32 {
+        self.dimensions().last().copied().unwrap_or(1)
+    }
+
+    /// Type of the elements of this array, or this type if it is not an array
+    pub fn element_type(&self) -> Self {
+        match self {
+            Self::Array(inner, _) => Self::Item(*inner),
+            Self::ImageArray { format, .. } => Self::Image { format: *format },
+            other => other.clone(),
+        }
+    }
+
+    pub fn is_image(&self) -> bool {
+        matches!(self, Self::Image { .. } | Self::ImageArray { .. })
+    }
+}
+
+/// Type of a member of an explicitly laid out struct (i.e. a uniform or storage block)
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum LayoutType {
+    Item(GenericType),
+    Array {
+        element: Box<LayoutType>,
+        len: u32,
+        stride: u32,
+    },
+    /// Runtime-sized array, only valid as the last member of a storage block
+    RuntimeArray {
+        element: Box<LayoutType>,
+        stride: u32,
+    },
+    Struct(StructType),
+}
+
+impl LayoutType {
+    /// Size in bytes of a member of this type
+    ///
+    /// # Parameters
+    ///
+    /// * `matrix_stride`: stride between matrix columns (or rows), if any
+    /// * `row_major`: true if matrices are stored row by row
+    pub fn size(&self, matrix_stride: Option<u32>, row_major: bool) -> u32 {
+        match self {
+            Self::Item(GenericType::Atom(atom_type)) => atom_type.size(),
+            Self::Item(GenericType::Vector(vector_type)) => {
+                vector_type.base_type.size() * vector_type.components
+            }
+            Self::Item(GenericType::Matrix(matrix_type)) => {
+                let (vectors, components) = matrix_type.vectors(row_major);
+                let stride =
+                    matrix_stride.unwrap_or_else(|| matrix_type.base_type.size() * components);
+                stride * vectors
+            }
+            Self::Array { len, stride, .. } => len * stride,
+            Self::RuntimeArray { .. } => 0,
+            Self::Struct(struct_type) => struct_type.size,
+        }
+    }
+
+    /// Base alignment in bytes of a member of this type
+    ///
+    /// # Parameters
+    ///
+    /// * `std430`: true if the std430 layout rules apply, false for std140
+    /// * `row_major`: true if matrices are stored row by row
+    pub fn alignment(&self, std430: bool, row_major: bool) -> u32 {
+        // std140 rounds up the alignment of arrays and structs to the alignment of a vec4
+        let round = |alignment: u32| {
+            if std430 {
+                alignment
+            } else {
+                (alignment + 15) & !15
+            }
+        };
+
+        match self {
+            Self::Item(GenericType::Atom(atom_type)) => atom_type.size(),
+            Self::Item(GenericType::Vector(vector_type)) => {
+                vector_type.base_type.size() * if vector_type.components == 2 { 2 } else { 4 }
+            }
+            Self::Item(GenericType::Matrix(matrix_type)) => {
+                // Matrices are laid out as arrays of column (or row) vectors
+                let (_, components) = matrix_type.vectors(row_major);
+                round(
+                    Self::Item(GenericType::Vector(VectorType::new(
+                        matrix_type.base_type,
+                        components,
+                    )))
+                    .alignment(std430, row_major),
+                )
+            }
+            Self::Array { element, .. } | Self::RuntimeArray { element, .. } => {
+                round(element.alignment(std430, row_major))
+            }
+            Self::Struct(struct_type) => round(
+                struct_type
+                    .members
+                    .iter()
+                    .map(|member| member.ty.alignment(std430, member.row_major))
+                    .max()
+                    .unwrap_or(0),
             ),
         }
     }
 }
+
+/// Member of an explicitly laid out struct
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StructMember {
+    pub name: String,
+    pub offset: u32,
+    pub ty: LayoutType,
+    /// Stride between matrix columns (or rows for row-major matrices)
+    pub matrix_stride: Option<u32>,
+    pub row_major: bool,
+}
+
+impl StructMember {
+    pub fn size(&self) -> u32 {
+        self.ty.size(self.matrix_stride, self.row_major)
+    }
+}
+
+/// Explicitly laid out struct type, as found in uniform and storage blocks
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StructType {
+    pub name: String,
+    pub members: Vec<StructMember>,
+    /// Total size of the struct, including trailing padding but excluding any runtime-sized array
+    pub size: u32,
+}
+
+impl StructType {
+    /// Trailing runtime-sized array of this struct, if any
+    pub fn runtime_array(&self) -> Option<&StructMember> {
+        self.members
+            .last()
+            .filter(|member| matches!(member.ty, LayoutType::RuntimeArray { .. }))
+    }
+}
@@ -1,3 +1,32 @@
+/// Empty directory for the files written by a test, unique to the test and the test process
+#[cfg(feature = "cache")]
+fn test_dir(name: &str) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("tinygl_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Compile a shader to SPIR-V and reflect it
+#[cfg(all(feature = "spirv", feature = "backend-shaderc"))]
+fn reflect_shader(
+    source: &'static str,
+    kind: tinygl_compiler::ShaderKind,
+) -> tinygl_compiler::Result<
+    tinygl_compiler::reflect::ReflectedObject<
+        tinygl_compiler::model::ShaderObject<
+            tinygl_compiler::model::GlslWithSpirVModule<'static, 'static>,
+        >,
+    >,
+> {
+    use tinygl_compiler::{model::*, reflect, Compiler};
+
+    let mut compiler = Compiler::new(true, None)?.with_shaderc();
+    GlslObject::from_str(source, kind)?
+        .compile(&mut compiler)?
+        .reflect_spirv(&reflect::SpirVBackend::new())
+}
+
 #[cfg(all(feature = "spirv", feature = "backend-shaderc"))]
 #[test]
 fn test_codegen() -> tinygl_compiler::Result<()> {
@@ -19,9 +48,600 @@ fn test_codegen() -> tinygl_compiler::Result<()> {
 
     // Check reflection result
     assert_eq!(vert.uniforms().len(), 0);
+    assert_eq!(vert.attributes().len(), 0);
     assert_eq!(frag.uniforms().len(), 1);
 
     assert!(compiler.wrap_shader(vert, true)?.generate().is_ok());
 
     Ok(())
 }
+
+#[cfg(all(feature = "spirv", feature = "backend-shaderc"))]
+#[test]
+fn test_uniform_block() -> tinygl_compiler::Result<()> {
+    use tinygl_compiler::{codegen::WrappedItem, Compiler, ShaderKind};
+
+    let mut compiler = Compiler::new(true, None)?.with_shaderc();
+
+    let frag = reflect_shader(
+        include_str!("../../shaders/uniform_block.frag"),
+        ShaderKind::Fragment,
+    )?;
+
+    // Check the std140 layout of the block
+    assert_eq!(frag.uniforms().len(), 0);
+    assert_eq!(frag.blocks().len(), 1);
+
+    let block = &frag.blocks()[0];
+    assert_eq!(block.name, "Camera");
+    assert_eq!(block.binding, Some(1));
+    assert_eq!(
+        block
+            .ty
+            .members
+            .iter()
+            .map(|member| (member.name.as_str(), member.offset))
+            .collect::<Vec<_>>(),
+        vec![
+            ("viewMatrix", 0),
+            ("eye", 64),
+            ("exposure", 80),
+            ("lights", 128)
+        ]
+    );
+    assert_eq!(block.ty.members[0].matrix_stride, Some(16));
+    assert_eq!(block.ty.size, 160);
+
+    // The same block in another shader maps to the same Rust type
+    let other = reflect_shader(
+        include_str!("../../shaders/uniform_block.frag"),
+        ShaderKind::Fragment,
+    )?;
+
+    let frag = compiler.wrap_shader(frag, true)?;
+    let other = compiler.wrap_shader(other, true)?;
+
+    assert_eq!(frag.structs().len(), 2);
+    assert_eq!(other.structs().len(), 0);
+    assert!(other.generate().is_ok());
+
+    // Block structs can be stored in typed buffers
+    let tokens = frag.generate()?.to_string();
+    assert!(tokens.contains("wrappers :: Pod for Camera"));
+    assert!(tokens.contains("wrappers :: Buffer < B >"));
+
+    Ok(())
+}
+
+#[cfg(all(feature = "spirv", feature = "backend-shaderc"))]
+#[test]
+fn test_storage_block() -> tinygl_compiler::Result<()> {
+    use tinygl_compiler::{codegen::WrappedItem, reflect::BlockKind, Compiler, ShaderKind};
+
+    let mut compiler = Compiler::new(true, None)?.with_shaderc();
+
+    let comp = reflect_shader(
+        include_str!("../../shaders/storage_block.comp"),
+        ShaderKind::Compute,
+    )?;
+
+    // Check the std430 layout of the block
+    assert_eq!(comp.blocks().len(), 1);
+
+    let block = &comp.blocks()[0];
+    assert_eq!(block.name, "Particles");
+    assert_eq!(block.kind, BlockKind::Storage);
+    assert_eq!(block.binding, Some(0));
+    assert_eq!(block.ty.size, 16);
+
+    let runtime_array = block.ty.runtime_array().expect("missing runtime array");
+    assert_eq!(runtime_array.name, "particles");
+    assert_eq!(runtime_array.offset, 16);
+
+    let comp = compiler.wrap_shader(comp, true)?;
+    assert!(comp.generate().is_ok());
+
+    Ok(())
+}
+
+#[cfg(all(feature = "spirv", feature = "backend-shaderc"))]
+#[test]
+fn test_vertex_attributes() -> tinygl_compiler::Result<()> {
+    use tinygl_compiler::{
+        codegen::WrappedItem,
+        types::{AtomType, GenericType, MatrixType, VectorType},
+        Compiler, ShaderKind,
+    };
+
+    let mut compiler = Compiler::new(true, None)?.with_shaderc();
+
+    let vert = reflect_shader(include_str!("../../shaders/mesh.vert"), ShaderKind::Vertex)?;
+    let frag = reflect_shader(include_str!("../../shaders/uv.frag"), ShaderKind::Fragment)?;
+
+    // Built-in inputs are skipped, fragment inputs are not attributes
+    assert_eq!(frag.attributes().len(), 0);
+    assert_eq!(
+        vert.attributes()
+            .iter()
+            .map(|attribute| (attribute.name.as_str(), attribute.location, attribute.ty))
+            .collect::<Vec<_>>(),
+        vec![
+            (
+                "position",
+                0,
+                GenericType::Vector(VectorType {
+                    base_type: AtomType::Float,
+                    components: 3
+                })
+            ),
+            (
+                "texCoord",
+                1,
+                GenericType::Vector(VectorType {
+                    base_type: AtomType::Float,
+                    components: 2
+                })
+            ),
+            (
+                "joints",
+                2,
+                GenericType::Vector(VectorType {
+                    base_type: AtomType::Int,
+                    components: 4
+                })
+            ),
+            (
+                "model",
+                3,
+                GenericType::Matrix(MatrixType {
+                    base_type: AtomType::Float,
+                    columns: 4,
+                    rows: 4
+                })
+            ),
+            (
+                "offset",
+                7,
+                GenericType::Matrix(MatrixType {
+                    base_type: AtomType::Double,
+                    columns: 4,
+                    rows: 4
+                })
+            ),
+        ]
+    );
+    assert_eq!(vert.attributes()[3].locations(), 4);
+    // Columns of double matrices with more than 2 rows take two locations each
+    assert_eq!(vert.attributes()[4].locations(), 8);
+
+    let vert = compiler.wrap_shader(vert, true)?;
+    let frag = compiler.wrap_shader(frag, true)?;
+    let program = compiler.wrap_program(&[&vert, &frag], "mesh")?;
+
+    assert_eq!(program.attributes().count(), 5);
+    assert!(program.generate().is_ok());
+
+    Ok(())
+}
+
+#[cfg(all(feature = "spirv", feature = "backend-shaderc"))]
+#[test]
+fn test_fragment_outputs() -> tinygl_compiler::Result<()> {
+    use tinygl_compiler::{
+        codegen::WrappedItem,
+        types::{AtomType, GenericType, VectorType},
+        Compiler, ShaderKind,
+    };
+
+    let mut compiler = Compiler::new(true, None)?.with_shaderc();
+
+    let vert = reflect_shader(include_str!("../../shaders/quad.vert"), ShaderKind::Vertex)?;
+    let frag = reflect_shader(
+        include_str!("../../shaders/gbuffer.frag"),
+        ShaderKind::Fragment,
+    )?;
+
+    // Vertex outputs and built-in outputs are skipped
+    assert_eq!(vert.outputs().len(), 0);
+    assert_eq!(
+        frag.outputs()
+            .iter()
+            .map(|output| (output.name.as_str(), output.location, output.ty))
+            .collect::<Vec<_>>(),
+        vec![
+            (
+                "albedo",
+                0,
+                GenericType::Vector(VectorType {
+                    base_type: AtomType::Float,
+                    components: 4
+                })
+            ),
+            (
+                "normal",
+                1,
+                GenericType::Vector(VectorType {
+                    base_type: AtomType::Float,
+                    components: 3
+                })
+            ),
+            ("materialId", 3, GenericType::Atom(AtomType::UInt)),
+        ]
+    );
+
+    let vert = compiler.wrap_shader(vert, true)?;
+    let frag = compiler.wrap_shader(frag, true)?;
+    let program = compiler.wrap_program(&[&vert, &frag], "gbuffer")?;
+
+    assert_eq!(program.outputs().count(), 3);
+    assert!(program.generate().is_ok());
+
+    Ok(())
+}
+
+#[cfg(all(feature = "spirv", feature = "backend-shaderc"))]
+#[test]
+fn test_compute_local_size() -> tinygl_compiler::Result<()> {
+    use tinygl_compiler::{
+        codegen::WrappedItem, reflect::LocalSizeDimension, Compiler, ShaderKind,
+        WrappedShaderDetails,
+    };
+
+    let mut compiler = Compiler::new(true, None)?.with_shaderc();
+
+    let literal = reflect_shader(
+        include_str!("../../shaders/storage_block.comp"),
+        ShaderKind::Compute,
+    )?;
+
+    let local_size = literal.local_size().expect("missing local size");
+    assert_eq!(local_size.default_size(), [64, 1, 1]);
+    assert!(!local_size.is_specialized());
+
+    let specialized = reflect_shader(include_str!("../../shaders/blur.comp"), ShaderKind::Compute)?;
+
+    let local_size = specialized.local_size().expect("missing local size");
+    assert_eq!(local_size.default_size(), [16, 8, 1]);
+    assert_eq!(
+        local_size.dimensions[1],
+        LocalSizeDimension::Specialized {
+            spec_id: 1,
+            default_value: 8
+        }
+    );
+
+    let literal = compiler.wrap_shader(literal, true)?;
+    let specialized = compiler.wrap_shader(specialized, true)?;
+
+    let program = compiler.wrap_program(&[&literal as &dyn WrappedShaderDetails], "particles")?;
+    let tokens = program.generate()?.to_string();
+    assert!(tokens.contains("LOCAL_SIZE"));
+    assert!(tokens.contains("dispatch_for_size"));
+    assert!(tokens.contains("dispatch_indirect"));
+    assert!(!tokens.contains("get_program_compute_work_group_size"));
+
+    // Specialized sizes are queried after linking
+    let program = compiler.wrap_program(&[&specialized as &dyn WrappedShaderDetails], "blur")?;
+    let tokens = program.generate()?.to_string();
+    assert!(tokens.contains("get_program_compute_work_group_size"));
+
+    Ok(())
+}
+
+#[cfg(all(feature = "spirv", feature = "backend-shaderc"))]
+#[test]
+fn test_spec_constants() -> tinygl_compiler::Result<()> {
+    use tinygl_compiler::{codegen::WrappedItem, types::AtomType, Compiler, ShaderKind};
+
+    let mut compiler = Compiler::new(true, None)?.with_shaderc();
+
+    let frag = reflect_shader(
+        include_str!("../../shaders/specialized.frag"),
+        ShaderKind::Fragment,
+    )?;
+
+    // Constants are sorted by id, defaults are stored as 32-bit patterns
+    assert_eq!(
+        frag.spec_constants()
+            .iter()
+            .map(|constant| (
+                constant.name.as_str(),
+                constant.spec_id,
+                constant.ty,
+                constant.default_value
+            ))
+            .collect::<Vec<_>>(),
+        vec![
+            ("sampleCount", 0, AtomType::Int, 4),
+            ("useFog", 1, AtomType::Bool, 1),
+            ("maxLights", 2, AtomType::UInt, 8),
+            ("fogDensity", 3, AtomType::Float, 0.5f32.to_bits()),
+        ]
+    );
+
+    let frag = compiler.wrap_shader(frag, true)?;
+    let tokens = frag.generate()?.to_string();
+
+    assert!(tokens.contains("SpecializedFragSpecialization"));
+    assert!(tokens.contains("build_with"));
+
+    Ok(())
+}
+
+#[cfg(all(feature = "spirv", feature = "backend-shaderc"))]
+#[test]
+fn test_permutations() -> tinygl_compiler::Result<()> {
+    use tinygl_compiler::{
+        codegen::WrappedItem, model::*, reflect, Compiler, FeatureKey, ShaderKind,
+        WrappedShaderDetails,
+    };
+
+    let mut compiler = Compiler::new(true, None)?.with_shaderc();
+    let reflector = reflect::SpirVBackend::new();
+
+    let vert = GlslObject::from_str(include_str!("../../shaders/quad.vert"), ShaderKind::Vertex)?;
+    let frag = GlslObject::from_str(include_str!("../../shaders/lit.frag"), ShaderKind::Fragment)?;
+
+    let permutations = compiler.wrap_permutations(
+        "lit",
+        &[&vert, &frag],
+        &[
+            FeatureKey::Bool("SHADOWS".to_owned()),
+            FeatureKey::Enum(
+                "QUALITY".to_owned(),
+                vec!["low".to_owned(), "high".to_owned()],
+            ),
+            // Not used by the shaders, so it should not produce new shaders
+            FeatureKey::Bool("DEBUG".to_owned()),
+        ],
+        &reflector,
+        true,
+    )?;
+
+    assert_eq!(permutations.variant_count(), 8);
+
+    // One vertex shader and 4 fragment shaders
+    assert_eq!(permutations.shaders().len(), 5);
+    assert_eq!(
+        permutations.variant_shaders(0),
+        permutations.variant_shaders(4)
+    );
+
+    // The uniform only exists when SHADOWS is enabled
+    assert_eq!(permutations.variant_values(1), vec![1, 0, 0]);
+    assert!(permutations
+        .variant_shaders(1)
+        .iter()
+        .any(|index| permutations.shaders()[*index].uniforms().len() == 1));
+    assert!(permutations
+        .variant_shaders(0)
+        .iter()
+        .all(|index| permutations.shaders()[*index].uniforms().is_empty()));
+
+    let tokens = permutations.generate()?.to_string();
+    assert!(tokens.contains("LitProgramVariant"));
+    assert!(tokens.contains("LitProgramQuality"));
+    assert!(tokens.contains("LitPrograms"));
+    assert!(tokens.contains("GlDrop for LitPrograms"));
+
+    // Keys which can't be turned into permutations are rejected
+    for keys in [
+        vec![FeatureKey::Enum("QUALITY".to_owned(), vec![])],
+        vec![
+            FeatureKey::Bool("SHADOWS".to_owned()),
+            FeatureKey::Bool("SHADOWS".to_owned()),
+        ],
+    ] {
+        assert!(matches!(
+            compiler.wrap_permutations("lit", &[&vert, &frag], &keys, &reflector, true),
+            Err(tinygl_compiler::Error::InvalidFeatureKey(..))
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "cache")]
+#[test]
+fn test_permutations_cache() -> tinygl_compiler::Result<()> {
+    use tinygl_compiler::{model::*, reflect, Compiler, FeatureKey, ShaderCache, ShaderKind};
+
+    let cache_dir = test_dir("test_permutations_cache");
+    let reflector = reflect::SpirVBackend::new();
+
+    let vert = GlslObject::from_str(include_str!("../../shaders/quad.vert"), ShaderKind::Vertex)?;
+    let frag = GlslObject::from_str(include_str!("../../shaders/lit.frag"), ShaderKind::Fragment)?;
+
+    let build = || -> tinygl_compiler::Result<(usize, usize, usize)> {
+        let mut compiler = Compiler::new(true, None)?
+            .with_shaderc()
+            .with_cache(ShaderCache::new(&cache_dir));
+
+        let permutations = compiler.wrap_permutations(
+            "lit",
+            &[&vert, &frag],
+            &[FeatureKey::Bool("SHADOWS".to_owned())],
+            &reflector,
+            true,
+        )?;
+        let cache = compiler.cache().unwrap();
+
+        Ok((permutations.shaders().len(), cache.hits(), cache.misses()))
+    };
+
+    // Every permutation of every stage is compiled once, then loaded from the cache
+    assert_eq!(build()?, (3, 0, 4));
+    assert_eq!(build()?, (3, 4, 0));
+
+    Ok(())
+}
+
+#[cfg(all(feature = "spirv", feature = "backend-shaderc"))]
+#[test]
+fn test_optimize_strip() -> tinygl_compiler::Result<()> {
+    use tinygl_compiler::{
+        codegen::WrappedItem, model::*, reflect, CompileOptions, Compiler, OptimizationLevel,
+        ShaderKind,
+    };
+
+    let mut compiler = Compiler::new(true, None)?
+        .with_options(
+            CompileOptions::new()
+                .optimize(OptimizationLevel::Size)
+                .strip_debug_info(true),
+        )
+        .with_shaderc();
+    let reflector = reflect::SpirVBackend::new();
+
+    let frag = GlslObject::from_str(include_str!("../../shaders/uv.frag"), ShaderKind::Fragment)?
+        .compile(&mut compiler)?
+        .reflect_spirv(&reflector)?;
+
+    // Names are still available for reflection
+    assert_eq!(frag.uniforms().len(), 1);
+    assert_eq!(frag.uniforms()[0].name, "uAlpha");
+
+    let spirv = frag.as_spirv().unwrap();
+    let stripped = spirv.strip_debug_info();
+    assert!(stripped.module().debug_names.is_empty());
+    assert!(stripped.as_bytes().len() < spirv.as_bytes().len());
+
+    let sizes = (spirv.as_bytes_u8().len(), stripped.as_bytes_u8().len());
+    let tokens = compiler.wrap_shader(frag, true)?.generate()?.to_string();
+    assert!(tokens.contains(&format!("SPIRV_ORIGINAL_SIZE : usize = {}usize", sizes.0)));
+    assert!(tokens.contains(&format!("SPIRV_SIZE : usize = {}usize", sizes.1)));
+
+    Ok(())
+}
+
+#[cfg(all(feature = "spirv", feature = "backend-shaderc"))]
+#[test]
+fn test_cache_uniforms() -> tinygl_compiler::Result<()> {
+    use tinygl_compiler::{
+        codegen::WrappedItem, model::*, reflect, CompileOptions, Compiler, ShaderKind,
+        WrappedShaderDetails,
+    };
+
+    let mut compiler = Compiler::new(true, None)?.with_shaderc();
+    let reflector = reflect::SpirVBackend::new();
+    let source = include_str!("../../shaders/uniform_arrays.frag");
+
+    let frag = GlslObject::from_str(source, ShaderKind::Fragment)?
+        .compile(&mut compiler)?
+        .reflect_spirv(&reflector)?;
+    let tokens = compiler.wrap_shader(frag, true)?.generate()?.to_string();
+    assert!(!tokens.contains("record_cache"));
+
+    // Arrays of arrays hold the location of each innermost array
+    assert!(tokens.contains("vec ! [Some (4) , Some (7)]"));
+
+    // Caching is opt-in, per shader object or for the whole compiler
+    let frag = GlslObject::from_str(source, ShaderKind::Fragment)?
+        .with_options(CompileOptions::new().cache_uniforms(true))
+        .compile(&mut compiler)?
+        .reflect_spirv(&reflector)?;
+    let frag = compiler.wrap_shader(frag, true)?;
+    let tokens = frag.generate()?.to_string();
+    assert!(tokens.contains("weights_cache"));
+    assert!(tokens.contains("record_cache"));
+    assert!(tokens.contains("invalidate_cache"));
+
+    let program = compiler.wrap_program(&[&frag as &dyn WrappedShaderDetails], "arrays")?;
+    let tokens = program.generate()?.to_string();
+    assert!(tokens.contains("uniform_cache_stats"));
+    assert!(tokens.contains("invalidate_uniform_cache"));
+
+    Ok(())
+}
+
+#[cfg(feature = "manifest")]
+#[test]
+fn test_manifest() -> tinygl_compiler::Result<()> {
+    use tinygl_compiler::{manifest::Manifest, Error};
+
+    // Paths are relative to the manifest, which lives next to the shaders
+    let manifest_path = concat!(env!("CARGO_MANIFEST_DIR"), "/../shaders/shaders.toml");
+
+    let manifest = Manifest::parse(
+        r#"
+        target = "spirv"
+
+        [shaders]
+        quad_vert = "quad.vert"
+        uv_frag = { path = "uv.frag", kind = "frag" }
+        uv_fog_frag = { path = "uv.frag", defines = { USE_FOG = "1" } }
+
+        [programs]
+        quad = ["quad_vert", "uv_frag"]
+
+        [uniform_sets]
+        global = ["quad"]
+        "#,
+        manifest_path,
+    )?;
+
+    let dest = test_dir("test_manifest").join("shaders.rs");
+    manifest.write(&dest, true)?;
+
+    let generated = std::fs::read_to_string(&dest)?;
+    assert!(generated.contains("QuadVertShader"));
+    assert!(generated.contains("UvFragShader"));
+    assert!(generated.contains("UvFogFragShader"));
+    assert!(generated.contains("QuadProgram"));
+
+    // Errors are reported for all the items at once
+    let manifest = Manifest::parse(
+        r#"
+        [shaders]
+        missing_vert = "missing.vert"
+        uv_frag = "uv.frag"
+
+        [programs]
+        quad = ["missing_vert", "uv_frag"]
+        other = ["uv_frag", "unknown"]
+        "#,
+        manifest_path,
+    )?;
+
+    match manifest.write(&dest, true) {
+        Err(Error::BuildFailed(errors)) => {
+            let names: Vec<_> = errors.iter().map(|(name, _)| name.as_str()).collect();
+            assert_eq!(names, vec!["missing_vert", "other"]);
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    // Unknown fields are rejected
+    assert!(Manifest::parse("shader = []", manifest_path).is_err());
+
+    Ok(())
+}
+
+#[cfg(feature = "manifest")]
+#[test]
+fn test_manifest_glsl_target() -> tinygl_compiler::Result<()> {
+    use tinygl_compiler::manifest::Manifest;
+
+    let manifest_path = concat!(env!("CARGO_MANIFEST_DIR"), "/../shaders/shaders.toml");
+
+    let manifest = Manifest::parse(
+        r#"
+        target = "glsl 300 es"
+
+        [shaders]
+        quad_vert = "quad.vert"
+        uv_frag = "uv.frag"
+        "#,
+        manifest_path,
+    )?;
+
+    let dest = test_dir("test_manifest_glsl_target").join("shaders.rs");
+    manifest.write(&dest, true)?;
+
+    // Sources are transpiled to the target version instead of keeping their own
+    let generated = std::fs::read_to_string(&dest)?;
+    assert!(generated.contains("#version 300 es"));
+    assert!(!generated.contains("#version 460"));
+
+    Ok(())
+}
@@ -52,3 +52,354 @@ fn test_basic_program() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn test_shader_kind_from_path() -> Result<()> {
+    assert_eq!(ShaderKind::from_path("quad.vert")?, ShaderKind::Vertex);
+    assert_eq!(
+        ShaderKind::from_path("patch.tesc")?,
+        ShaderKind::TessControl
+    );
+    assert_eq!(
+        ShaderKind::from_path("patch.tese")?,
+        ShaderKind::TessEvaluation
+    );
+    assert_eq!(ShaderKind::from_path("lines.geom")?, ShaderKind::Geometry);
+    assert_eq!(ShaderKind::from_path("uv.frag.spv")?, ShaderKind::Fragment);
+
+    // Unknown extensions are errors
+    assert!(ShaderKind::from_path("quad.glsl").is_err());
+    assert!(ShaderKind::from_path("quad").is_err());
+
+    for kind in &[
+        ShaderKind::Vertex,
+        ShaderKind::TessControl,
+        ShaderKind::TessEvaluation,
+        ShaderKind::Geometry,
+        ShaderKind::Fragment,
+        ShaderKind::Compute,
+    ] {
+        assert_eq!(ShaderKind::from_extension(kind.extension()), Some(*kind));
+    }
+
+    Ok(())
+}
+
+#[cfg(all(feature = "spirv", feature = "backend-shaderc"))]
+#[test]
+fn test_tessellation_program() -> Result<()> {
+    use tinygl_compiler::{codegen::WrappedItem, reflect, Compiler};
+
+    let mut compiler = Compiler::new(true, None)?.with_shaderc();
+    let reflector = reflect::SpirVBackend::new();
+
+    let tesc = GlslObject::from_str(
+        include_str!("../../shaders/patch.tesc"),
+        ShaderKind::TessControl,
+    )?
+    .compile(&mut compiler)?
+    .reflect_spirv(&reflector)?;
+    let tese = GlslObject::from_str(
+        include_str!("../../shaders/patch.tese"),
+        ShaderKind::TessEvaluation,
+    )?
+    .compile(&mut compiler)?
+    .reflect_spirv(&reflector)?;
+
+    // The patch size comes from the control stage
+    assert_eq!(tesc.output_patch_vertices(), Some(4));
+    assert_eq!(tese.output_patch_vertices(), None);
+
+    let tesc = compiler.wrap_shader(tesc, true)?;
+    let tese = compiler.wrap_shader(tese, true)?;
+    let program = compiler.wrap_program(&[&tesc, &tese], "patch")?;
+
+    assert!(tesc.generate().is_ok());
+
+    // The input patch size is not declared, so it is passed to the setter
+    let tokens = program.generate()?.to_string();
+    assert!(tokens.contains("OUTPUT_PATCH_VERTICES : i32 = 4i32"));
+    assert!(tokens.contains("count : i32"));
+
+    Ok(())
+}
+
+#[test]
+fn test_compile_options() {
+    use tinygl_compiler::{CompileOptions, GlslProfile};
+
+    let options = CompileOptions::new()
+        .define("PRODUCT_ID", Some("1"))
+        .define("PRODUCT_ID", Some("2"))
+        .define("USE_FOG", None)
+        .undefine("TINYGL")
+        .force_version(GlslVersion::V4_50, GlslProfile::Core);
+
+    // Later definitions replace earlier ones
+    assert_eq!(
+        options.macros().collect::<Vec<_>>(),
+        vec![("PRODUCT_ID", Some("2")), ("USE_FOG", None)]
+    );
+    assert_eq!(
+        options.forced_version(),
+        Some((GlslVersion::V4_50, GlslProfile::Core))
+    );
+    assert!(!options.is_warnings_as_errors());
+
+    // The default options identify tinygl builds
+    assert!(CompileOptions::default()
+        .macros()
+        .any(|(name, _)| name == "TINYGL"));
+}
+
+#[cfg(all(feature = "spirv", feature = "backend-shaderc"))]
+#[test]
+fn test_per_shader_options() -> Result<()> {
+    use tinygl_compiler::{reflect, CompileOptions, Compiler};
+
+    let mut compiler = Compiler::new(true, None)?
+        .with_options(CompileOptions::new().define("PRODUCT_ID", Some("1")))
+        .with_shaderc();
+    let reflector = reflect::SpirVBackend::new();
+    let source = include_str!("../../shaders/product.frag");
+
+    // Compiler-wide options
+    let frag = GlslObject::from_str(source, ShaderKind::Fragment)?
+        .compile(&mut compiler)?
+        .reflect_spirv(&reflector)?;
+    assert_eq!(frag.uniforms().len(), 0);
+
+    // Per-object overrides
+    let options = compiler.options().clone().define("PRODUCT_ID", Some("2"));
+    let frag = GlslObject::from_str(source, ShaderKind::Fragment)?
+        .with_options(options)
+        .compile(&mut compiler)?
+        .reflect_spirv(&reflector)?;
+    assert_eq!(frag.uniforms().len(), 1);
+
+    // Missing definitions are compilation errors
+    let options = compiler.options().clone().undefine("PRODUCT_ID");
+    assert!(GlslObject::from_str(source, ShaderKind::Fragment)?
+        .with_options(options)
+        .compile(&mut compiler)
+        .is_err());
+
+    Ok(())
+}
+
+#[test]
+fn test_include_resolver() -> Result<()> {
+    use std::path::PathBuf;
+    use tinygl_compiler::{IncludeResolver, IncludeType, MemoryFileSystem};
+
+    let resolver = IncludeResolver::new()
+        .quote_dir("project")
+        .system_dir("lib")
+        .file_system(
+            MemoryFileSystem::new()
+                .with_file("shaders/local.glsl", "// local")
+                .with_file("project/common.glsl", "// project")
+                .with_file("lib/common.glsl", "// lib")
+                .with_file("lib/noise.glsl", "// noise"),
+        );
+
+    // Quoted includes look next to the including file first
+    let local = resolver.resolve("local.glsl", IncludeType::Relative, "shaders/main.frag")?;
+    assert_eq!(local.path, PathBuf::from("shaders/local.glsl"));
+    assert!(!local.is_disk);
+
+    // Then in the quote directories, before the system directories
+    let common = resolver.resolve("common.glsl", IncludeType::Relative, "shaders/main.frag")?;
+    assert_eq!(common.content, "// project");
+
+    // Angled includes only use the system directories
+    let common = resolver.resolve("common.glsl", IncludeType::Standard, "shaders/main.frag")?;
+    assert_eq!(common.content, "// lib");
+    let noise = resolver.resolve(
+        "../lib/noise.glsl",
+        IncludeType::Relative,
+        "shaders/main.frag",
+    )?;
+    assert_eq!(noise.path, PathBuf::from("lib/noise.glsl"));
+    assert!(resolver
+        .resolve("local.glsl", IncludeType::Standard, "shaders/main.frag")
+        .is_err());
+
+    // Missing files report the searched directories
+    let error = resolver
+        .resolve("missing.glsl", IncludeType::Relative, "shaders/main.frag")
+        .unwrap_err()
+        .to_string();
+    assert_eq!(
+        error,
+        "missing.glsl: included file not found, searched in: shaders, project, lib"
+    );
+
+    Ok(())
+}
+
+#[cfg(all(feature = "spirv", feature = "backend-shaderc"))]
+#[test]
+fn test_virtual_includes() -> Result<()> {
+    use tinygl_compiler::{reflect, Compiler, IncludeResolver, MemoryFileSystem};
+
+    let files = MemoryFileSystem::new()
+        .with_file("lib/color.glsl", "uniform vec4 color;\n")
+        .with_file("lib/a.glsl", "#include <b.glsl>\n")
+        .with_file("lib/b.glsl", "#include <a.glsl>\n");
+
+    let mut compiler = Compiler::new(true, None)?
+        .with_include_resolver(IncludeResolver::new().system_dir("lib").file_system(files))
+        .with_shaderc();
+    let reflector = reflect::SpirVBackend::new();
+
+    let frag = GlslObject::from_str(
+        "#version 460 core\n#extension GL_GOOGLE_include_directive : require\n#include <color.glsl>\nlayout(location = 0) out vec4 o_FragColor;\nvoid main() { o_FragColor = color; }\n",
+        ShaderKind::Fragment,
+    )?
+    .compile(&mut compiler)?
+    .reflect_spirv(&reflector)?;
+    assert_eq!(frag.uniforms().len(), 1);
+
+    // Cycles are reported with the include chain
+    let error = GlslObject::from_str(
+        "#version 460 core\n#extension GL_GOOGLE_include_directive : require\n#include <a.glsl>\nvoid main() {}\n",
+        ShaderKind::Fragment,
+    )?
+    .compile(&mut compiler)
+    .err()
+    .expect("include cycle should not compile")
+    .to_string();
+    assert!(error.contains("include cycle"));
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_diagnostics() {
+    use tinygl_compiler::{Diagnostic, Severity};
+
+    let output = "shaders/main.frag:3: error: 'colr' : undeclared identifier\n\
+                  C:\\shaders\\lib.glsl:12:5: warning: unused variable\n\
+                  error: linking failed\n\
+                  2 errors generated.\n";
+    let diagnostics = Diagnostic::parse(output);
+
+    assert_eq!(diagnostics.len(), 3);
+    assert_eq!(diagnostics[0].severity, Severity::Error);
+    assert_eq!(diagnostics[0].file.as_deref(), Some("shaders/main.frag"));
+    assert_eq!(diagnostics[0].line, Some(3));
+    assert_eq!(diagnostics[0].column, None);
+    assert_eq!(diagnostics[0].message, "'colr' : undeclared identifier");
+    assert_eq!(diagnostics[1].severity, Severity::Warning);
+    assert_eq!(
+        diagnostics[1].file.as_deref(),
+        Some("C:\\shaders\\lib.glsl")
+    );
+    assert_eq!(
+        (diagnostics[1].line, diagnostics[1].column),
+        (Some(12), Some(5))
+    );
+    assert_eq!(diagnostics[2].file, None);
+
+    // Snippets show the offending source line
+    let source = "#version 460 core\nout vec4 color;\nvoid main() { color = colr; }\n";
+    assert_eq!(
+        diagnostics[0].clone().with_source(source).render(),
+        "error: 'colr' : undeclared identifier\n \
+         --> shaders/main.frag:3\n  \
+         |\n\
+         3 | void main() { color = colr; }\n  \
+         | ^^^^^^^^^^^^^^^^^^^^^^^^^^^^^"
+    );
+}
+
+#[cfg(all(feature = "spirv", feature = "backend-shaderc"))]
+#[test]
+fn test_compile_diagnostics() -> Result<()> {
+    use tinygl_compiler::{Compiler, Error, IncludeResolver, MemoryFileSystem};
+
+    let files =
+        MemoryFileSystem::new().with_file("lib/broken.glsl", "\nvec4 broken() { return colr; }\n");
+    let mut compiler = Compiler::new(true, None)?
+        .with_include_resolver(IncludeResolver::new().system_dir("lib").file_system(files))
+        .with_shaderc();
+
+    let error = GlslObject::from_str(
+        "#version 460 core\n#extension GL_GOOGLE_include_directive : require\n#include <broken.glsl>\nvoid main() {}\n",
+        ShaderKind::Fragment,
+    )?
+    .compile(&mut compiler)
+    .err()
+    .expect("undeclared identifier should not compile");
+
+    // Errors point to the included file
+    match error {
+        Error::CompilationError(diagnostics) => {
+            let diagnostic = diagnostics.iter().find(|d| d.is_error()).unwrap();
+            assert_eq!(diagnostic.file.as_deref(), Some("lib/broken.glsl"));
+            assert_eq!(diagnostic.line, Some(2));
+            assert_eq!(
+                diagnostic.source_line.as_deref(),
+                Some("vec4 broken() { return colr; }")
+            );
+        }
+        other => panic!("unexpected error: {}", other),
+    }
+
+    Ok(())
+}
+
+#[test]
+fn test_parse_target_type() {
+    use tinygl_compiler::TargetType;
+
+    assert_eq!("spirv".parse::<TargetType>().ok(), Some(TargetType::SpirV));
+    assert_eq!(
+        "glsl 330".parse::<TargetType>().ok(),
+        Some(TargetType::Glsl(GlslVersion::V3_30))
+    );
+    assert_eq!(
+        "glsl 300 es".parse::<TargetType>().ok(),
+        Some(TargetType::Glsl(GlslVersion::V3_00Es))
+    );
+    assert!("glsl 310 es".parse::<TargetType>().is_err());
+    assert!("hlsl".parse::<TargetType>().is_err());
+}
+
+#[cfg(feature = "cache")]
+#[test]
+fn test_shader_cache() -> Result<()> {
+    use tinygl_compiler::{reflect, Compiler, IncludeResolver, MemoryFileSystem, ShaderCache};
+
+    // Unique to this process, so concurrent test runs don't share entries
+    let cache_dir =
+        std::env::temp_dir().join(format!("tinygl_test_shader_cache_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&cache_dir);
+
+    let source = "#version 460 core\n#extension GL_GOOGLE_include_directive : require\n#include <color.glsl>\nlayout(location = 0) out vec4 o_FragColor;\nvoid main() { o_FragColor = color; }\n";
+    let reflector = reflect::SpirVBackend::new();
+
+    let build = |color: &str| -> Result<(usize, usize, usize)> {
+        let files = MemoryFileSystem::new().with_file("lib/color.glsl", color);
+        let mut compiler = Compiler::new(true, None)?
+            .with_include_resolver(IncludeResolver::new().system_dir("lib").file_system(files))
+            .with_shaderc()
+            .with_cache(ShaderCache::new(&cache_dir));
+
+        let object = GlslObject::from_str(source, ShaderKind::Fragment)?;
+        let frag = compiler.compile_reflect(&object, &reflector)?;
+        let cache = compiler.cache().unwrap();
+
+        Ok((frag.uniforms().len(), cache.hits(), cache.misses()))
+    };
+
+    // First build compiles, second one is loaded from the cache
+    assert_eq!(build("uniform vec4 color;\n")?, (1, 0, 1));
+    assert_eq!(build("uniform vec4 color;\n")?, (1, 1, 0));
+
+    // Changing an included file invalidates the entry
+    assert_eq!(build("const vec4 color = vec4(1.0);\n")?, (0, 0, 1));
+
+    Ok(())
+}
@@ -1,4 +1,9 @@
-use tinygl_compiler::{model::GlslObject, reflect, Compiler, Result, ShaderKind};
+use tinygl_compiler::{
+    model::GlslObject,
+    reflect,
+    types::{AtomType, GenericType, ItemOrArrayType, MatrixType},
+    Compiler, Result, ShaderKind,
+};
 
 fn find<'p>(
     program: &'p tinygl_compiler::WrappedProgram,
@@ -66,23 +71,21 @@ fn uniform_reflect() -> Result<()> {
         "testFloatMat2",
         "testFloatMat3",
         "testFloatMat4",
-        // Disabled: rectangular matrices not supported
-        //"testFloatMat2x3",
-        //"testFloatMat2x4",
-        //"testFloatMat3x2",
-        //"testFloatMat3x4",
-        //"testFloatMat4x2",
-        //"testFloatMat4x3",
+        "testFloatMat2x3",
+        "testFloatMat2x4",
+        "testFloatMat3x2",
+        "testFloatMat3x4",
+        "testFloatMat4x2",
+        "testFloatMat4x3",
         "testDoubleMat2",
         "testDoubleMat3",
         "testDoubleMat4",
-        // Disabled: rectangular matrices not supported
-        //"testDoubleMat2x3",
-        //"testDoubleMat2x4",
-        //"testDoubleMat3x2",
-        //"testDoubleMat3x4",
-        //"testDoubleMat4x2",
-        //"testDoubleMat4x3",
+        "testDoubleMat2x3",
+        "testDoubleMat2x4",
+        "testDoubleMat3x2",
+        "testDoubleMat3x4",
+        "testDoubleMat4x2",
+        "testDoubleMat4x3",
         "testSampler1D",
         "testSampler2D",
         "testSampler3D",
@@ -129,6 +132,16 @@ fn uniform_reflect() -> Result<()> {
         );
     }
 
+    // Rectangular matrices are columns x rows
+    assert_eq!(
+        find(&program, "testFloatMat2x3").and_then(|uniform| uniform.ty.clone()),
+        Some(ItemOrArrayType::Item(GenericType::Matrix(MatrixType {
+            base_type: AtomType::Float,
+            columns: 2,
+            rows: 3,
+        })))
+    );
+
     let set = compiler
         .wrap_uniforms(&[&program], "global")
         .expect("failed to wrap uniforms");
@@ -140,3 +153,120 @@ fn uniform_reflect() -> Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn uniform_reflect_glsl_backend() -> Result<()> {
+    let mut compiler = Compiler::new(true, None)?.with_shaderc();
+
+    let source = include_str!("../../shaders/uniform_reflect.frag");
+
+    let spirv_shader = GlslObject::from_str(source, ShaderKind::Fragment)?
+        .compile(&mut compiler)?
+        .reflect_spirv(&reflect::SpirVBackend::new())?;
+
+    let glsl_shader =
+        GlslObject::from_str(source, ShaderKind::Fragment)?.reflect(&reflect::GlslBackend::new())?;
+
+    let key = |uniform: &reflect::FoundUniform| {
+        (
+            uniform.name.clone(),
+            uniform.location,
+            uniform.ty.clone(),
+            uniform.binding,
+            uniform.location_name.clone(),
+        )
+    };
+
+    let mut spirv_uniforms: Vec<_> = spirv_shader.uniforms().iter().map(key).collect();
+    let mut glsl_uniforms: Vec<_> = glsl_shader.uniforms().iter().map(key).collect();
+    spirv_uniforms.sort_by(|a, b| a.0.cmp(&b.0));
+    glsl_uniforms.sort_by(|a, b| a.0.cmp(&b.0));
+
+    assert!(!glsl_uniforms.is_empty());
+    assert_eq!(spirv_uniforms, glsl_uniforms);
+
+    Ok(())
+}
+
+#[test]
+fn uniform_reflect_warnings() -> Result<()> {
+    let mut compiler = Compiler::new(true, None)?.with_shaderc();
+
+    let source = "#version 460 core\n\
+                  #extension GL_TINYGL_unknown_extension : warn\n\
+                  struct Light { vec3 position; vec3 color; };\n\
+                  layout(location = 0) uniform Light light;\n\
+                  layout(location = 0) out vec4 o_FragColor;\n\
+                  void main() { o_FragColor = vec4(light.color, 1.0); }\n";
+
+    let shader = GlslObject::from_str(source, ShaderKind::Fragment)?.compile(&mut compiler)?;
+
+    // Compiler warnings are kept on the object
+    assert!(shader
+        .warnings()
+        .iter()
+        .any(|warning| warning.message.contains("GL_TINYGL_unknown_extension")));
+
+    // Reflection warnings are attributed to the object
+    let shader = shader.reflect_spirv(&reflect::SpirVBackend::new())?;
+    let warning = shader
+        .reflection_warnings()
+        .iter()
+        .find(|warning| warning.message.contains("unsupported type"))
+        .expect("struct uniforms should not be wrapped");
+    assert_eq!(
+        warning.file.as_deref(),
+        Some(shader.info().source_path.to_string().as_str())
+    );
+    assert_eq!(
+        shader.warnings().count(),
+        shader.object().warnings().len() + 1
+    );
+
+    Ok(())
+}
+
+#[test]
+fn uniform_reflect_arrays() -> Result<()> {
+    use tinygl_compiler::codegen::WrappedItem;
+
+    let mut compiler = Compiler::new(true, None)?.with_shaderc();
+
+    let source = include_str!("../../shaders/uniform_arrays.frag");
+
+    let spirv_shader = GlslObject::from_str(source, ShaderKind::Fragment)?
+        .compile(&mut compiler)?
+        .reflect_spirv(&reflect::SpirVBackend::new())?;
+
+    let glsl_shader =
+        GlslObject::from_str(source, ShaderKind::Fragment)?.reflect(&reflect::GlslBackend::new())?;
+
+    let find = |uniforms: &[reflect::FoundUniform], name: &str| {
+        uniforms
+            .iter()
+            .find(|uniform| uniform.name == name)
+            .and_then(|uniform| uniform.ty.clone())
+    };
+
+    // Both backends agree on the dimensions, outermost first
+    for uniforms in [spirv_shader.uniforms(), glsl_shader.uniforms()].iter() {
+        let offsets = find(uniforms, "offsets").expect("missing uniform: offsets");
+        assert_eq!(offsets.dimensions(), &[2, 3]);
+        assert_eq!(offsets.element_count(), 6);
+        assert_eq!(offsets.inner_len(), 3);
+
+        let textures = find(uniforms, "textures").expect("missing uniform: textures");
+        assert!(textures.is_image());
+        assert_eq!(textures.dimensions(), &[4]);
+    }
+
+    let shader = compiler.wrap_shader(spirv_shader, true)?;
+    let tokens = shader.generate()?.to_string();
+
+    assert!(tokens.contains("set_weights_at"));
+    assert!(tokens.contains("program_uniform2fv"));
+    assert!(tokens.contains("program_uniform_matrix3fv"));
+    assert!(tokens.contains("get_textures_binding_at"));
+
+    Ok(())
+}
@@ -1,3 +1,4 @@
+use std::rc::Rc;
 use std::time::Instant;
 
 use tinygl::gl;
@@ -60,7 +61,9 @@ fn main() -> Result<(), String> {
             .make_current()
             .expect("failed to make window context current");
         (
-            tinygl::Context::from_loader_function(|s| current.get_proc_address(s) as *const _),
+            Rc::new(tinygl::Context::from_loader_function(|s| {
+                current.get_proc_address(s) as *const _
+            })),
             current,
         )
     };
@@ -88,7 +91,8 @@ fn main() -> Result<(), String> {
             .expect("failed to link program")
     };
 
-    let time = program.get_uniform_location(&gl, "iTime");
+    // The event loop owns the program, which is deleted when the loop drops it
+    let program = tinygl::wrappers::GlHandle::new(&gl, program);
 
     // Use program
     unsafe {
@@ -159,8 +163,8 @@ fn main() -> Result<(), String> {
                     gl.clear_color(1.0, 0.0, 1.0, 1.0);
                     gl.clear(tinygl::gl::COLOR_BUFFER_BIT);
 
-                    // Set current time
-                    // TODO: Keep uniform location around
+                    // Set current time, uniform locations borrow the program
+                    let time = program.get_uniform_location(&gl, "iTime");
                     time.set_f32(&gl, start.elapsed().as_secs_f32());
 
                     // Render
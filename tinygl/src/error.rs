@@ -32,16 +32,26 @@ pub enum Error {
     OpenGlError(#[from] OpenGlErrorCode),
     #[error("failed to create buffer: {0}")]
     BufferCreationFailed(#[source] OpenGlErrorCode),
+    #[error("failed to map buffer: {0}")]
+    BufferMapFailed(#[source] OpenGlErrorCode),
     #[error("failed to create framebuffer: {0}")]
     FramebufferCreationFailed(#[source] OpenGlErrorCode),
+    #[error("framebuffer is incomplete: {0}")]
+    FramebufferIncomplete(crate::wrappers::FramebufferIncompleteReason),
     #[error("failed to create renderbuffer: {0}")]
     RenderbufferCreationFailed(#[source] OpenGlErrorCode),
+    #[error("{0} samples requested, but at most {1} are supported")]
+    UnsupportedSampleCount(u32, u32),
+    #[error("failed to create sampler: {0}")]
+    SamplerCreationFailed(#[source] OpenGlErrorCode),
     #[error("failed to create shader: {0}")]
     ShaderCreationFailed(#[source] OpenGlErrorCode),
     #[error("failed to compile shader: {0}")]
     ShaderCompilationFailed(String),
     #[error("failed to create texture: {0}")]
     TextureCreationFailed(#[source] OpenGlErrorCode),
+    #[error("failed to upload texture data: {0}")]
+    TextureUploadFailed(#[source] OpenGlErrorCode),
     #[error("failed to create program: {0}")]
     ProgramCreationFailed(#[source] OpenGlErrorCode),
     #[error("failed to link program: {0}")]
@@ -50,6 +60,8 @@ pub enum Error {
     QueryCreationFailed(#[source] OpenGlErrorCode),
     #[error("vertex array creation failed: {0}")]
     VertexArrayCreationFailed(#[source] OpenGlErrorCode),
+    #[error("invalid source for vertex attribute {0}: {1}")]
+    VertexAttributeMismatch(&'static str, String),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -199,6 +199,14 @@ impl Context {
         status == TRUE as i32
     }
 
+    /// Get the local workgroup size of a linked compute program
+    pub unsafe fn get_program_compute_work_group_size(&self, program: Program) -> [u32; 3] {
+        let mut size = [0i32; 3];
+        self.gl
+            .get_programiv(program, COMPUTE_WORK_GROUP_SIZE, size.as_mut_ptr());
+        [size[0] as u32, size[1] as u32, size[2] as u32]
+    }
+
     pub unsafe fn get_program_info_log(&self, program: Program) -> Option<String> {
         // Get log length
         let mut length = 0;
@@ -223,26 +231,30 @@ impl Context {
         Some(String::from_utf8_lossy(&info_log[..length as usize]).to_string())
     }
 
-    pub unsafe fn bind_buffer(&self, target: u32, buffer: Option<&wrappers::Buffer>) {
+    pub unsafe fn bind_buffer<T: wrappers::Pod>(
+        &self,
+        target: u32,
+        buffer: Option<&wrappers::Buffer<T>>,
+    ) {
         self.gl
             .bind_buffer(target, buffer.map(|t| t.name()).unwrap_or(0));
     }
 
-    pub unsafe fn bind_buffer_base(
+    pub unsafe fn bind_buffer_base<T: wrappers::Pod>(
         &self,
         target: u32,
         index: u32,
-        buffer: Option<&wrappers::Buffer>,
+        buffer: Option<&wrappers::Buffer<T>>,
     ) {
         self.gl
             .bind_buffer_base(target, index, buffer.map(|t| t.name()).unwrap_or(0));
     }
 
-    pub unsafe fn bind_buffer_range(
+    pub unsafe fn bind_buffer_range<T: wrappers::Pod>(
         &self,
         target: u32,
         index: u32,
-        buffer: Option<&wrappers::Buffer>,
+        buffer: Option<&wrappers::Buffer<T>>,
         offset: isize,
         size: isize,
     ) {
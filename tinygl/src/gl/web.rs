@@ -4,6 +4,8 @@ use web_sys::{
     WebGlUniformLocation, WebGlVertexArrayObject,
 };
 
+use wasm_bindgen::JsValue;
+
 use crate::wrappers;
 
 // Import constants
@@ -665,32 +667,36 @@ impl Context {
             .unwrap_or(false)
     }
 
-    pub unsafe fn bind_buffer(&self, target: u32, buffer: Option<&wrappers::Buffer>) {
+    pub unsafe fn bind_buffer<T: wrappers::Pod>(
+        &self,
+        target: u32,
+        buffer: Option<&wrappers::Buffer<T>>,
+    ) {
         self.gl.bind_buffer(target, buffer.map(|b| b.name()));
     }
 
-    pub unsafe fn bind_buffer_base(
+    pub unsafe fn bind_buffer_base<T: wrappers::Pod>(
         &self,
         target: u32,
         index: u32,
-        buffer: Option<&wrappers::Buffer>,
+        buffer: Option<&wrappers::Buffer<T>>,
     ) {
         self.gl
             .bind_buffer_base(target, index, buffer.map(|b| b.name()));
     }
 
-    pub unsafe fn bind_buffer_range(
+    pub unsafe fn bind_buffer_range<T: wrappers::Pod>(
         &self,
         target: u32,
         index: u32,
-        buffer: Option<&wrappers::Buffer>,
+        buffer: Option<&wrappers::Buffer<T>>,
         offset: isize,
         size: isize,
     ) {
         self.gl.bind_buffer_range_with_i32_and_i32(
             target,
             index,
-            buffer.map(|t| t.name()),
+            buffer.map(|b| b.name()),
             offset as _,
             size as _,
         );
@@ -722,6 +728,11 @@ impl Context {
         self.gl.bind_vertex_array(vertex_array.map(|t| t.name()));
     }
 
+    pub unsafe fn get_buffer_sub_data(&self, target: u32, offset: i32, dst_data: &mut [u8]) {
+        self.gl
+            .get_buffer_sub_data_with_i32_and_u8_array(target, offset, dst_data);
+    }
+
     pub unsafe fn buffer_data_u8_slice(&self, target: u32, data: &[u8], usage: u32) {
         self.gl.buffer_data_with_u8_array(target, data, usage);
     }
@@ -734,6 +745,36 @@ impl Context {
         self.gl
             .buffer_sub_data_with_i32_and_u8_array(target, offset as _, data);
     }
+
+    pub unsafe fn draw_buffers(&self, buffers: &[u32]) {
+        let buffers: js_sys::Array = buffers.iter().copied().map(JsValue::from).collect();
+        self.gl.draw_buffers(&buffers);
+    }
+
+    pub unsafe fn vertex_attrib_pointer_f32(
+        &self,
+        index: u32,
+        size: i32,
+        data_type: u32,
+        normalized: bool,
+        stride: i32,
+        offset: i32,
+    ) {
+        self.gl
+            .vertex_attrib_pointer_with_i32(index, size, data_type, normalized, stride, offset);
+    }
+
+    pub unsafe fn vertex_attrib_pointer_i32(
+        &self,
+        index: u32,
+        size: i32,
+        data_type: u32,
+        stride: i32,
+        offset: i32,
+    ) {
+        self.gl
+            .vertex_attrib_i_pointer_with_i32(index, size, data_type, stride, offset);
+    }
 }
 
 impl std::ops::Deref for Context {
@@ -14,6 +14,9 @@ pub use framebuffer::*;
 mod renderbuffer;
 pub use renderbuffer::*;
 
+mod sampler;
+pub use sampler::*;
+
 mod shader;
 pub use shader::*;
 
@@ -31,6 +34,9 @@ pub use texture::*;
 mod uniform_block;
 pub use uniform_block::*;
 
+mod uniform_cache;
+pub use uniform_cache::*;
+
 mod uniform_location;
 pub use uniform_location::*;
 
@@ -117,6 +123,20 @@ impl<'gl, T: GlDrop> GlRefHandle<'gl, T> {
     pub fn into_inner(mut self) -> T {
         self.res.take().unwrap()
     }
+
+    /// Turn this handle into a long-lived handle
+    ///
+    /// # Parameters
+    ///
+    /// * `gl`: context this handle was created with
+    pub fn into_handle(self, gl: &Rc<crate::Context>) -> GlHandle<T> {
+        debug_assert!(
+            std::ptr::eq(self.gl, gl.as_ref()),
+            "handle moved to a different context"
+        );
+
+        GlHandle::new(gl, self.into_inner())
+    }
 }
 
 impl<'gl, T: GlDrop> Drop for GlRefHandle<'gl, T> {
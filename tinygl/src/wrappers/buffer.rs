@@ -1,14 +1,259 @@
+use std::marker::PhantomData;
+use std::ops::{Bound, Range, RangeBounds};
+
 use crate::OpenGlErrorCode;
 
-pub struct Buffer {
-    name: crate::gl::Buffer,
+#[cfg(not(target_arch = "wasm32"))]
+mod desktop;
+#[cfg(not(target_arch = "wasm32"))]
+pub use desktop::*;
+
+#[cfg(target_arch = "wasm32")]
+mod web;
+#[cfg(target_arch = "wasm32")]
+pub use web::*;
+
+/// Plain data types that can be stored in a buffer
+///
+/// # Safety
+///
+/// The type must not contain padding bytes, pointers or references, and any bit pattern must
+/// be a valid value of the type.
+pub unsafe trait Pod: Copy + 'static {}
+
+unsafe impl Pod for u8 {}
+unsafe impl Pod for i8 {}
+unsafe impl Pod for u16 {}
+unsafe impl Pod for i16 {}
+unsafe impl Pod for u32 {}
+unsafe impl Pod for i32 {}
+unsafe impl Pod for u64 {}
+unsafe impl Pod for i64 {}
+unsafe impl Pod for f32 {}
+unsafe impl Pod for f64 {}
+unsafe impl<T: Pod, const N: usize> Pod for [T; N] {}
+
+fn as_bytes<T: Pod>(data: &[T]) -> &[u8] {
+    unsafe { std::slice::from_raw_parts(data.as_ptr() as *const u8, std::mem::size_of_val(data)) }
+}
+
+fn as_bytes_mut<T: Pod>(data: &mut [T]) -> &mut [u8] {
+    unsafe {
+        std::slice::from_raw_parts_mut(data.as_mut_ptr() as *mut u8, std::mem::size_of_val(data))
+    }
+}
+
+/// Kind of storage backing a buffer
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum BufferUsage {
+    /// Mutable storage, with the given usage hint (`STATIC_DRAW`, `DYNAMIC_DRAW`, etc.)
+    Mutable(u32),
+    /// Immutable storage, with the given storage flags (`DYNAMIC_STORAGE_BIT`,
+    /// `MAP_PERSISTENT_BIT`, etc.)
+    #[cfg(not(target_arch = "wasm32"))]
+    Immutable(u32),
+}
+
+impl BufferUsage {
+    /// true if the storage can be reallocated
+    pub fn is_mutable(&self) -> bool {
+        matches!(self, Self::Mutable(_))
+    }
+
+    /// true if the storage can stay mapped while the buffer is in use
+    pub fn is_persistent(&self) -> bool {
+        match self {
+            Self::Mutable(_) => false,
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::Immutable(flags) => flags & crate::gl::MAP_PERSISTENT_BIT != 0,
+        }
+    }
+}
+
+/// Kind of data held by a buffer
+///
+/// WebGL fixes the kind of a buffer when it is first bound: index buffers can only be bound to
+/// `ELEMENT_ARRAY_BUFFER`, and other buffers can be bound to any target except this one.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum BufferKind {
+    /// Vertex, uniform, storage or any other non-index data
+    Data,
+    /// Indices for indexed draw calls
+    Index,
+}
+
+/// Access requested when mapping a buffer range
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum MapAccess {
+    /// The mapped range is only read from
+    Read,
+    /// The mapped range is only written to, its initial contents are undefined
+    Write,
+    /// The mapped range is read from and written to
+    ReadWrite,
 }
 
-impl Buffer {
-    impl_nnew!(BufferCreationFailed, create_buffers, create_buffer);
+impl MapAccess {
+    pub fn is_read(&self) -> bool {
+        matches!(self, Self::Read | Self::ReadWrite)
+    }
+
+    pub fn is_write(&self) -> bool {
+        matches!(self, Self::Write | Self::ReadWrite)
+    }
+}
+
+/// Buffer object holding values of type `T`
+///
+/// The buffer remembers how many values it holds (its length), how many values its storage
+/// can hold (its capacity) and how its storage was allocated.
+pub struct Buffer<T: Pod = u8> {
+    pub(super) name: crate::gl::Buffer,
+    len: usize,
+    capacity: usize,
+    usage: BufferUsage,
+    kind: BufferKind,
+    _data: PhantomData<T>,
+}
+
+impl<T: Pod> Buffer<T> {
+    impl_nnew!(
+        BufferCreationFailed,
+        create_buffers,
+        create_buffer,
+        |name| Self::from_name(name)
+    );
+
+    fn from_name(name: crate::gl::Buffer) -> Self {
+        Self {
+            name,
+            len: 0,
+            capacity: 0,
+            usage: BufferUsage::Mutable(crate::gl::STATIC_DRAW),
+            kind: BufferKind::Data,
+            _data: PhantomData,
+        }
+    }
 
     impl_name!(pub crate::gl::BufferName);
 
+    /// Create a buffer without storage, holding data of the given kind
+    ///
+    /// # Parameters
+    ///
+    /// * `gl`: GL context
+    /// * `kind`: kind of data held by the buffer
+    pub fn with_kind(gl: &crate::Context, kind: BufferKind) -> crate::Result<Self> {
+        let mut buffer = Self::new(gl)?;
+        buffer.kind = kind;
+        Ok(buffer)
+    }
+
+    /// Create an index buffer with mutable storage for `capacity` values
+    ///
+    /// On WebGL, transfers to and from index buffers unbind the current vertex array, so they
+    /// don't change its index buffer.
+    ///
+    /// # Parameters
+    ///
+    /// * `gl`: GL context
+    /// * `capacity`: number of indices to allocate storage for
+    /// * `usage`: usage hint for the storage
+    pub fn index_with_capacity(
+        gl: &crate::Context,
+        capacity: usize,
+        usage: u32,
+    ) -> crate::Result<Self> {
+        let mut buffer = Self::with_kind(gl, BufferKind::Index)?;
+        buffer.usage = BufferUsage::Mutable(usage);
+        buffer.allocate(gl, capacity, None);
+        Ok(buffer)
+    }
+
+    /// Create an index buffer with mutable storage initialized from `data`
+    ///
+    /// # Parameters
+    ///
+    /// * `gl`: GL context
+    /// * `data`: initial indices
+    /// * `usage`: usage hint for the storage
+    pub fn index_from_slice(gl: &crate::Context, data: &[T], usage: u32) -> crate::Result<Self> {
+        let mut buffer = Self::with_kind(gl, BufferKind::Index)?;
+        buffer.usage = BufferUsage::Mutable(usage);
+        buffer.allocate(gl, data.len(), Some(data));
+        buffer.len = data.len();
+        Ok(buffer)
+    }
+
+    /// Create a buffer with mutable storage for `capacity` values
+    ///
+    /// # Parameters
+    ///
+    /// * `gl`: GL context
+    /// * `capacity`: number of values to allocate storage for
+    /// * `usage`: usage hint for the storage
+    pub fn with_capacity(gl: &crate::Context, capacity: usize, usage: u32) -> crate::Result<Self> {
+        let mut buffer = Self::new(gl)?;
+        buffer.usage = BufferUsage::Mutable(usage);
+        buffer.allocate(gl, capacity, None);
+        Ok(buffer)
+    }
+
+    /// Create a buffer with mutable storage holding `len` values of undefined contents
+    ///
+    /// # Parameters
+    ///
+    /// * `gl`: GL context
+    /// * `len`: number of values in the buffer
+    /// * `usage`: usage hint for the storage
+    pub fn with_len(gl: &crate::Context, len: usize, usage: u32) -> crate::Result<Self> {
+        let mut buffer = Self::with_capacity(gl, len, usage)?;
+        buffer.len = len;
+        Ok(buffer)
+    }
+
+    /// Create a buffer with mutable storage initialized from `data`
+    ///
+    /// # Parameters
+    ///
+    /// * `gl`: GL context
+    /// * `data`: initial contents of the buffer
+    /// * `usage`: usage hint for the storage
+    pub fn from_slice(gl: &crate::Context, data: &[T], usage: u32) -> crate::Result<Self> {
+        let mut buffer = Self::new(gl)?;
+        buffer.usage = BufferUsage::Mutable(usage);
+        buffer.allocate(gl, data.len(), Some(data));
+        buffer.len = data.len();
+        Ok(buffer)
+    }
+
+    /// Number of values in the buffer
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Number of values the current storage can hold
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn usage(&self) -> BufferUsage {
+        self.usage
+    }
+
+    pub fn kind(&self) -> BufferKind {
+        self.kind
+    }
+
+    /// Size of the values in the buffer, in bytes
+    pub fn size_bytes(&self) -> usize {
+        self.len * std::mem::size_of::<T>()
+    }
+
     pub unsafe fn bind(&self, gl: &crate::Context, target: u32) {
         gl.bind_buffer(target, Some(self));
     }
@@ -27,6 +272,111 @@ impl Buffer {
     ) {
         gl.bind_buffer_range(target, index, Some(self), offset, size);
     }
+
+    /// Replace the contents of the buffer
+    ///
+    /// The current storage is reused if it is large enough, otherwise mutable storage is
+    /// reallocated with the same usage hint.
+    ///
+    /// # Parameters
+    ///
+    /// * `gl`: GL context
+    /// * `data`: new contents of the buffer
+    ///
+    /// # Panics
+    ///
+    /// Panics if the buffer has immutable storage that is too small to hold `data`.
+    pub fn upload(&mut self, gl: &crate::Context, data: &[T]) {
+        if data.len() <= self.capacity {
+            self.sub_data(gl, 0, data);
+        } else {
+            assert!(
+                self.usage.is_mutable(),
+                "cannot upload {} values to immutable storage for {} values",
+                data.len(),
+                self.capacity
+            );
+
+            self.allocate(gl, data.len(), Some(data));
+        }
+
+        self.len = data.len();
+    }
+
+    /// Update a range of values in the buffer
+    ///
+    /// # Parameters
+    ///
+    /// * `gl`: GL context
+    /// * `range`: range of values to update
+    /// * `data`: new values for the range
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of bounds or if its length does not match `data`.
+    pub fn update(&self, gl: &crate::Context, range: impl RangeBounds<usize>, data: &[T]) {
+        let range = self.check_range(range);
+        assert_eq!(
+            range.len(),
+            data.len(),
+            "range and data lengths do not match"
+        );
+
+        self.sub_data(gl, range.start, data);
+    }
+
+    /// Read a range of values back from the buffer
+    ///
+    /// # Parameters
+    ///
+    /// * `gl`: GL context
+    /// * `range`: range of values to read
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of bounds.
+    pub fn read_back(&self, gl: &crate::Context, range: impl RangeBounds<usize>) -> Vec<T> {
+        let range = self.check_range(range);
+
+        // Any bit pattern is a valid value of T
+        let mut data: Vec<T> = vec![unsafe { std::mem::zeroed() }; range.len()];
+        self.get_sub_data(gl, range.start, &mut data);
+        data
+    }
+
+    fn check_range(&self, range: impl RangeBounds<usize>) -> Range<usize> {
+        let start = match range.start_bound() {
+            Bound::Included(&start) => start,
+            Bound::Excluded(&start) => start + 1,
+            Bound::Unbounded => 0,
+        };
+
+        let end = match range.end_bound() {
+            Bound::Included(&end) => end + 1,
+            Bound::Excluded(&end) => end,
+            Bound::Unbounded => self.len,
+        };
+
+        assert!(
+            start <= end && end <= self.len,
+            "range {}..{} out of bounds for buffer of length {}",
+            start,
+            end,
+            self.len
+        );
+
+        start..end
+    }
 }
 
-impl_ndrop!(Buffer, delete_buffers, delete_buffer);
+impl<T: Pod> super::GlDrop for Buffer<T> {
+    #[cfg(not(target_arch = "wasm32"))]
+    unsafe fn drop(&mut self, gl: &crate::Context) {
+        gl.delete_buffers(1, &self.name);
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    unsafe fn drop(&mut self, gl: &crate::Context) {
+        gl.delete_buffer(Some(&self.name));
+    }
+}
@@ -0,0 +1,212 @@
+use std::ops::RangeBounds;
+
+use super::{as_bytes, as_bytes_mut, Buffer, BufferUsage, MapAccess, Pod};
+use crate::OpenGlErrorCode;
+
+impl<T: Pod> Buffer<T> {
+    /// Create a buffer with immutable storage holding `len` values of undefined contents
+    ///
+    /// # Parameters
+    ///
+    /// * `gl`: GL context
+    /// * `len`: number of values in the buffer
+    /// * `flags`: storage flags
+    pub fn with_storage(gl: &crate::Context, len: usize, flags: u32) -> crate::Result<Self> {
+        let mut buffer = Self::new(gl)?;
+
+        unsafe {
+            gl.named_buffer_storage(
+                buffer.name,
+                (len * std::mem::size_of::<T>()) as isize,
+                std::ptr::null(),
+                flags,
+            );
+        }
+
+        buffer.len = len;
+        buffer.capacity = len;
+        buffer.usage = BufferUsage::Immutable(flags);
+        Ok(buffer)
+    }
+
+    /// Create a buffer with immutable storage that can stay mapped while it is in use
+    ///
+    /// The storage is mapped for reading and writing with coherent access, so writes through
+    /// a mapping are visible to GL without flushing.
+    ///
+    /// # Parameters
+    ///
+    /// * `gl`: GL context
+    /// * `len`: number of values in the buffer
+    pub fn with_persistent_storage(gl: &crate::Context, len: usize) -> crate::Result<Self> {
+        Self::with_storage(
+            gl,
+            len,
+            crate::gl::DYNAMIC_STORAGE_BIT
+                | crate::gl::MAP_READ_BIT
+                | crate::gl::MAP_WRITE_BIT
+                | crate::gl::MAP_PERSISTENT_BIT
+                | crate::gl::MAP_COHERENT_BIT,
+        )
+    }
+
+    pub(super) fn allocate(&mut self, gl: &crate::Context, capacity: usize, data: Option<&[T]>) {
+        let usage = match self.usage {
+            BufferUsage::Mutable(usage) => usage,
+            BufferUsage::Immutable(_) => unreachable!("immutable storage cannot be reallocated"),
+        };
+
+        unsafe {
+            gl.named_buffer_data(
+                self.name,
+                (capacity * std::mem::size_of::<T>()) as isize,
+                data.map(|data| data.as_ptr() as *const std::ffi::c_void)
+                    .unwrap_or_else(std::ptr::null),
+                usage,
+            );
+        }
+
+        self.capacity = capacity;
+    }
+
+    pub(super) fn sub_data(&self, gl: &crate::Context, offset: usize, data: &[T]) {
+        if data.is_empty() {
+            return;
+        }
+
+        let bytes = as_bytes(data);
+        unsafe {
+            gl.named_buffer_sub_data(
+                self.name,
+                (offset * std::mem::size_of::<T>()) as isize,
+                bytes.len() as isize,
+                bytes.as_ptr() as *const std::ffi::c_void,
+            );
+        }
+    }
+
+    pub(super) fn get_sub_data(&self, gl: &crate::Context, offset: usize, data: &mut [T]) {
+        if data.is_empty() {
+            return;
+        }
+
+        let bytes = as_bytes_mut(data);
+        unsafe {
+            gl.get_named_buffer_sub_data(
+                self.name,
+                (offset * std::mem::size_of::<T>()) as isize,
+                bytes.len() as isize,
+                bytes.as_mut_ptr() as *mut std::ffi::c_void,
+            );
+        }
+    }
+
+    /// Map a range of values into client memory
+    ///
+    /// The range is unmapped when the returned guard is dropped. Buffers with persistent
+    /// storage are mapped persistently, so they can be used by GL while the guard is alive.
+    ///
+    /// # Parameters
+    ///
+    /// * `gl`: GL context
+    /// * `range`: range of values to map
+    /// * `access`: access to the mapped values
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of bounds.
+    pub fn map_range<'b>(
+        &'b self,
+        gl: &'b crate::Context,
+        range: impl RangeBounds<usize>,
+        access: MapAccess,
+    ) -> crate::Result<BufferMapping<'b, T>> {
+        let range = self.check_range(range);
+        if range.is_empty() {
+            return Ok(BufferMapping {
+                gl,
+                name: self.name,
+                access,
+                data: &mut [],
+            });
+        }
+
+        let mut flags = 0;
+        if access.is_read() {
+            flags |= crate::gl::MAP_READ_BIT;
+        }
+        if access.is_write() {
+            flags |= crate::gl::MAP_WRITE_BIT;
+        }
+        if let BufferUsage::Immutable(storage) = self.usage {
+            flags |= storage & (crate::gl::MAP_PERSISTENT_BIT | crate::gl::MAP_COHERENT_BIT);
+        }
+
+        let size = std::mem::size_of::<T>();
+        unsafe {
+            let ptr = gl.map_named_buffer_range(
+                self.name,
+                (range.start * size) as isize,
+                (range.len() * size) as isize,
+                flags,
+            );
+
+            if ptr.is_null() {
+                return Err(crate::Error::BufferMapFailed(OpenGlErrorCode(
+                    gl.get_error(),
+                )));
+            }
+
+            Ok(BufferMapping {
+                gl,
+                name: self.name,
+                access,
+                data: std::slice::from_raw_parts_mut(ptr as *mut T, range.len()),
+            })
+        }
+    }
+}
+
+/// Range of a buffer mapped into client memory
+///
+/// The range is unmapped when this guard is dropped.
+pub struct BufferMapping<'b, T: Pod> {
+    gl: &'b crate::Context,
+    name: crate::gl::Buffer,
+    access: MapAccess,
+    data: &'b mut [T],
+}
+
+impl<'b, T: Pod> BufferMapping<'b, T> {
+    pub fn access(&self) -> MapAccess {
+        self.access
+    }
+}
+
+impl<'b, T: Pod> std::ops::Deref for BufferMapping<'b, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        self.data
+    }
+}
+
+impl<'b, T: Pod> std::ops::DerefMut for BufferMapping<'b, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        assert!(
+            self.access.is_write(),
+            "buffer range not mapped for writing"
+        );
+        self.data
+    }
+}
+
+impl<'b, T: Pod> Drop for BufferMapping<'b, T> {
+    fn drop(&mut self) {
+        if !self.data.is_empty() {
+            unsafe {
+                self.gl.unmap_named_buffer(self.name);
+            }
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use std::ops::RangeBounds;
+
+use super::{as_bytes, as_bytes_mut, Buffer, BufferKind, BufferUsage, MapAccess, Pod};
+
+// WebGL has no direct state access, so data moves through the copy bindings, or through the
+// index binding for index buffers since WebGL doesn't allow binding them anywhere else
+impl<T: Pod> Buffer<T> {
+    /// Bind the buffer for a transfer, returning the target it was bound to
+    ///
+    /// # Parameters
+    ///
+    /// * `gl`: GL context
+    /// * `copy_target`: copy target to use for data buffers
+    unsafe fn bind_transfer(&self, gl: &crate::Context, copy_target: u32) -> u32 {
+        match self.kind {
+            BufferKind::Data => {
+                self.bind(gl, copy_target);
+                copy_target
+            }
+            BufferKind::Index => {
+                // The index binding is part of the vertex array state
+                gl.bind_vertex_array(None);
+                self.bind(gl, crate::gl::ELEMENT_ARRAY_BUFFER);
+                crate::gl::ELEMENT_ARRAY_BUFFER
+            }
+        }
+    }
+
+    pub(super) fn allocate(&mut self, gl: &crate::Context, capacity: usize, data: Option<&[T]>) {
+        let BufferUsage::Mutable(usage) = self.usage;
+
+        unsafe {
+            let target = self.bind_transfer(gl, crate::gl::COPY_WRITE_BUFFER);
+
+            match data {
+                Some(data) => gl.buffer_data_u8_slice(target, as_bytes(data), usage),
+                None => {
+                    gl.buffer_data_size(target, (capacity * std::mem::size_of::<T>()) as i32, usage)
+                }
+            }
+        }
+
+        self.capacity = capacity;
+    }
+
+    pub(super) fn sub_data(&self, gl: &crate::Context, offset: usize, data: &[T]) {
+        if data.is_empty() {
+            return;
+        }
+
+        unsafe {
+            let target = self.bind_transfer(gl, crate::gl::COPY_WRITE_BUFFER);
+            gl.buffer_sub_data_u8_slice(
+                target,
+                (offset * std::mem::size_of::<T>()) as isize,
+                as_bytes(data),
+            );
+        }
+    }
+
+    pub(super) fn get_sub_data(&self, gl: &crate::Context, offset: usize, data: &mut [T]) {
+        if data.is_empty() {
+            return;
+        }
+
+        unsafe {
+            let target = self.bind_transfer(gl, crate::gl::COPY_READ_BUFFER);
+            gl.get_buffer_sub_data(
+                target,
+                (offset * std::mem::size_of::<T>()) as i32,
+                as_bytes_mut(data),
+            );
+        }
+    }
+
+    /// Map a range of values into client memory
+    ///
+    /// WebGL can't map buffers, so the range is copied into client memory and written back
+    /// when the returned guard is dropped if it was mapped for writing.
+    ///
+    /// # Parameters
+    ///
+    /// * `gl`: GL context
+    /// * `range`: range of values to map
+    /// * `access`: access to the mapped values
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of bounds.
+    pub fn map_range<'b>(
+        &'b self,
+        gl: &'b crate::Context,
+        range: impl RangeBounds<usize>,
+        access: MapAccess,
+    ) -> crate::Result<BufferMapping<'b, T>> {
+        let range = self.check_range(range);
+        let data = if access.is_read() {
+            self.read_back(gl, range.clone())
+        } else {
+            // Any bit pattern is a valid value of T
+            vec![unsafe { std::mem::zeroed() }; range.len()]
+        };
+
+        Ok(BufferMapping {
+            gl,
+            buffer: self,
+            offset: range.start,
+            access,
+            data,
+        })
+    }
+}
+
+/// Range of a buffer copied into client memory
+///
+/// The range is written back to the buffer when this guard is dropped, if it was mapped for
+/// writing.
+pub struct BufferMapping<'b, T: Pod> {
+    gl: &'b crate::Context,
+    buffer: &'b Buffer<T>,
+    offset: usize,
+    access: MapAccess,
+    data: Vec<T>,
+}
+
+impl<'b, T: Pod> BufferMapping<'b, T> {
+    pub fn access(&self) -> MapAccess {
+        self.access
+    }
+}
+
+impl<'b, T: Pod> std::ops::Deref for BufferMapping<'b, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        &self.data
+    }
+}
+
+impl<'b, T: Pod> std::ops::DerefMut for BufferMapping<'b, T> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        assert!(
+            self.access.is_write(),
+            "buffer range not mapped for writing"
+        );
+        &mut self.data
+    }
+}
+
+impl<'b, T: Pod> Drop for BufferMapping<'b, T> {
+    fn drop(&mut self) {
+        if self.access.is_write() {
+            self.buffer.sub_data(self.gl, self.offset, &self.data);
+        }
+    }
+}
@@ -1,5 +1,13 @@
 use crate::OpenGlErrorCode;
 
+use super::{Buffer, CubeFace, GlRefHandle, InternalFormat, MagFilter, Pod, Renderbuffer, Texture};
+
+#[cfg(not(target_arch = "wasm32"))]
+mod desktop;
+
+#[cfg(target_arch = "wasm32")]
+mod web;
+
 pub struct Framebuffer {
     name: crate::gl::Framebuffer,
 }
@@ -13,9 +21,515 @@ impl Framebuffer {
 
     impl_name!(pub crate::gl::FramebufferName);
 
+    /// Create a builder for a framebuffer
+    pub fn builder<'a>() -> FramebufferBuilder<'a> {
+        FramebufferBuilder::default()
+    }
+
     pub unsafe fn bind(&self, gl: &crate::Context, target: u32) {
         gl.bind_framebuffer(target, Some(self));
     }
+
+    /// Check that this framebuffer can be rendered to
+    ///
+    /// # Errors
+    ///
+    /// Returns [crate::Error::FramebufferIncomplete] with the reason reported by GL if the
+    /// framebuffer is not complete.
+    pub fn check_status(&self, gl: &crate::Context) -> crate::Result<()> {
+        match FramebufferIncompleteReason::from_status(self.status(gl)) {
+            Some(reason) => Err(crate::Error::FramebufferIncomplete(reason)),
+            None => Ok(()),
+        }
+    }
+
+    /// Copy a rectangle of pixels to another framebuffer
+    ///
+    /// # Parameters
+    ///
+    /// * `gl`: GL context
+    /// * `target`: framebuffer to copy to, `None` for the default framebuffer
+    /// * `src`: source rectangle, as `[x0, y0, x1, y1]`
+    /// * `dst`: destination rectangle, as `[x0, y0, x1, y1]`
+    /// * `mask`: buffers to copy (`COLOR_BUFFER_BIT`, `DEPTH_BUFFER_BIT`, `STENCIL_BUFFER_BIT`)
+    /// * `filter`: filter used when the rectangles have different sizes
+    pub fn blit_to(
+        &self,
+        gl: &crate::Context,
+        target: Option<&Framebuffer>,
+        src: [i32; 4],
+        dst: [i32; 4],
+        mask: u32,
+        filter: MagFilter,
+    ) {
+        self.blit(gl, target, src, dst, mask, filter.gl_enum());
+    }
+
+    /// Clear a floating-point or normalized color draw buffer
+    ///
+    /// # Parameters
+    ///
+    /// * `gl`: GL context
+    /// * `draw_buffer`: index of the draw buffer to clear
+    /// * `color`: clear color
+    pub fn clear_color(&self, gl: &crate::Context, draw_buffer: u32, color: [f32; 4]) {
+        self.clear_fv(gl, crate::gl::COLOR, draw_buffer as i32, &color);
+    }
+
+    /// Clear a signed integer color draw buffer
+    pub fn clear_color_int(&self, gl: &crate::Context, draw_buffer: u32, color: [i32; 4]) {
+        self.clear_iv(gl, crate::gl::COLOR, draw_buffer as i32, &color);
+    }
+
+    /// Clear an unsigned integer color draw buffer
+    pub fn clear_color_uint(&self, gl: &crate::Context, draw_buffer: u32, color: [u32; 4]) {
+        self.clear_uiv(gl, crate::gl::COLOR, draw_buffer as i32, &color);
+    }
+
+    pub fn clear_depth(&self, gl: &crate::Context, depth: f32) {
+        self.clear_fv(gl, crate::gl::DEPTH, 0, &[depth]);
+    }
+
+    pub fn clear_stencil(&self, gl: &crate::Context, stencil: i32) {
+        self.clear_iv(gl, crate::gl::STENCIL, 0, &[stencil]);
+    }
+
+    pub fn clear_depth_stencil(&self, gl: &crate::Context, depth: f32, stencil: i32) {
+        self.clear_fi(gl, depth, stencil);
+    }
+
+    /// Read pixels from an attachment into a buffer
+    ///
+    /// The read buffer of the framebuffer is restored after reading from a color attachment, and
+    /// so are the pixel pack buffer and read framebuffer bindings of the context.
+    ///
+    /// # Parameters
+    ///
+    /// * `gl`: GL context
+    /// * `attachment`: attachment to read from
+    /// * `offset`: offset of the rectangle to read, in pixels
+    /// * `size`: size of the rectangle to read, in pixels
+    /// * `format`: format of the attachment, which determines the layout of the pixels
+    /// * `buffer`: buffer to write the pixels to, starting at its first value
+    ///
+    /// # Panics
+    ///
+    /// Panics if `buffer` is too small to hold the pixels, or on WebGL if `attachment` is not a
+    /// color attachment.
+    pub fn read_pixels<T: Pod>(
+        &self,
+        gl: &crate::Context,
+        attachment: Attachment,
+        offset: [i32; 2],
+        size: [u32; 2],
+        format: InternalFormat,
+        buffer: &Buffer<T>,
+    ) {
+        // WebGL only reads pixels from color attachments
+        #[cfg(target_arch = "wasm32")]
+        assert!(
+            matches!(attachment, Attachment::Color(_)),
+            "cannot read pixels from {:?}",
+            attachment
+        );
+
+        let row_size = size[0] as usize * format.pixel_size();
+        assert!(
+            buffer.size_bytes() >= row_size * size[1] as usize,
+            "buffer too small for {}x{} pixels",
+            size[0],
+            size[1]
+        );
+
+        let alignment = super::texture::row_alignment(row_size);
+        self.read_pixels_to_pack_buffer(gl, attachment, offset, size, format, alignment, buffer);
+    }
 }
 
 impl_ndrop!(Framebuffer, delete_framebuffers, delete_framebuffer);
+
+/// Attachment point of a framebuffer
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Attachment {
+    /// Color attachment with the given index
+    Color(u32),
+    Depth,
+    Stencil,
+    DepthStencil,
+}
+
+impl Attachment {
+    pub fn gl_enum(self) -> u32 {
+        match self {
+            Self::Color(index) => crate::gl::COLOR_ATTACHMENT0 + index,
+            Self::Depth => crate::gl::DEPTH_ATTACHMENT,
+            Self::Stencil => crate::gl::STENCIL_ATTACHMENT,
+            Self::DepthStencil => crate::gl::DEPTH_STENCIL_ATTACHMENT,
+        }
+    }
+}
+
+/// Reason reported by GL for an incomplete framebuffer
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum FramebufferIncompleteReason {
+    /// The default framebuffer does not exist
+    #[cfg(not(target_arch = "wasm32"))]
+    Undefined,
+    /// An attachment is not complete, or its format can't be rendered to
+    IncompleteAttachment,
+    /// No image is attached
+    MissingAttachment,
+    /// Attachments don't have the same size
+    #[cfg(target_arch = "wasm32")]
+    IncompleteDimensions,
+    /// A draw buffer refers to a missing attachment
+    #[cfg(not(target_arch = "wasm32"))]
+    IncompleteDrawBuffer,
+    /// The read buffer refers to a missing attachment
+    #[cfg(not(target_arch = "wasm32"))]
+    IncompleteReadBuffer,
+    /// The combination of attachment formats is not supported
+    Unsupported,
+    /// Attachments don't have the same number of samples
+    IncompleteMultisample,
+    /// Layered and non-layered attachments are mixed, or use different targets
+    #[cfg(not(target_arch = "wasm32"))]
+    IncompleteLayerTargets,
+    /// Unknown status value
+    Unknown(u32),
+}
+
+impl FramebufferIncompleteReason {
+    /// Reason for a value returned by `glCheckFramebufferStatus`, `None` if it is complete
+    pub fn from_status(status: u32) -> Option<Self> {
+        use crate::gl;
+
+        Some(match status {
+            gl::FRAMEBUFFER_COMPLETE => return None,
+            #[cfg(not(target_arch = "wasm32"))]
+            gl::FRAMEBUFFER_UNDEFINED => Self::Undefined,
+            gl::FRAMEBUFFER_INCOMPLETE_ATTACHMENT => Self::IncompleteAttachment,
+            gl::FRAMEBUFFER_INCOMPLETE_MISSING_ATTACHMENT => Self::MissingAttachment,
+            #[cfg(target_arch = "wasm32")]
+            gl::FRAMEBUFFER_INCOMPLETE_DIMENSIONS => Self::IncompleteDimensions,
+            #[cfg(not(target_arch = "wasm32"))]
+            gl::FRAMEBUFFER_INCOMPLETE_DRAW_BUFFER => Self::IncompleteDrawBuffer,
+            #[cfg(not(target_arch = "wasm32"))]
+            gl::FRAMEBUFFER_INCOMPLETE_READ_BUFFER => Self::IncompleteReadBuffer,
+            gl::FRAMEBUFFER_UNSUPPORTED => Self::Unsupported,
+            gl::FRAMEBUFFER_INCOMPLETE_MULTISAMPLE => Self::IncompleteMultisample,
+            #[cfg(not(target_arch = "wasm32"))]
+            gl::FRAMEBUFFER_INCOMPLETE_LAYER_TARGETS => Self::IncompleteLayerTargets,
+            other => Self::Unknown(other),
+        })
+    }
+}
+
+impl std::fmt::Display for FramebufferIncompleteReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::Undefined => write!(f, "the default framebuffer does not exist"),
+            Self::IncompleteAttachment => {
+                write!(
+                    f,
+                    "an attachment is incomplete or has a non-renderable format"
+                )
+            }
+            Self::MissingAttachment => write!(f, "no image is attached"),
+            #[cfg(target_arch = "wasm32")]
+            Self::IncompleteDimensions => write!(f, "attachments have different sizes"),
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::IncompleteDrawBuffer => write!(f, "a draw buffer has no attachment"),
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::IncompleteReadBuffer => write!(f, "the read buffer has no attachment"),
+            Self::Unsupported => write!(f, "the combination of formats is not supported"),
+            Self::IncompleteMultisample => {
+                write!(f, "attachments have different numbers of samples")
+            }
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::IncompleteLayerTargets => {
+                write!(f, "attachments mix layered and non-layered images")
+            }
+            Self::Unknown(status) => write!(f, "unknown status {:x}", status),
+        }
+    }
+}
+
+/// Image attached to a framebuffer
+enum AttachmentSource<'a> {
+    Texture {
+        texture: &'a Texture,
+        level: u32,
+    },
+    TextureLayer {
+        texture: &'a Texture,
+        level: u32,
+        layer: u32,
+    },
+    CubeFace {
+        texture: &'a Texture,
+        face: CubeFace,
+        level: u32,
+    },
+    Renderbuffer(&'a Renderbuffer),
+}
+
+/// Builder for [Framebuffer] objects
+#[derive(Default)]
+pub struct FramebufferBuilder<'a> {
+    attachments: Vec<(Attachment, AttachmentSource<'a>)>,
+    draw_buffers: Option<Vec<Option<Attachment>>>,
+    read_buffer: Option<Option<Attachment>>,
+}
+
+impl<'a> FramebufferBuilder<'a> {
+    /// Attach a mip level of a 2D texture
+    ///
+    /// # Parameters
+    ///
+    /// * `attachment`: attachment point
+    /// * `texture`: texture to attach
+    /// * `level`: mip level to attach
+    pub fn texture(mut self, attachment: Attachment, texture: &'a Texture, level: u32) -> Self {
+        self.attachments
+            .push((attachment, AttachmentSource::Texture { texture, level }));
+        self
+    }
+
+    /// Attach a layer of an array or 3D texture
+    ///
+    /// # Parameters
+    ///
+    /// * `attachment`: attachment point
+    /// * `texture`: texture to attach
+    /// * `level`: mip level to attach
+    /// * `layer`: layer (or depth slice) to attach
+    pub fn texture_layer(
+        mut self,
+        attachment: Attachment,
+        texture: &'a Texture,
+        level: u32,
+        layer: u32,
+    ) -> Self {
+        self.attachments.push((
+            attachment,
+            AttachmentSource::TextureLayer {
+                texture,
+                level,
+                layer,
+            },
+        ));
+        self
+    }
+
+    /// Attach a face of a cube map texture
+    ///
+    /// # Parameters
+    ///
+    /// * `attachment`: attachment point
+    /// * `texture`: cube map to attach
+    /// * `face`: face to attach
+    /// * `level`: mip level to attach
+    pub fn cube_face(
+        mut self,
+        attachment: Attachment,
+        texture: &'a super::TextureCube,
+        face: CubeFace,
+        level: u32,
+    ) -> Self {
+        self.attachments.push((
+            attachment,
+            AttachmentSource::CubeFace {
+                texture,
+                face,
+                level,
+            },
+        ));
+        self
+    }
+
+    /// Attach a renderbuffer
+    pub fn renderbuffer(mut self, attachment: Attachment, renderbuffer: &'a Renderbuffer) -> Self {
+        self.attachments
+            .push((attachment, AttachmentSource::Renderbuffer(renderbuffer)));
+        self
+    }
+
+    /// Set the attachments written by each fragment shader output location
+    ///
+    /// By default, location `i` is written to color attachment `i` if it is attached.
+    pub fn draw_buffers(self, draw_buffers: &[Option<Attachment>]) -> Self {
+        Self {
+            draw_buffers: Some(draw_buffers.to_vec()),
+            ..self
+        }
+    }
+
+    /// Set the attachment pixels are read from
+    ///
+    /// By default, pixels are read from the color attachment with the lowest index.
+    pub fn read_buffer(self, read_buffer: Option<Attachment>) -> Self {
+        Self {
+            read_buffer: Some(read_buffer),
+            ..self
+        }
+    }
+
+    /// Create the framebuffer, attach the images and check that it is complete
+    ///
+    /// # Parameters
+    ///
+    /// * `gl`: GL context
+    ///
+    /// # Errors
+    ///
+    /// Returns [crate::Error::FramebufferIncomplete] if the framebuffer can't be rendered to.
+    pub fn build(self, gl: &crate::Context) -> crate::Result<GlRefHandle<'_, Framebuffer>> {
+        if let Some(reason) = self.validate() {
+            return Err(crate::Error::FramebufferIncomplete(reason));
+        }
+
+        let framebuffer = GlRefHandle::new(gl, Framebuffer::new(gl)?);
+
+        let mut color_indices: Vec<u32> = Vec::new();
+        for (attachment, source) in &self.attachments {
+            framebuffer.attach(gl, attachment.gl_enum(), source);
+
+            if let Attachment::Color(index) = attachment {
+                color_indices.push(*index);
+            }
+        }
+
+        color_indices.sort_unstable();
+
+        let draw_buffers = self.draw_buffers.unwrap_or_else(|| {
+            let count = color_indices.last().map(|index| index + 1).unwrap_or(0);
+            (0..count)
+                .map(|index| {
+                    color_indices
+                        .binary_search(&index)
+                        .ok()
+                        .map(|_| Attachment::Color(index))
+                })
+                .collect()
+        });
+
+        let read_buffer = self
+            .read_buffer
+            .unwrap_or_else(|| color_indices.first().map(|index| Attachment::Color(*index)));
+
+        let draw_buffers: Vec<u32> = draw_buffers
+            .iter()
+            .map(|buffer| buffer.map(Attachment::gl_enum).unwrap_or(crate::gl::NONE))
+            .collect();
+
+        framebuffer.set_buffers(
+            gl,
+            &draw_buffers,
+            read_buffer
+                .map(Attachment::gl_enum)
+                .unwrap_or(crate::gl::NONE),
+        );
+
+        framebuffer.check_status(gl)?;
+        Ok(framebuffer)
+    }
+
+    /// Check the renderbuffers allocated with [Renderbuffer::with_storage] before asking GL
+    fn validate(&self) -> Option<FramebufferIncompleteReason> {
+        let mut samples = None;
+        #[cfg(target_arch = "wasm32")]
+        let mut size = None;
+
+        for (attachment, source) in &self.attachments {
+            // Textures are never multisampled, and the storage of other renderbuffers is unknown
+            let renderbuffer = match source {
+                AttachmentSource::Renderbuffer(renderbuffer) => renderbuffer,
+                _ => {
+                    if *samples.get_or_insert(0) != 0 {
+                        return Some(FramebufferIncompleteReason::IncompleteMultisample);
+                    }
+
+                    continue;
+                }
+            };
+
+            let format = match renderbuffer.format() {
+                Some(format) => format,
+                None => continue,
+            };
+
+            let compatible = match attachment {
+                Attachment::Color(_) => format.attachment_type().is_some(),
+                Attachment::Depth => format.has_depth(),
+                Attachment::Stencil => format.has_stencil(),
+                Attachment::DepthStencil => format.has_depth() && format.has_stencil(),
+            };
+
+            if !compatible {
+                return Some(FramebufferIncompleteReason::IncompleteAttachment);
+            }
+
+            if *samples.get_or_insert(renderbuffer.samples()) != renderbuffer.samples() {
+                return Some(FramebufferIncompleteReason::IncompleteMultisample);
+            }
+
+            // WebGL requires all attachments to have the same size
+            #[cfg(target_arch = "wasm32")]
+            {
+                let renderbuffer_size = [renderbuffer.width(), renderbuffer.height()];
+                if *size.get_or_insert(renderbuffer_size) != renderbuffer_size {
+                    return Some(FramebufferIncompleteReason::IncompleteDimensions);
+                }
+            }
+        }
+
+        None
+    }
+}
+
+/// Component type of a color attachment
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum AttachmentType {
+    /// Floating-point or normalized fixed-point attachment
+    Float,
+    /// Signed integer attachment
+    Int,
+    /// Unsigned integer attachment
+    UInt,
+}
+
+impl AttachmentType {
+    /// Attachment type for a value of `FRAMEBUFFER_ATTACHMENT_COMPONENT_TYPE`
+    pub fn from_component_type(component_type: u32) -> Option<Self> {
+        match component_type {
+            crate::gl::FLOAT | crate::gl::UNSIGNED_NORMALIZED | crate::gl::SIGNED_NORMALIZED => {
+                Some(Self::Float)
+            }
+            crate::gl::INT => Some(Self::Int),
+            crate::gl::UNSIGNED_INT => Some(Self::UInt),
+            _ => None,
+        }
+    }
+}
+
+/// Description of a fragment shader output
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct FragmentOutput {
+    /// Name of the output, as declared in the shader
+    pub name: &'static str,
+    /// Location of the output, i.e. its index in the draw buffers
+    pub location: u32,
+    /// Type of attachment this output can be written to
+    pub ty: AttachmentType,
+    /// Number of components written by the output
+    pub components: u32,
+}
+
+impl FragmentOutput {
+    /// Color attachment this output is written to by default
+    pub fn attachment(&self) -> u32 {
+        crate::gl::COLOR_ATTACHMENT0 + self.location
+    }
+}
@@ -0,0 +1,183 @@
+use super::{Attachment, AttachmentSource, Buffer, Framebuffer, InternalFormat, Pod};
+
+impl Framebuffer {
+    pub(super) fn attach(&self, gl: &crate::Context, attachment: u32, source: &AttachmentSource) {
+        unsafe {
+            match source {
+                AttachmentSource::Texture { texture, level } => gl.named_framebuffer_texture(
+                    self.name,
+                    attachment,
+                    texture.name(),
+                    *level as i32,
+                ),
+                AttachmentSource::TextureLayer {
+                    texture,
+                    level,
+                    layer,
+                } => gl.named_framebuffer_texture_layer(
+                    self.name,
+                    attachment,
+                    texture.name(),
+                    *level as i32,
+                    *layer as i32,
+                ),
+                // Cube maps are attached as layered textures, with one layer per face
+                AttachmentSource::CubeFace {
+                    texture,
+                    face,
+                    level,
+                } => gl.named_framebuffer_texture_layer(
+                    self.name,
+                    attachment,
+                    texture.name(),
+                    *level as i32,
+                    face.index() as i32,
+                ),
+                AttachmentSource::Renderbuffer(renderbuffer) => gl.named_framebuffer_renderbuffer(
+                    self.name,
+                    attachment,
+                    crate::gl::RENDERBUFFER,
+                    renderbuffer.name(),
+                ),
+            }
+        }
+    }
+
+    pub(super) fn set_buffers(&self, gl: &crate::Context, draw_buffers: &[u32], read_buffer: u32) {
+        unsafe {
+            gl.named_framebuffer_draw_buffers(
+                self.name,
+                draw_buffers.len() as i32,
+                draw_buffers.as_ptr(),
+            );
+            gl.named_framebuffer_read_buffer(self.name, read_buffer);
+        }
+    }
+
+    pub(super) fn status(&self, gl: &crate::Context) -> u32 {
+        unsafe { gl.check_named_framebuffer_status(self.name, crate::gl::FRAMEBUFFER) }
+    }
+
+    pub(super) fn blit(
+        &self,
+        gl: &crate::Context,
+        target: Option<&Framebuffer>,
+        src: [i32; 4],
+        dst: [i32; 4],
+        mask: u32,
+        filter: u32,
+    ) {
+        unsafe {
+            gl.blit_named_framebuffer(
+                self.name,
+                target.map(|t| t.name).unwrap_or(0),
+                src[0],
+                src[1],
+                src[2],
+                src[3],
+                dst[0],
+                dst[1],
+                dst[2],
+                dst[3],
+                mask,
+                filter,
+            );
+        }
+    }
+
+    pub(super) fn clear_fv(
+        &self,
+        gl: &crate::Context,
+        buffer: u32,
+        draw_buffer: i32,
+        value: &[f32],
+    ) {
+        unsafe {
+            gl.clear_named_framebufferfv(self.name, buffer, draw_buffer, value.as_ptr());
+        }
+    }
+
+    pub(super) fn clear_iv(
+        &self,
+        gl: &crate::Context,
+        buffer: u32,
+        draw_buffer: i32,
+        value: &[i32],
+    ) {
+        unsafe {
+            gl.clear_named_framebufferiv(self.name, buffer, draw_buffer, value.as_ptr());
+        }
+    }
+
+    pub(super) fn clear_uiv(
+        &self,
+        gl: &crate::Context,
+        buffer: u32,
+        draw_buffer: i32,
+        value: &[u32],
+    ) {
+        unsafe {
+            gl.clear_named_framebufferuiv(self.name, buffer, draw_buffer, value.as_ptr());
+        }
+    }
+
+    pub(super) fn clear_fi(&self, gl: &crate::Context, depth: f32, stencil: i32) {
+        unsafe {
+            gl.clear_named_framebufferfi(self.name, crate::gl::DEPTH_STENCIL, 0, depth, stencil);
+        }
+    }
+
+    pub(super) fn read_pixels_to_pack_buffer<T: Pod>(
+        &self,
+        gl: &crate::Context,
+        attachment: Attachment,
+        offset: [i32; 2],
+        size: [u32; 2],
+        format: InternalFormat,
+        alignment: i32,
+        buffer: &Buffer<T>,
+    ) {
+        unsafe {
+            // Keep the bindings of the caller, which glReadPixels can't avoid
+            let mut pack_buffer = 0;
+            gl.get_integerv(crate::gl::PIXEL_PACK_BUFFER_BINDING, &mut pack_buffer);
+            let mut read_framebuffer = 0;
+            gl.get_integerv(crate::gl::READ_FRAMEBUFFER_BINDING, &mut read_framebuffer);
+
+            self.bind(gl, crate::gl::READ_FRAMEBUFFER);
+            buffer.bind(gl, crate::gl::PIXEL_PACK_BUFFER);
+
+            // Switch the read buffer for this read only, keeping the one set by the builder
+            let read_buffer = if let Attachment::Color(_) = attachment {
+                let mut read_buffer = 0;
+                gl.get_integerv(crate::gl::READ_BUFFER, &mut read_buffer);
+                gl.named_framebuffer_read_buffer(self.name, attachment.gl_enum());
+                Some(read_buffer as u32)
+            } else {
+                None
+            };
+
+            gl.pixel_storei(crate::gl::PACK_ALIGNMENT, alignment);
+
+            // With a pixel pack buffer bound, the pointer is an offset into the buffer
+            gl.read_pixels(
+                offset[0],
+                offset[1],
+                size[0] as i32,
+                size[1] as i32,
+                format.pixel_format(),
+                format.pixel_type(),
+                std::ptr::null_mut(),
+            );
+
+            gl.pixel_storei(crate::gl::PACK_ALIGNMENT, 4);
+
+            if let Some(read_buffer) = read_buffer {
+                gl.named_framebuffer_read_buffer(self.name, read_buffer);
+            }
+
+            (**gl).bind_buffer(crate::gl::PIXEL_PACK_BUFFER, pack_buffer as u32);
+            (**gl).bind_framebuffer(crate::gl::READ_FRAMEBUFFER, read_framebuffer as u32);
+        }
+    }
+}
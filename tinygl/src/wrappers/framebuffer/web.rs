@@ -0,0 +1,201 @@
+use super::{Attachment, AttachmentSource, Buffer, Framebuffer, InternalFormat, Pod};
+
+// WebGL has no direct state access, so these functions bind the framebuffer
+
+impl Framebuffer {
+    pub(super) fn attach(&self, gl: &crate::Context, attachment: u32, source: &AttachmentSource) {
+        let target = crate::gl::FRAMEBUFFER;
+        unsafe {
+            self.bind(gl, target);
+        }
+
+        match source {
+            AttachmentSource::Texture { texture, level } => gl.framebuffer_texture_2d(
+                target,
+                attachment,
+                crate::gl::TEXTURE_2D,
+                Some(texture.name()),
+                *level as i32,
+            ),
+            AttachmentSource::TextureLayer {
+                texture,
+                level,
+                layer,
+            } => gl.framebuffer_texture_layer(
+                target,
+                attachment,
+                Some(texture.name()),
+                *level as i32,
+                *layer as i32,
+            ),
+            AttachmentSource::CubeFace {
+                texture,
+                face,
+                level,
+            } => gl.framebuffer_texture_2d(
+                target,
+                attachment,
+                face.target(),
+                Some(texture.name()),
+                *level as i32,
+            ),
+            AttachmentSource::Renderbuffer(renderbuffer) => gl.framebuffer_renderbuffer(
+                target,
+                attachment,
+                crate::gl::RENDERBUFFER,
+                Some(renderbuffer.name()),
+            ),
+        }
+    }
+
+    pub(super) fn set_buffers(&self, gl: &crate::Context, draw_buffers: &[u32], read_buffer: u32) {
+        unsafe {
+            self.bind(gl, crate::gl::FRAMEBUFFER);
+            gl.draw_buffers(draw_buffers);
+        }
+
+        gl.read_buffer(read_buffer);
+    }
+
+    pub(super) fn status(&self, gl: &crate::Context) -> u32 {
+        unsafe {
+            self.bind(gl, crate::gl::FRAMEBUFFER);
+        }
+
+        gl.check_framebuffer_status(crate::gl::FRAMEBUFFER)
+    }
+
+    pub(super) fn blit(
+        &self,
+        gl: &crate::Context,
+        target: Option<&Framebuffer>,
+        src: [i32; 4],
+        dst: [i32; 4],
+        mask: u32,
+        filter: u32,
+    ) {
+        unsafe {
+            self.bind(gl, crate::gl::READ_FRAMEBUFFER);
+            gl.bind_framebuffer(crate::gl::DRAW_FRAMEBUFFER, target);
+        }
+
+        gl.blit_framebuffer(
+            src[0], src[1], src[2], src[3], dst[0], dst[1], dst[2], dst[3], mask, filter,
+        );
+    }
+
+    pub(super) fn clear_fv(
+        &self,
+        gl: &crate::Context,
+        buffer: u32,
+        draw_buffer: i32,
+        value: &[f32],
+    ) {
+        unsafe {
+            self.bind(gl, crate::gl::DRAW_FRAMEBUFFER);
+        }
+
+        gl.clear_bufferfv_with_f32_array(buffer, draw_buffer, value);
+    }
+
+    pub(super) fn clear_iv(
+        &self,
+        gl: &crate::Context,
+        buffer: u32,
+        draw_buffer: i32,
+        value: &[i32],
+    ) {
+        unsafe {
+            self.bind(gl, crate::gl::DRAW_FRAMEBUFFER);
+        }
+
+        gl.clear_bufferiv_with_i32_array(buffer, draw_buffer, value);
+    }
+
+    pub(super) fn clear_uiv(
+        &self,
+        gl: &crate::Context,
+        buffer: u32,
+        draw_buffer: i32,
+        value: &[u32],
+    ) {
+        unsafe {
+            self.bind(gl, crate::gl::DRAW_FRAMEBUFFER);
+        }
+
+        gl.clear_bufferuiv_with_u32_array(buffer, draw_buffer, value);
+    }
+
+    pub(super) fn clear_fi(&self, gl: &crate::Context, depth: f32, stencil: i32) {
+        unsafe {
+            self.bind(gl, crate::gl::DRAW_FRAMEBUFFER);
+        }
+
+        gl.clear_bufferfi(crate::gl::DEPTH_STENCIL, 0, depth, stencil);
+    }
+
+    pub(super) fn read_pixels_to_pack_buffer<T: Pod>(
+        &self,
+        gl: &crate::Context,
+        attachment: Attachment,
+        offset: [i32; 2],
+        size: [u32; 2],
+        format: InternalFormat,
+        alignment: i32,
+        buffer: &Buffer<T>,
+    ) {
+        use wasm_bindgen::JsCast;
+
+        // Keep the bindings of the caller, which readPixels can't avoid
+        let pack_buffer = gl
+            .get_parameter(crate::gl::PIXEL_PACK_BUFFER_BINDING)
+            .expect("failed to query the pixel pack buffer")
+            .dyn_into::<crate::gl::Buffer>()
+            .ok();
+        let read_framebuffer = gl
+            .get_parameter(crate::gl::READ_FRAMEBUFFER_BINDING)
+            .expect("failed to query the read framebuffer")
+            .dyn_into::<crate::gl::Framebuffer>()
+            .ok();
+
+        unsafe {
+            self.bind(gl, crate::gl::READ_FRAMEBUFFER);
+            buffer.bind(gl, crate::gl::PIXEL_PACK_BUFFER);
+        }
+
+        // Switch the read buffer for this read only, keeping the one set by the builder
+        let read_buffer = if let Attachment::Color(_) = attachment {
+            let read_buffer = gl
+                .get_parameter(crate::gl::READ_BUFFER)
+                .expect("failed to query the read buffer")
+                .as_f64()
+                .unwrap() as u32;
+            gl.read_buffer(attachment.gl_enum());
+            Some(read_buffer)
+        } else {
+            None
+        };
+
+        gl.pixel_storei(crate::gl::PACK_ALIGNMENT, alignment);
+
+        gl.read_pixels_with_i32(
+            offset[0],
+            offset[1],
+            size[0] as i32,
+            size[1] as i32,
+            format.pixel_format(),
+            format.pixel_type(),
+            0,
+        )
+        .expect("invalid pixel read");
+
+        gl.pixel_storei(crate::gl::PACK_ALIGNMENT, 4);
+
+        if let Some(read_buffer) = read_buffer {
+            gl.read_buffer(read_buffer);
+        }
+
+        (**gl).bind_buffer(crate::gl::PIXEL_PACK_BUFFER, pack_buffer.as_ref());
+        (**gl).bind_framebuffer(crate::gl::READ_FRAMEBUFFER, read_framebuffer.as_ref());
+    }
+}
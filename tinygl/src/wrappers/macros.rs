@@ -26,16 +26,20 @@ macro_rules! impl_name {
 
 macro_rules! impl_nnew {
     ($e:ident, $fd:ident, $fw:ident) => {
+        impl_nnew!($e, $fd, $fw, |name| Self { name });
+    };
+
+    ($e:ident, $fd:ident, $fw:ident, |$name:ident| $ctor:expr) => {
         #[cfg(not(target_arch = "wasm32"))]
         pub fn new(gl: &crate::Context) -> crate::Result<Self> {
             unsafe {
-                let mut name = 0u32;
-                gl.$fd(1, &mut name);
-                if name == 0 {
+                let mut $name = 0u32;
+                gl.$fd(1, &mut $name);
+                if $name == 0 {
                     return Err(crate::Error::$e(OpenGlErrorCode(gl.get_error())));
                 }
 
-                Ok(Self { name })
+                Ok($ctor)
             }
         }
 
@@ -43,7 +47,7 @@ macro_rules! impl_nnew {
         pub fn new(gl: &crate::Context) -> crate::Result<Self> {
             Ok(gl
                 .$fw()
-                .map(|name| Self { name })
+                .map(|$name| $ctor)
                 .ok_or_else(|| crate::Error::$e(OpenGlErrorCode(gl.get_error())))?)
         }
     };
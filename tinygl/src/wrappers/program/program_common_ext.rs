@@ -5,7 +5,7 @@ use super::ProgramCommon;
 
 pub trait ProgramCommonExt {
     unsafe fn use_program(&self, gl: &Context);
-    fn get_uniform_location(&self, gl: &Context, name: &str) -> UniformLocation;
+    fn get_uniform_location(&self, gl: &Context, name: &str) -> UniformLocation<'_>;
     fn get_uniform_block_index(&self, gl: &Context, name: &str) -> Option<UniformBlock>;
 }
 
@@ -15,10 +15,10 @@ impl<T: ProgramCommon> ProgramCommonExt for T {
     }
 
     #[cfg(not(target_arch = "wasm32"))]
-    fn get_uniform_location(&self, gl: &Context, name: &str) -> UniformLocation {
+    fn get_uniform_location(&self, gl: &Context, name: &str) -> UniformLocation<'_> {
         use std::ffi::CString;
 
-        UniformLocation::new(self.name(), unsafe {
+        UniformLocation::new(self, unsafe {
             let name = CString::new(name).expect("invalid location identifier");
             let loc = gl.get_uniform_location(self.name(), name.as_ptr());
 
@@ -31,8 +31,8 @@ impl<T: ProgramCommon> ProgramCommonExt for T {
     }
 
     #[cfg(target_arch = "wasm32")]
-    fn get_uniform_location(&self, gl: &Context, name: &str) -> UniformLocation {
-        UniformLocation::new(gl.get_uniform_location(self.name(), name))
+    fn get_uniform_location(&self, gl: &Context, name: &str) -> UniformLocation<'_> {
+        UniformLocation::new(self, gl.get_uniform_location(self.name(), name))
     }
 
     #[cfg(not(target_arch = "wasm32"))]
@@ -1,21 +1,186 @@
 use crate::OpenGlErrorCode;
 
+use super::InternalFormat;
+
 pub struct Renderbuffer {
     name: crate::gl::Renderbuffer,
+    format: Option<InternalFormat>,
+    width: u32,
+    height: u32,
+    samples: u32,
 }
 
 impl Renderbuffer {
     impl_nnew!(
         RenderbufferCreationFailed,
         create_renderbuffers,
-        create_renderbuffer
+        create_renderbuffer,
+        |name| Self::from_name(name)
     );
 
+    fn from_name(name: crate::gl::Renderbuffer) -> Self {
+        Self {
+            name,
+            format: None,
+            width: 0,
+            height: 0,
+            samples: 0,
+        }
+    }
+
+    /// Create a renderbuffer and allocate its storage
+    ///
+    /// # Parameters
+    ///
+    /// * `gl`: GL context
+    /// * `format`: internal format of the renderbuffer
+    /// * `width`: width of the renderbuffer
+    /// * `height`: height of the renderbuffer
+    /// * `samples`: number of samples per pixel, 0 for a single-sampled renderbuffer
+    ///
+    /// # Errors
+    ///
+    /// Returns [crate::Error::UnsupportedSampleCount] if `samples` is larger than
+    /// [Renderbuffer::max_samples] for `format`.
+    pub fn with_storage(
+        gl: &crate::Context,
+        format: InternalFormat,
+        width: u32,
+        height: u32,
+        samples: u32,
+    ) -> crate::Result<Self> {
+        let max_samples = Self::max_samples(gl, format);
+        if samples > max_samples {
+            return Err(crate::Error::UnsupportedSampleCount(samples, max_samples));
+        }
+
+        let mut renderbuffer = Self::new(gl)?;
+        renderbuffer.storage(gl, format, width, height, samples);
+        renderbuffer.format = Some(format);
+        renderbuffer.width = width;
+        renderbuffer.height = height;
+        renderbuffer.samples = samples;
+        Ok(renderbuffer)
+    }
+
     impl_name!(pub crate::gl::RenderbufferName);
 
     pub unsafe fn bind(&self, gl: &crate::Context) {
         gl.bind_renderbuffer(crate::gl::RENDERBUFFER, Some(self));
     }
+
+    /// Internal format of the renderbuffer, `None` if its storage was not allocated
+    pub fn format(&self) -> Option<InternalFormat> {
+        self.format
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Number of samples per pixel, 0 if the renderbuffer is single-sampled
+    pub fn samples(&self) -> u32 {
+        self.samples
+    }
+
+    /// Maximum number of samples supported for renderbuffers of a given format
+    ///
+    /// This is lower than `MAX_SAMPLES` for some formats, such as integer formats.
+    ///
+    /// # Parameters
+    ///
+    /// * `gl`: GL context
+    /// * `format`: internal format of the renderbuffer
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn max_samples(gl: &crate::Context, format: InternalFormat) -> u32 {
+        // Supported sample counts are listed in descending order, and none are listed for
+        // formats which can't be multisampled
+        let mut max_samples = 0;
+        unsafe {
+            gl.get_internalformativ(
+                crate::gl::RENDERBUFFER,
+                format.gl_enum(),
+                crate::gl::SAMPLES,
+                1,
+                &mut max_samples,
+            );
+        }
+
+        max_samples as _
+    }
+
+    /// Maximum number of samples supported for renderbuffers of a given format
+    ///
+    /// This is lower than `MAX_SAMPLES` for some formats, such as integer formats.
+    ///
+    /// # Parameters
+    ///
+    /// * `gl`: GL context
+    /// * `format`: internal format of the renderbuffer
+    #[cfg(target_arch = "wasm32")]
+    pub fn max_samples(gl: &crate::Context, format: InternalFormat) -> u32 {
+        use wasm_bindgen::JsCast;
+
+        // Supported sample counts are listed in descending order, and none are listed for
+        // formats which can't be multisampled
+        gl.get_internalformat_parameter(
+            crate::gl::RENDERBUFFER,
+            format.gl_enum(),
+            crate::gl::SAMPLES,
+        )
+        .expect("failed to get SAMPLES")
+        .unchecked_into::<js_sys::Int32Array>()
+        .to_vec()
+        .first()
+        .copied()
+        .unwrap_or(0) as _
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn storage(
+        &self,
+        gl: &crate::Context,
+        format: InternalFormat,
+        width: u32,
+        height: u32,
+        samples: u32,
+    ) {
+        unsafe {
+            gl.named_renderbuffer_storage_multisample(
+                self.name,
+                samples as i32,
+                format.gl_enum(),
+                width as i32,
+                height as i32,
+            );
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn storage(
+        &self,
+        gl: &crate::Context,
+        format: InternalFormat,
+        width: u32,
+        height: u32,
+        samples: u32,
+    ) {
+        unsafe {
+            self.bind(gl);
+        }
+
+        gl.renderbuffer_storage_multisample(
+            crate::gl::RENDERBUFFER,
+            samples as i32,
+            format.gl_enum(),
+            width as i32,
+            height as i32,
+        );
+    }
 }
 
 impl_ndrop!(Renderbuffer, delete_renderbuffers, delete_renderbuffer);
@@ -0,0 +1,282 @@
+use crate::OpenGlErrorCode;
+
+use super::{CompareFunc, GlRefHandle, MagFilter, MinFilter, Wrap};
+
+// Same value for the core, ARB and EXT versions of the parameter
+const TEXTURE_MAX_ANISOTROPY: u32 = 0x84FE;
+
+/// Sampler object, holding sampling state independently of textures
+pub struct Sampler {
+    name: crate::gl::Sampler,
+}
+
+impl Sampler {
+    impl_nnew!(SamplerCreationFailed, create_samplers, create_sampler);
+
+    impl_name!(pub crate::gl::SamplerName);
+
+    /// Create a builder for a sampler
+    pub fn builder() -> SamplerBuilder {
+        SamplerBuilder::default()
+    }
+
+    /// Bind this sampler to a texture unit
+    ///
+    /// # Parameters
+    ///
+    /// * `gl`: GL context
+    /// * `unit`: index of the texture unit
+    pub unsafe fn bind(&self, gl: &crate::Context, unit: u32) {
+        gl.bind_sampler(unit, make_name!(Option => self.name));
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn parameter_i(&self, gl: &crate::Context, pname: u32, value: i32) {
+        unsafe {
+            gl.sampler_parameteri(self.name, pname, value);
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn parameter_i(&self, gl: &crate::Context, pname: u32, value: i32) {
+        gl.sampler_parameteri(&self.name, pname, value);
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn parameter_f(&self, gl: &crate::Context, pname: u32, value: f32) {
+        unsafe {
+            gl.sampler_parameterf(self.name, pname, value);
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn parameter_f(&self, gl: &crate::Context, pname: u32, value: f32) {
+        gl.sampler_parameterf(&self.name, pname, value);
+    }
+}
+
+impl_ndrop!(Sampler, delete_samplers, delete_sampler);
+
+/// Builder for [Sampler] objects
+///
+/// Parameters that are not set keep their GL default value.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct SamplerBuilder {
+    min_filter: Option<MinFilter>,
+    mag_filter: Option<MagFilter>,
+    wrap: [Option<Wrap>; 3],
+    max_anisotropy: Option<f32>,
+    lod_range: Option<(f32, f32)>,
+    #[cfg(not(target_arch = "wasm32"))]
+    lod_bias: Option<f32>,
+    #[cfg(not(target_arch = "wasm32"))]
+    border_color: Option<[f32; 4]>,
+    compare: Option<Option<CompareFunc>>,
+}
+
+impl SamplerBuilder {
+    /// Set the filter used when a texel covers less than a pixel
+    pub fn min_filter(self, filter: MinFilter) -> Self {
+        Self {
+            min_filter: Some(filter),
+            ..self
+        }
+    }
+
+    /// Set the filter used when a texel covers more than a pixel
+    pub fn mag_filter(self, filter: MagFilter) -> Self {
+        Self {
+            mag_filter: Some(filter),
+            ..self
+        }
+    }
+
+    /// Set both the minification and magnification filters
+    ///
+    /// # Parameters
+    ///
+    /// * `filter`: magnification filter, also used between texels of the base level
+    /// * `mipmaps`: true to filter linearly between mip levels
+    pub fn filter(self, filter: MagFilter, mipmaps: bool) -> Self {
+        let min_filter = match (filter, mipmaps) {
+            (MagFilter::Nearest, false) => MinFilter::Nearest,
+            (MagFilter::Linear, false) => MinFilter::Linear,
+            (MagFilter::Nearest, true) => MinFilter::NearestMipmapLinear,
+            (MagFilter::Linear, true) => MinFilter::LinearMipmapLinear,
+        };
+
+        self.min_filter(min_filter).mag_filter(filter)
+    }
+
+    /// Set the wrap mode along all axes
+    pub fn wrap(self, wrap: Wrap) -> Self {
+        Self {
+            wrap: [Some(wrap); 3],
+            ..self
+        }
+    }
+
+    /// Set the wrap mode along each axis
+    ///
+    /// # Parameters
+    ///
+    /// * `s`: wrap mode for the first texture coordinate
+    /// * `t`: wrap mode for the second texture coordinate
+    /// * `r`: wrap mode for the third texture coordinate
+    pub fn wrap_axes(self, s: Wrap, t: Wrap, r: Wrap) -> Self {
+        Self {
+            wrap: [Some(s), Some(t), Some(r)],
+            ..self
+        }
+    }
+
+    /// Set the maximum degree of anisotropic filtering
+    ///
+    /// This is ignored if anisotropic filtering is not supported, which requires OpenGL 4.6,
+    /// `GL_ARB_texture_filter_anisotropic` or `GL_EXT_texture_filter_anisotropic` on desktop, and
+    /// `EXT_texture_filter_anisotropic` on WebGL.
+    pub fn max_anisotropy(self, max_anisotropy: f32) -> Self {
+        Self {
+            max_anisotropy: Some(max_anisotropy),
+            ..self
+        }
+    }
+
+    /// Set the range of level of details that can be sampled
+    pub fn lod_range(self, min: f32, max: f32) -> Self {
+        Self {
+            lod_range: Some((min, max)),
+            ..self
+        }
+    }
+
+    /// Set the bias added to the level of detail before sampling
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn lod_bias(self, bias: f32) -> Self {
+        Self {
+            lod_bias: Some(bias),
+            ..self
+        }
+    }
+
+    /// Set the color sampled outside of textures with [Wrap::ClampToBorder]
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn border_color(self, color: [f32; 4]) -> Self {
+        Self {
+            border_color: Some(color),
+            ..self
+        }
+    }
+
+    /// Set the depth comparison used by shadow samplers, `None` to disable it
+    pub fn compare(self, func: Option<CompareFunc>) -> Self {
+        Self {
+            compare: Some(func),
+            ..self
+        }
+    }
+
+    /// Create the sampler and apply the parameters of this builder
+    ///
+    /// # Parameters
+    ///
+    /// * `gl`: GL context
+    pub fn build(self, gl: &crate::Context) -> crate::Result<GlRefHandle<'_, Sampler>> {
+        let sampler = GlRefHandle::new(gl, Sampler::new(gl)?);
+
+        if let Some(filter) = self.min_filter {
+            sampler.parameter_i(gl, crate::gl::TEXTURE_MIN_FILTER, filter.gl_enum() as i32);
+        }
+
+        if let Some(filter) = self.mag_filter {
+            sampler.parameter_i(gl, crate::gl::TEXTURE_MAG_FILTER, filter.gl_enum() as i32);
+        }
+
+        let wrap_names = [
+            crate::gl::TEXTURE_WRAP_S,
+            crate::gl::TEXTURE_WRAP_T,
+            crate::gl::TEXTURE_WRAP_R,
+        ];
+
+        for (pname, wrap) in wrap_names.iter().zip(self.wrap.iter()) {
+            if let Some(wrap) = wrap {
+                sampler.parameter_i(gl, *pname, wrap.gl_enum() as i32);
+            }
+        }
+
+        if let Some(max_anisotropy) = self.max_anisotropy {
+            if Self::supports_anisotropy(gl) {
+                sampler.parameter_f(gl, TEXTURE_MAX_ANISOTROPY, max_anisotropy);
+            }
+        }
+
+        if let Some((min, max)) = self.lod_range {
+            sampler.parameter_f(gl, crate::gl::TEXTURE_MIN_LOD, min);
+            sampler.parameter_f(gl, crate::gl::TEXTURE_MAX_LOD, max);
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            if let Some(bias) = self.lod_bias {
+                sampler.parameter_f(gl, crate::gl::TEXTURE_LOD_BIAS, bias);
+            }
+
+            if let Some(color) = self.border_color {
+                unsafe {
+                    gl.sampler_parameterfv(
+                        sampler.name,
+                        crate::gl::TEXTURE_BORDER_COLOR,
+                        color.as_ptr(),
+                    );
+                }
+            }
+        }
+
+        if let Some(compare) = self.compare {
+            let (mode, func) = match compare {
+                Some(func) => (crate::gl::COMPARE_REF_TO_TEXTURE, func),
+                None => (crate::gl::NONE, CompareFunc::LessEqual),
+            };
+
+            sampler.parameter_i(gl, crate::gl::TEXTURE_COMPARE_MODE, mode as i32);
+            sampler.parameter_i(gl, crate::gl::TEXTURE_COMPARE_FUNC, func.gl_enum() as i32);
+        }
+
+        Ok(sampler)
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn supports_anisotropy(gl: &crate::Context) -> bool {
+        unsafe {
+            let (mut major, mut minor) = (0, 0);
+            gl.get_integerv(crate::gl::MAJOR_VERSION, &mut major);
+            gl.get_integerv(crate::gl::MINOR_VERSION, &mut minor);
+
+            // Anisotropic filtering is only core since OpenGL 4.6
+            if (major, minor) >= (4, 6) {
+                return true;
+            }
+
+            let mut extensions = 0;
+            gl.get_integerv(crate::gl::NUM_EXTENSIONS, &mut extensions);
+
+            (0..extensions as u32).any(|i| {
+                let name = gl.get_stringi(crate::gl::EXTENSIONS, i);
+                !name.is_null()
+                    && matches!(
+                        std::ffi::CStr::from_ptr(name as *const _).to_bytes(),
+                        b"GL_ARB_texture_filter_anisotropic" | b"GL_EXT_texture_filter_anisotropic"
+                    )
+            })
+        }
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    fn supports_anisotropy(gl: &crate::Context) -> bool {
+        // Extensions have to be requested before their enums can be used
+        matches!(
+            gl.get_extension("EXT_texture_filter_anisotropic"),
+            Ok(Some(_))
+        )
+    }
+}
@@ -3,7 +3,7 @@ use crate::OpenGlErrorCode;
 #[cfg(all(not(target_arch = "wasm32"), feature = "opengl46"))]
 mod binary_shader;
 #[cfg(all(not(target_arch = "wasm32"), feature = "opengl46"))]
-pub use binary_shader::BinaryShader;
+pub use binary_shader::{BinaryShader, SpecializationInfo};
 
 mod runtime_shader;
 pub use runtime_shader::*;
@@ -12,7 +12,7 @@ mod shader_common;
 pub use shader_common::*;
 
 mod source_shader;
-pub use source_shader::SourceShader;
+pub use source_shader::{inject_defines, SourceShader};
 
 /// Build a shader name and try to compile it using the given callback
 unsafe fn make_shader<F>(
@@ -1,12 +1,41 @@
+use std::ffi::CStr;
+
 use crate::Context;
 
 use super::{make_shader, ShaderCommon};
 
+/// Specialization of a SPIR-V shader, passed to glSpecializeShader
+#[derive(Debug, Clone, Copy)]
+pub struct SpecializationInfo<'a> {
+    /// Name of the entry point
+    pub entry_point: &'a CStr,
+    /// Ids of the specialization constants to set
+    pub constant_index: &'a [u32],
+    /// Values of the specialization constants, as 32-bit patterns
+    pub constant_value: &'a [u32],
+}
+
+impl Default for SpecializationInfo<'_> {
+    fn default() -> Self {
+        Self {
+            entry_point: CStr::from_bytes_with_nul(b"main\0").unwrap(),
+            constant_index: &[],
+            constant_value: &[],
+        }
+    }
+}
+
 pub fn build_bin_shader(
     gl: &Context,
     binary: &[u8],
     kind: u32,
+    specialization: &SpecializationInfo,
 ) -> crate::Result<crate::gl::Shader> {
+    debug_assert_eq!(
+        specialization.constant_index.len(),
+        specialization.constant_value.len()
+    );
+
     unsafe {
         make_shader(gl, kind, |shader_name| {
             use crate::gl;
@@ -23,10 +52,10 @@ pub fn build_bin_shader(
             // Specialize the binary
             gl.specialize_shader(
                 shader_name,
-                b"main\0".as_ptr() as *const i8,
-                0,
-                std::ptr::null(),
-                std::ptr::null(),
+                specialization.entry_point.as_ptr(),
+                specialization.constant_index.len() as u32,
+                specialization.constant_index.as_ptr(),
+                specialization.constant_value.as_ptr(),
             );
         })
     }
@@ -37,6 +66,14 @@ pub trait BinaryShader<'a>: ShaderCommon {
     fn get_binary() -> &'a [u8];
 
     fn build(gl: &Context, kind: u32) -> crate::Result<crate::gl::Shader> {
-        build_bin_shader(gl, Self::get_binary(), kind)
+        Self::build_specialized(gl, kind, &SpecializationInfo::default())
+    }
+
+    fn build_specialized(
+        gl: &Context,
+        kind: u32,
+        specialization: &SpecializationInfo,
+    ) -> crate::Result<crate::gl::Shader> {
+        build_bin_shader(gl, Self::get_binary(), kind, specialization)
     }
 }
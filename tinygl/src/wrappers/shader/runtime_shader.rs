@@ -11,9 +11,19 @@ pub struct RuntimeShader {
 impl RuntimeShader {
     #[cfg(all(not(target_arch = "wasm32"), feature = "opengl46"))]
     pub fn build_bin(gl: &Context, binary: &[u8], kind: u32) -> crate::Result<Self> {
+        Self::build_bin_specialized(gl, binary, kind, &Default::default())
+    }
+
+    #[cfg(all(not(target_arch = "wasm32"), feature = "opengl46"))]
+    pub fn build_bin_specialized(
+        gl: &Context,
+        binary: &[u8],
+        kind: u32,
+        specialization: &super::SpecializationInfo,
+    ) -> crate::Result<Self> {
         Ok(Self {
             kind,
-            name: super::binary_shader::build_bin_shader(gl, binary, kind)?,
+            name: super::binary_shader::build_bin_shader(gl, binary, kind, specialization)?,
         })
     }
 
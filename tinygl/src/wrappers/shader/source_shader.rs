@@ -1,3 +1,5 @@
+use std::borrow::Cow;
+
 use crate::Context;
 
 use super::{make_shader, ShaderCommon};
@@ -33,6 +35,45 @@ pub fn build_src_shader(gl: &Context, src: &str, kind: u32) -> crate::Result<cra
     }
 }
 
+/// Insert preprocessor definitions in a shader source, after its `#version` directive
+///
+/// # Parameters
+///
+/// * `src`: GLSL source of the shader
+/// * `defines`: macro names and values to define
+pub fn inject_defines<'s>(src: &'s str, defines: &[(&str, String)]) -> Cow<'s, str> {
+    use std::fmt::Write;
+
+    if defines.is_empty() {
+        return Cow::Borrowed(src);
+    }
+
+    // The #version directive must come before anything else
+    let trimmed = src.trim_start();
+    let split = if trimmed.starts_with("#version") {
+        let start = src.len() - trimmed.len();
+        trimmed
+            .find('\n')
+            .map(|pos| start + pos + 1)
+            .unwrap_or_else(|| src.len())
+    } else {
+        0
+    };
+
+    let mut output = String::with_capacity(src.len());
+    output.push_str(&src[..split]);
+    if !output.is_empty() && !output.ends_with('\n') {
+        output.push('\n');
+    }
+
+    for (name, value) in defines {
+        writeln!(output, "#define {} {}", name, value).ok();
+    }
+
+    output.push_str(&src[split..]);
+    Cow::Owned(output)
+}
+
 /// GLSL shader wrapper
 pub trait SourceShader<'a>: ShaderCommon {
     fn get_source() -> &'a str;
@@ -40,4 +81,12 @@ pub trait SourceShader<'a>: ShaderCommon {
     fn build(gl: &Context, kind: u32) -> crate::Result<crate::gl::Shader> {
         build_src_shader(gl, Self::get_source(), kind)
     }
+
+    fn build_with_defines(
+        gl: &Context,
+        kind: u32,
+        defines: &[(&str, String)],
+    ) -> crate::Result<crate::gl::Shader> {
+        build_src_shader(gl, &inject_defines(Self::get_source(), defines), kind)
+    }
 }
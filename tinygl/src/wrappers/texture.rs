@@ -1,5 +1,23 @@
 use crate::OpenGlErrorCode;
 
+use super::Pod;
+
+mod format;
+pub use format::*;
+
+mod params;
+pub use params::*;
+
+#[cfg(not(target_arch = "wasm32"))]
+mod desktop;
+#[cfg(not(target_arch = "wasm32"))]
+use desktop::*;
+
+#[cfg(target_arch = "wasm32")]
+mod web;
+#[cfg(target_arch = "wasm32")]
+use web::*;
+
 pub struct Texture {
     name: crate::gl::Texture,
 }
@@ -15,3 +33,729 @@ impl Texture {
 }
 
 impl_ndrop!(Texture, delete_textures, delete_texture);
+
+/// Number of levels in a full mipmap chain for a texture of the given size
+///
+/// # Parameters
+///
+/// * `width`: width of the base level
+/// * `height`: height of the base level
+/// * `depth`: depth of the base level, 1 for 2D textures
+pub fn max_mip_levels(width: u32, height: u32, depth: u32) -> u32 {
+    32 - width.max(height).max(depth).max(1).leading_zeros()
+}
+
+/// Largest pack or unpack alignment compatible with rows of `row_size` bytes
+pub(crate) fn row_alignment(row_size: usize) -> i32 {
+    [8, 4, 2]
+        .iter()
+        .copied()
+        .find(|alignment| row_size & (alignment - 1) == 0)
+        .unwrap_or(1) as i32
+}
+
+fn mip_size(size: u32, level: u32) -> u32 {
+    (size >> level).max(1)
+}
+
+/// Region of a texture level, the third axis being the layer or face for array and cube textures
+struct Region {
+    level: u32,
+    offset: [u32; 3],
+    size: [u32; 3],
+}
+
+impl Region {
+    fn check(&self, levels: u32, extent: [u32; 3]) {
+        assert!(
+            self.level < levels,
+            "level {} out of bounds for a texture with {} level(s)",
+            self.level,
+            levels
+        );
+
+        for axis in 0..3 {
+            assert!(
+                self.offset[axis] + self.size[axis] <= extent[axis],
+                "region {:?}+{:?} out of bounds for level {} of size {:?}",
+                self.offset,
+                self.size,
+                self.level,
+                extent
+            );
+        }
+    }
+}
+
+fn upload_region<P: Pod>(
+    gl: &crate::Context,
+    texture: &Texture,
+    target: u32,
+    format: InternalFormat,
+    region: &Region,
+    data: &[P],
+) -> crate::Result<()> {
+    let pixels = region
+        .size
+        .iter()
+        .map(|size| *size as usize)
+        .product::<usize>();
+    assert_eq!(
+        std::mem::size_of_val(data),
+        pixels * format.pixel_size(),
+        "pixel data does not match the size of the region"
+    );
+
+    let bytes = unsafe {
+        std::slice::from_raw_parts(data.as_ptr() as *const u8, std::mem::size_of_val(data))
+    };
+
+    let alignment = row_alignment(region.size[0] as usize * format.pixel_size());
+    pixel_store(gl, crate::gl::UNPACK_ALIGNMENT, alignment);
+    let result = sub_image(gl, texture, target, region, format, bytes);
+    pixel_store(gl, crate::gl::UNPACK_ALIGNMENT, 4);
+    result
+}
+
+macro_rules! impl_typed_texture {
+    ($t:ident, $target:expr) => {
+        impl $t {
+            /// Target this texture is bound to
+            pub const TARGET: u32 = $target;
+
+            pub fn format(&self) -> InternalFormat {
+                self.format
+            }
+
+            /// Number of mip levels in the texture storage
+            pub fn levels(&self) -> u32 {
+                self.levels
+            }
+
+            /// Bind this texture to a texture unit
+            ///
+            /// # Parameters
+            ///
+            /// * `gl`: GL context
+            /// * `unit`: index of the texture unit
+            pub unsafe fn bind(&self, gl: &crate::Context, unit: u32) {
+                bind_unit(gl, &self.texture, Self::TARGET, unit);
+            }
+
+            /// Generate all mip levels from the base level
+            pub fn generate_mipmaps(&self, gl: &crate::Context) {
+                generate_mipmap(gl, &self.texture, Self::TARGET);
+            }
+
+            pub fn set_min_filter(&self, gl: &crate::Context, filter: MinFilter) {
+                let value = filter.gl_enum() as i32;
+                parameter_i(
+                    gl,
+                    &self.texture,
+                    Self::TARGET,
+                    crate::gl::TEXTURE_MIN_FILTER,
+                    value,
+                );
+            }
+
+            pub fn set_mag_filter(&self, gl: &crate::Context, filter: MagFilter) {
+                let value = filter.gl_enum() as i32;
+                parameter_i(
+                    gl,
+                    &self.texture,
+                    Self::TARGET,
+                    crate::gl::TEXTURE_MAG_FILTER,
+                    value,
+                );
+            }
+
+            /// Set the wrap mode along all axes
+            pub fn set_wrap(&self, gl: &crate::Context, wrap: Wrap) {
+                self.set_wrap_s(gl, wrap);
+                self.set_wrap_t(gl, wrap);
+                self.set_wrap_r(gl, wrap);
+            }
+
+            pub fn set_wrap_s(&self, gl: &crate::Context, wrap: Wrap) {
+                let value = wrap.gl_enum() as i32;
+                parameter_i(
+                    gl,
+                    &self.texture,
+                    Self::TARGET,
+                    crate::gl::TEXTURE_WRAP_S,
+                    value,
+                );
+            }
+
+            pub fn set_wrap_t(&self, gl: &crate::Context, wrap: Wrap) {
+                let value = wrap.gl_enum() as i32;
+                parameter_i(
+                    gl,
+                    &self.texture,
+                    Self::TARGET,
+                    crate::gl::TEXTURE_WRAP_T,
+                    value,
+                );
+            }
+
+            pub fn set_wrap_r(&self, gl: &crate::Context, wrap: Wrap) {
+                let value = wrap.gl_enum() as i32;
+                parameter_i(
+                    gl,
+                    &self.texture,
+                    Self::TARGET,
+                    crate::gl::TEXTURE_WRAP_R,
+                    value,
+                );
+            }
+
+            /// Set the depth comparison used by shadow samplers, `None` to disable it
+            pub fn set_compare(&self, gl: &crate::Context, func: Option<CompareFunc>) {
+                let (mode, func) = match func {
+                    Some(func) => (crate::gl::COMPARE_REF_TO_TEXTURE, func),
+                    None => (crate::gl::NONE, CompareFunc::LessEqual),
+                };
+
+                let texture = &self.texture;
+                parameter_i(
+                    gl,
+                    texture,
+                    Self::TARGET,
+                    crate::gl::TEXTURE_COMPARE_MODE,
+                    mode as i32,
+                );
+                parameter_i(
+                    gl,
+                    texture,
+                    Self::TARGET,
+                    crate::gl::TEXTURE_COMPARE_FUNC,
+                    func.gl_enum() as i32,
+                );
+            }
+
+            /// Set the range of level of details that can be sampled
+            pub fn set_lod_range(&self, gl: &crate::Context, min: f32, max: f32) {
+                parameter_f(
+                    gl,
+                    &self.texture,
+                    Self::TARGET,
+                    crate::gl::TEXTURE_MIN_LOD,
+                    min,
+                );
+                parameter_f(
+                    gl,
+                    &self.texture,
+                    Self::TARGET,
+                    crate::gl::TEXTURE_MAX_LOD,
+                    max,
+                );
+            }
+
+            /// Set the range of mip levels that can be sampled
+            pub fn set_level_range(&self, gl: &crate::Context, base: u32, max: u32) {
+                let texture = &self.texture;
+                parameter_i(
+                    gl,
+                    texture,
+                    Self::TARGET,
+                    crate::gl::TEXTURE_BASE_LEVEL,
+                    base as i32,
+                );
+                parameter_i(
+                    gl,
+                    texture,
+                    Self::TARGET,
+                    crate::gl::TEXTURE_MAX_LEVEL,
+                    max as i32,
+                );
+            }
+
+            #[cfg(not(target_arch = "wasm32"))]
+            pub fn set_lod_bias(&self, gl: &crate::Context, bias: f32) {
+                parameter_f(
+                    gl,
+                    &self.texture,
+                    Self::TARGET,
+                    crate::gl::TEXTURE_LOD_BIAS,
+                    bias,
+                );
+            }
+
+            #[cfg(not(target_arch = "wasm32"))]
+            pub fn set_border_color(&self, gl: &crate::Context, color: [f32; 4]) {
+                parameter_fv(
+                    gl,
+                    &self.texture,
+                    Self::TARGET,
+                    crate::gl::TEXTURE_BORDER_COLOR,
+                    &color,
+                );
+            }
+        }
+
+        impl std::ops::Deref for $t {
+            type Target = Texture;
+
+            fn deref(&self) -> &Self::Target {
+                &self.texture
+            }
+        }
+
+        impl super::GlDrop for $t {
+            unsafe fn drop(&mut self, gl: &crate::Context) {
+                super::GlDrop::drop(&mut self.texture, gl);
+            }
+        }
+    };
+}
+
+/// 2D texture with immutable storage
+pub struct Texture2D {
+    texture: Texture,
+    format: InternalFormat,
+    levels: u32,
+    width: u32,
+    height: u32,
+}
+
+impl Texture2D {
+    /// Create a 2D texture and allocate its storage
+    ///
+    /// # Parameters
+    ///
+    /// * `gl`: GL context
+    /// * `format`: internal format of the texture
+    /// * `width`: width of the base level
+    /// * `height`: height of the base level
+    /// * `levels`: number of mip levels, at most [max_mip_levels]
+    pub fn with_storage(
+        gl: &crate::Context,
+        format: InternalFormat,
+        width: u32,
+        height: u32,
+        levels: u32,
+    ) -> crate::Result<Self> {
+        assert!(levels >= 1 && levels <= max_mip_levels(width, height, 1));
+
+        let texture = create(gl, Self::TARGET)?;
+        storage(
+            gl,
+            &texture,
+            Self::TARGET,
+            format,
+            levels,
+            [width, height, 1],
+        );
+
+        Ok(Self {
+            texture,
+            format,
+            levels,
+            width,
+            height,
+        })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    /// Size of a mip level
+    pub fn level_size(&self, level: u32) -> [u32; 2] {
+        [mip_size(self.width, level), mip_size(self.height, level)]
+    }
+
+    /// Upload pixels to a region of a mip level
+    ///
+    /// # Parameters
+    ///
+    /// * `gl`: GL context
+    /// * `level`: mip level to update
+    /// * `offset`: offset of the region, in pixels
+    /// * `size`: size of the region, in pixels
+    /// * `data`: pixel data, laid out as expected for [InternalFormat::pixel_format] and
+    ///   [InternalFormat::pixel_type]
+    ///
+    /// # Errors
+    ///
+    /// Returns [crate::Error::TextureUploadFailed] if GL rejects the pixel data.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the level or region is out of bounds, or if `data` does not match its size.
+    pub fn upload<P: Pod>(
+        &self,
+        gl: &crate::Context,
+        level: u32,
+        offset: [u32; 2],
+        size: [u32; 2],
+        data: &[P],
+    ) -> crate::Result<()> {
+        let region = Region {
+            level,
+            offset: [offset[0], offset[1], 0],
+            size: [size[0], size[1], 1],
+        };
+
+        let [width, height] = self.level_size(level);
+        region.check(self.levels, [width, height, 1]);
+        upload_region(gl, &self.texture, Self::TARGET, self.format, &region, data)
+    }
+
+    /// Upload pixels to a whole mip level
+    pub fn upload_level<P: Pod>(
+        &self,
+        gl: &crate::Context,
+        level: u32,
+        data: &[P],
+    ) -> crate::Result<()> {
+        self.upload(gl, level, [0, 0], self.level_size(level), data)
+    }
+}
+
+impl_typed_texture!(Texture2D, crate::gl::TEXTURE_2D);
+
+/// Array of 2D textures with immutable storage
+pub struct Texture2DArray {
+    texture: Texture,
+    format: InternalFormat,
+    levels: u32,
+    width: u32,
+    height: u32,
+    layers: u32,
+}
+
+impl Texture2DArray {
+    /// Create a 2D array texture and allocate its storage
+    ///
+    /// # Parameters
+    ///
+    /// * `gl`: GL context
+    /// * `format`: internal format of the texture
+    /// * `width`: width of the base level
+    /// * `height`: height of the base level
+    /// * `layers`: number of layers in the array
+    /// * `levels`: number of mip levels, at most [max_mip_levels]
+    pub fn with_storage(
+        gl: &crate::Context,
+        format: InternalFormat,
+        width: u32,
+        height: u32,
+        layers: u32,
+        levels: u32,
+    ) -> crate::Result<Self> {
+        assert!(levels >= 1 && levels <= max_mip_levels(width, height, 1));
+
+        let texture = create(gl, Self::TARGET)?;
+        storage(
+            gl,
+            &texture,
+            Self::TARGET,
+            format,
+            levels,
+            [width, height, layers],
+        );
+
+        Ok(Self {
+            texture,
+            format,
+            levels,
+            width,
+            height,
+            layers,
+        })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn layers(&self) -> u32 {
+        self.layers
+    }
+
+    /// Size of a mip level of a layer
+    pub fn level_size(&self, level: u32) -> [u32; 2] {
+        [mip_size(self.width, level), mip_size(self.height, level)]
+    }
+
+    /// Upload pixels to a region of a mip level
+    ///
+    /// # Parameters
+    ///
+    /// * `gl`: GL context
+    /// * `level`: mip level to update
+    /// * `offset`: offset of the region, in pixels and layers
+    /// * `size`: size of the region, in pixels and layers
+    /// * `data`: pixel data, laid out as expected for [InternalFormat::pixel_format] and
+    ///   [InternalFormat::pixel_type]
+    ///
+    /// # Errors
+    ///
+    /// Returns [crate::Error::TextureUploadFailed] if GL rejects the pixel data.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the level or region is out of bounds, or if `data` does not match its size.
+    pub fn upload<P: Pod>(
+        &self,
+        gl: &crate::Context,
+        level: u32,
+        offset: [u32; 3],
+        size: [u32; 3],
+        data: &[P],
+    ) -> crate::Result<()> {
+        let region = Region {
+            level,
+            offset,
+            size,
+        };
+
+        let [width, height] = self.level_size(level);
+        region.check(self.levels, [width, height, self.layers]);
+        upload_region(gl, &self.texture, Self::TARGET, self.format, &region, data)
+    }
+
+    /// Upload pixels to a whole mip level of a layer
+    pub fn upload_layer<P: Pod>(
+        &self,
+        gl: &crate::Context,
+        level: u32,
+        layer: u32,
+        data: &[P],
+    ) -> crate::Result<()> {
+        let [width, height] = self.level_size(level);
+        self.upload(gl, level, [0, 0, layer], [width, height, 1], data)
+    }
+}
+
+impl_typed_texture!(Texture2DArray, crate::gl::TEXTURE_2D_ARRAY);
+
+/// 3D texture with immutable storage
+pub struct Texture3D {
+    texture: Texture,
+    format: InternalFormat,
+    levels: u32,
+    width: u32,
+    height: u32,
+    depth: u32,
+}
+
+impl Texture3D {
+    /// Create a 3D texture and allocate its storage
+    ///
+    /// # Parameters
+    ///
+    /// * `gl`: GL context
+    /// * `format`: internal format of the texture
+    /// * `width`: width of the base level
+    /// * `height`: height of the base level
+    /// * `depth`: depth of the base level
+    /// * `levels`: number of mip levels, at most [max_mip_levels]
+    pub fn with_storage(
+        gl: &crate::Context,
+        format: InternalFormat,
+        width: u32,
+        height: u32,
+        depth: u32,
+        levels: u32,
+    ) -> crate::Result<Self> {
+        assert!(levels >= 1 && levels <= max_mip_levels(width, height, depth));
+
+        let texture = create(gl, Self::TARGET)?;
+        storage(
+            gl,
+            &texture,
+            Self::TARGET,
+            format,
+            levels,
+            [width, height, depth],
+        );
+
+        Ok(Self {
+            texture,
+            format,
+            levels,
+            width,
+            height,
+            depth,
+        })
+    }
+
+    pub fn width(&self) -> u32 {
+        self.width
+    }
+
+    pub fn height(&self) -> u32 {
+        self.height
+    }
+
+    pub fn depth(&self) -> u32 {
+        self.depth
+    }
+
+    /// Size of a mip level
+    pub fn level_size(&self, level: u32) -> [u32; 3] {
+        [
+            mip_size(self.width, level),
+            mip_size(self.height, level),
+            mip_size(self.depth, level),
+        ]
+    }
+
+    /// Upload pixels to a region of a mip level
+    ///
+    /// # Parameters
+    ///
+    /// * `gl`: GL context
+    /// * `level`: mip level to update
+    /// * `offset`: offset of the region, in pixels
+    /// * `size`: size of the region, in pixels
+    /// * `data`: pixel data, laid out as expected for [InternalFormat::pixel_format] and
+    ///   [InternalFormat::pixel_type]
+    ///
+    /// # Errors
+    ///
+    /// Returns [crate::Error::TextureUploadFailed] if GL rejects the pixel data.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the level or region is out of bounds, or if `data` does not match its size.
+    pub fn upload<P: Pod>(
+        &self,
+        gl: &crate::Context,
+        level: u32,
+        offset: [u32; 3],
+        size: [u32; 3],
+        data: &[P],
+    ) -> crate::Result<()> {
+        let region = Region {
+            level,
+            offset,
+            size,
+        };
+
+        region.check(self.levels, self.level_size(level));
+        upload_region(gl, &self.texture, Self::TARGET, self.format, &region, data)
+    }
+
+    /// Upload pixels to a whole mip level
+    pub fn upload_level<P: Pod>(
+        &self,
+        gl: &crate::Context,
+        level: u32,
+        data: &[P],
+    ) -> crate::Result<()> {
+        self.upload(gl, level, [0, 0, 0], self.level_size(level), data)
+    }
+}
+
+impl_typed_texture!(Texture3D, crate::gl::TEXTURE_3D);
+
+/// Cube map texture with immutable storage
+pub struct TextureCube {
+    texture: Texture,
+    format: InternalFormat,
+    levels: u32,
+    size: u32,
+}
+
+impl TextureCube {
+    /// Create a cube map texture and allocate its storage
+    ///
+    /// # Parameters
+    ///
+    /// * `gl`: GL context
+    /// * `format`: internal format of the texture
+    /// * `size`: width and height of the faces of the base level
+    /// * `levels`: number of mip levels, at most [max_mip_levels]
+    pub fn with_storage(
+        gl: &crate::Context,
+        format: InternalFormat,
+        size: u32,
+        levels: u32,
+    ) -> crate::Result<Self> {
+        assert!(levels >= 1 && levels <= max_mip_levels(size, size, 1));
+
+        let texture = create(gl, Self::TARGET)?;
+        storage(gl, &texture, Self::TARGET, format, levels, [size, size, 1]);
+
+        Ok(Self {
+            texture,
+            format,
+            levels,
+            size,
+        })
+    }
+
+    /// Width and height of the faces of the base level
+    pub fn size(&self) -> u32 {
+        self.size
+    }
+
+    /// Width and height of the faces of a mip level
+    pub fn level_size(&self, level: u32) -> u32 {
+        mip_size(self.size, level)
+    }
+
+    /// Upload pixels to a region of a face
+    ///
+    /// # Parameters
+    ///
+    /// * `gl`: GL context
+    /// * `face`: face to update
+    /// * `level`: mip level to update
+    /// * `offset`: offset of the region, in pixels
+    /// * `size`: size of the region, in pixels
+    /// * `data`: pixel data, laid out as expected for [InternalFormat::pixel_format] and
+    ///   [InternalFormat::pixel_type]
+    ///
+    /// # Errors
+    ///
+    /// Returns [crate::Error::TextureUploadFailed] if GL rejects the pixel data.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the level or region is out of bounds, or if `data` does not match its size.
+    pub fn upload<P: Pod>(
+        &self,
+        gl: &crate::Context,
+        face: CubeFace,
+        level: u32,
+        offset: [u32; 2],
+        size: [u32; 2],
+        data: &[P],
+    ) -> crate::Result<()> {
+        let region = Region {
+            level,
+            offset: [offset[0], offset[1], face.index()],
+            size: [size[0], size[1], 1],
+        };
+
+        let face_size = self.level_size(level);
+        region.check(self.levels, [face_size, face_size, 6]);
+        upload_region(gl, &self.texture, Self::TARGET, self.format, &region, data)
+    }
+
+    /// Upload pixels to a whole mip level of a face
+    pub fn upload_face<P: Pod>(
+        &self,
+        gl: &crate::Context,
+        face: CubeFace,
+        level: u32,
+        data: &[P],
+    ) -> crate::Result<()> {
+        let face_size = self.level_size(level);
+        self.upload(gl, face, level, [0, 0], [face_size, face_size], data)
+    }
+}
+
+impl_typed_texture!(TextureCube, crate::gl::TEXTURE_CUBE_MAP);
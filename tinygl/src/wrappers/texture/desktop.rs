@@ -0,0 +1,141 @@
+use super::{InternalFormat, Region, Texture};
+use crate::OpenGlErrorCode;
+
+pub(super) fn create(gl: &crate::Context, target: u32) -> crate::Result<Texture> {
+    unsafe {
+        let mut name = 0u32;
+        gl.create_textures(target, 1, &mut name);
+        if name == 0 {
+            return Err(crate::Error::TextureCreationFailed(OpenGlErrorCode(
+                gl.get_error(),
+            )));
+        }
+
+        Ok(Texture { name })
+    }
+}
+
+pub(super) fn storage(
+    gl: &crate::Context,
+    texture: &Texture,
+    target: u32,
+    format: InternalFormat,
+    levels: u32,
+    size: [u32; 3],
+) {
+    unsafe {
+        match target {
+            crate::gl::TEXTURE_2D | crate::gl::TEXTURE_CUBE_MAP => gl.texture_storage_2d(
+                texture.name,
+                levels as i32,
+                format.gl_enum(),
+                size[0] as i32,
+                size[1] as i32,
+            ),
+            _ => gl.texture_storage_3d(
+                texture.name,
+                levels as i32,
+                format.gl_enum(),
+                size[0] as i32,
+                size[1] as i32,
+                size[2] as i32,
+            ),
+        }
+    }
+}
+
+pub(super) fn sub_image(
+    gl: &crate::Context,
+    texture: &Texture,
+    target: u32,
+    region: &Region,
+    format: InternalFormat,
+    data: &[u8],
+) -> crate::Result<()> {
+    unsafe {
+        // Cube map faces are addressed as layers
+        match target {
+            crate::gl::TEXTURE_2D => gl.texture_sub_image_2d(
+                texture.name,
+                region.level as i32,
+                region.offset[0] as i32,
+                region.offset[1] as i32,
+                region.size[0] as i32,
+                region.size[1] as i32,
+                format.pixel_format(),
+                format.pixel_type(),
+                data.as_ptr() as *const std::ffi::c_void,
+            ),
+            _ => gl.texture_sub_image_3d(
+                texture.name,
+                region.level as i32,
+                region.offset[0] as i32,
+                region.offset[1] as i32,
+                region.offset[2] as i32,
+                region.size[0] as i32,
+                region.size[1] as i32,
+                region.size[2] as i32,
+                format.pixel_format(),
+                format.pixel_type(),
+                data.as_ptr() as *const std::ffi::c_void,
+            ),
+        }
+
+        match gl.get_error() {
+            crate::gl::NO_ERROR => Ok(()),
+            error => Err(crate::Error::TextureUploadFailed(OpenGlErrorCode(error))),
+        }
+    }
+}
+
+pub(super) fn pixel_store(gl: &crate::Context, pname: u32, value: i32) {
+    unsafe {
+        gl.pixel_storei(pname, value);
+    }
+}
+
+pub(super) fn generate_mipmap(gl: &crate::Context, texture: &Texture, _target: u32) {
+    unsafe {
+        gl.generate_texture_mipmap(texture.name);
+    }
+}
+
+pub(super) fn parameter_i(
+    gl: &crate::Context,
+    texture: &Texture,
+    _target: u32,
+    pname: u32,
+    value: i32,
+) {
+    unsafe {
+        gl.texture_parameteri(texture.name, pname, value);
+    }
+}
+
+pub(super) fn parameter_f(
+    gl: &crate::Context,
+    texture: &Texture,
+    _target: u32,
+    pname: u32,
+    value: f32,
+) {
+    unsafe {
+        gl.texture_parameterf(texture.name, pname, value);
+    }
+}
+
+pub(super) fn parameter_fv(
+    gl: &crate::Context,
+    texture: &Texture,
+    _target: u32,
+    pname: u32,
+    value: &[f32],
+) {
+    unsafe {
+        gl.texture_parameterfv(texture.name, pname, value.as_ptr());
+    }
+}
+
+pub(super) unsafe fn bind_unit(gl: &crate::Context, texture: &Texture, _target: u32, unit: u32) {
+    gl.bind_texture_unit(unit, texture.name);
+}
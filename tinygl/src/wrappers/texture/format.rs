@@ -0,0 +1,178 @@
+use crate::wrappers::AttachmentType;
+
+/// Sized internal format of a texture or renderbuffer
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum InternalFormat {
+    R8,
+    Rg8,
+    Rgb8,
+    Rgba8,
+    Srgb8,
+    Srgb8Alpha8,
+    Rgb10A2,
+    R16f,
+    Rg16f,
+    Rgba16f,
+    R32f,
+    Rg32f,
+    Rgb32f,
+    Rgba32f,
+    R11fG11fB10f,
+    R8ui,
+    Rgba8ui,
+    R32ui,
+    Rg32ui,
+    Rgba32ui,
+    R32i,
+    Rg32i,
+    Rgba32i,
+    Depth16,
+    Depth24,
+    Depth32f,
+    Depth24Stencil8,
+    Depth32fStencil8,
+}
+
+impl InternalFormat {
+    /// GL value of the internal format
+    pub fn gl_enum(self) -> u32 {
+        use crate::gl;
+
+        match self {
+            Self::R8 => gl::R8,
+            Self::Rg8 => gl::RG8,
+            Self::Rgb8 => gl::RGB8,
+            Self::Rgba8 => gl::RGBA8,
+            Self::Srgb8 => gl::SRGB8,
+            Self::Srgb8Alpha8 => gl::SRGB8_ALPHA8,
+            Self::Rgb10A2 => gl::RGB10_A2,
+            Self::R16f => gl::R16F,
+            Self::Rg16f => gl::RG16F,
+            Self::Rgba16f => gl::RGBA16F,
+            Self::R32f => gl::R32F,
+            Self::Rg32f => gl::RG32F,
+            Self::Rgb32f => gl::RGB32F,
+            Self::Rgba32f => gl::RGBA32F,
+            Self::R11fG11fB10f => gl::R11F_G11F_B10F,
+            Self::R8ui => gl::R8UI,
+            Self::Rgba8ui => gl::RGBA8UI,
+            Self::R32ui => gl::R32UI,
+            Self::Rg32ui => gl::RG32UI,
+            Self::Rgba32ui => gl::RGBA32UI,
+            Self::R32i => gl::R32I,
+            Self::Rg32i => gl::RG32I,
+            Self::Rgba32i => gl::RGBA32I,
+            Self::Depth16 => gl::DEPTH_COMPONENT16,
+            Self::Depth24 => gl::DEPTH_COMPONENT24,
+            Self::Depth32f => gl::DEPTH_COMPONENT32F,
+            Self::Depth24Stencil8 => gl::DEPTH24_STENCIL8,
+            Self::Depth32fStencil8 => gl::DEPTH32F_STENCIL8,
+        }
+    }
+
+    /// Format of client pixel data for this internal format
+    pub fn pixel_format(self) -> u32 {
+        use crate::gl;
+
+        match self {
+            Self::R8 | Self::R16f | Self::R32f => gl::RED,
+            Self::Rg8 | Self::Rg16f | Self::Rg32f => gl::RG,
+            Self::Rgb8 | Self::Srgb8 | Self::Rgb32f | Self::R11fG11fB10f => gl::RGB,
+            Self::Rgba8 | Self::Srgb8Alpha8 | Self::Rgb10A2 | Self::Rgba16f | Self::Rgba32f => {
+                gl::RGBA
+            }
+            Self::R8ui | Self::R32ui | Self::R32i => gl::RED_INTEGER,
+            Self::Rg32ui | Self::Rg32i => gl::RG_INTEGER,
+            Self::Rgba8ui | Self::Rgba32ui | Self::Rgba32i => gl::RGBA_INTEGER,
+            Self::Depth16 | Self::Depth24 | Self::Depth32f => gl::DEPTH_COMPONENT,
+            Self::Depth24Stencil8 | Self::Depth32fStencil8 => gl::DEPTH_STENCIL,
+        }
+    }
+
+    /// Type of client pixel data for this internal format
+    pub fn pixel_type(self) -> u32 {
+        use crate::gl;
+
+        match self {
+            Self::R8
+            | Self::Rg8
+            | Self::Rgb8
+            | Self::Rgba8
+            | Self::Srgb8
+            | Self::Srgb8Alpha8
+            | Self::R8ui
+            | Self::Rgba8ui => gl::UNSIGNED_BYTE,
+            Self::Rgb10A2 => gl::UNSIGNED_INT_2_10_10_10_REV,
+            Self::R16f
+            | Self::Rg16f
+            | Self::Rgba16f
+            | Self::R32f
+            | Self::Rg32f
+            | Self::Rgb32f
+            | Self::Rgba32f
+            | Self::Depth32f => gl::FLOAT,
+            Self::R11fG11fB10f => gl::UNSIGNED_INT_10F_11F_11F_REV,
+            Self::R32ui | Self::Rg32ui | Self::Rgba32ui | Self::Depth24 => gl::UNSIGNED_INT,
+            Self::R32i | Self::Rg32i | Self::Rgba32i => gl::INT,
+            Self::Depth16 => gl::UNSIGNED_SHORT,
+            Self::Depth24Stencil8 => gl::UNSIGNED_INT_24_8,
+            Self::Depth32fStencil8 => gl::FLOAT_32_UNSIGNED_INT_24_8_REV,
+        }
+    }
+
+    /// Size in bytes of one pixel of client data
+    pub fn pixel_size(self) -> usize {
+        match self {
+            Self::R8 | Self::R8ui => 1,
+            Self::Rg8 | Self::Depth16 => 2,
+            Self::Rgb8 | Self::Srgb8 => 3,
+            Self::Rgba8
+            | Self::Srgb8Alpha8
+            | Self::Rgb10A2
+            | Self::R16f
+            | Self::R32f
+            | Self::R11fG11fB10f
+            | Self::Rgba8ui
+            | Self::R32ui
+            | Self::R32i
+            | Self::Depth24
+            | Self::Depth32f
+            | Self::Depth24Stencil8 => 4,
+            Self::Rg16f | Self::Rg32f | Self::Rg32ui | Self::Rg32i | Self::Depth32fStencil8 => 8,
+            Self::Rgb32f => 12,
+            Self::Rgba16f | Self::Rgba32f | Self::Rgba32ui | Self::Rgba32i => 16,
+        }
+    }
+
+    /// true if the format has a depth component
+    pub fn has_depth(self) -> bool {
+        matches!(
+            self,
+            Self::Depth16
+                | Self::Depth24
+                | Self::Depth32f
+                | Self::Depth24Stencil8
+                | Self::Depth32fStencil8
+        )
+    }
+
+    /// true if the format has a stencil component
+    pub fn has_stencil(self) -> bool {
+        matches!(self, Self::Depth24Stencil8 | Self::Depth32fStencil8)
+    }
+
+    /// Type of color attachment this format can be used as, `None` for depth formats
+    pub fn attachment_type(self) -> Option<AttachmentType> {
+        match self.pixel_format() {
+            crate::gl::RED_INTEGER | crate::gl::RG_INTEGER | crate::gl::RGBA_INTEGER => {
+                if self.pixel_type() == crate::gl::INT {
+                    Some(AttachmentType::Int)
+                } else {
+                    Some(AttachmentType::UInt)
+                }
+            }
+            _ if self.has_depth() => None,
+            _ => Some(AttachmentType::Float),
+        }
+    }
+}
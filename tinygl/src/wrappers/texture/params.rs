@@ -0,0 +1,129 @@
+use crate::gl;
+
+/// Filter used when a texture is magnified
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum MagFilter {
+    Nearest,
+    Linear,
+}
+
+impl MagFilter {
+    pub fn gl_enum(self) -> u32 {
+        match self {
+            Self::Nearest => gl::NEAREST,
+            Self::Linear => gl::LINEAR,
+        }
+    }
+}
+
+/// Filter used when a texture is minified
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum MinFilter {
+    Nearest,
+    Linear,
+    NearestMipmapNearest,
+    LinearMipmapNearest,
+    NearestMipmapLinear,
+    LinearMipmapLinear,
+}
+
+impl MinFilter {
+    pub fn gl_enum(self) -> u32 {
+        match self {
+            Self::Nearest => gl::NEAREST,
+            Self::Linear => gl::LINEAR,
+            Self::NearestMipmapNearest => gl::NEAREST_MIPMAP_NEAREST,
+            Self::LinearMipmapNearest => gl::LINEAR_MIPMAP_NEAREST,
+            Self::NearestMipmapLinear => gl::NEAREST_MIPMAP_LINEAR,
+            Self::LinearMipmapLinear => gl::LINEAR_MIPMAP_LINEAR,
+        }
+    }
+
+    /// true if the filter samples from mip levels other than the base level
+    pub fn uses_mipmaps(self) -> bool {
+        !matches!(self, Self::Nearest | Self::Linear)
+    }
+}
+
+/// Handling of texture coordinates outside of [0, 1]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum Wrap {
+    Repeat,
+    MirroredRepeat,
+    ClampToEdge,
+    #[cfg(not(target_arch = "wasm32"))]
+    ClampToBorder,
+}
+
+impl Wrap {
+    pub fn gl_enum(self) -> u32 {
+        match self {
+            Self::Repeat => gl::REPEAT,
+            Self::MirroredRepeat => gl::MIRRORED_REPEAT,
+            Self::ClampToEdge => gl::CLAMP_TO_EDGE,
+            #[cfg(not(target_arch = "wasm32"))]
+            Self::ClampToBorder => gl::CLAMP_TO_BORDER,
+        }
+    }
+}
+
+/// Comparison applied to the reference value when sampling depth textures
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum CompareFunc {
+    Never,
+    Less,
+    Equal,
+    LessEqual,
+    Greater,
+    NotEqual,
+    GreaterEqual,
+    Always,
+}
+
+impl CompareFunc {
+    pub fn gl_enum(self) -> u32 {
+        match self {
+            Self::Never => gl::NEVER,
+            Self::Less => gl::LESS,
+            Self::Equal => gl::EQUAL,
+            Self::LessEqual => gl::LEQUAL,
+            Self::Greater => gl::GREATER,
+            Self::NotEqual => gl::NOTEQUAL,
+            Self::GreaterEqual => gl::GEQUAL,
+            Self::Always => gl::ALWAYS,
+        }
+    }
+}
+
+/// Face of a cube map texture
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum CubeFace {
+    PositiveX,
+    NegativeX,
+    PositiveY,
+    NegativeY,
+    PositiveZ,
+    NegativeZ,
+}
+
+impl CubeFace {
+    /// All faces, in layer order
+    pub const ALL: [CubeFace; 6] = [
+        Self::PositiveX,
+        Self::NegativeX,
+        Self::PositiveY,
+        Self::NegativeY,
+        Self::PositiveZ,
+        Self::NegativeZ,
+    ];
+
+    /// Layer index of the face
+    pub fn index(self) -> u32 {
+        self as u32
+    }
+
+    /// Target used to refer to this face in non-DSA calls
+    pub fn target(self) -> u32 {
+        gl::TEXTURE_CUBE_MAP_POSITIVE_X + self.index()
+    }
+}
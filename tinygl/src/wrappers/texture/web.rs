@@ -0,0 +1,165 @@
+use super::{InternalFormat, Region, Texture};
+use crate::OpenGlErrorCode;
+
+// WebGL has no direct state access, so these functions bind the texture on the active unit
+
+pub(super) fn create(gl: &crate::Context, target: u32) -> crate::Result<Texture> {
+    let texture = Texture::new(gl)?;
+    unsafe {
+        texture.bind(gl, target);
+    }
+
+    Ok(texture)
+}
+
+pub(super) fn storage(
+    gl: &crate::Context,
+    texture: &Texture,
+    target: u32,
+    format: InternalFormat,
+    levels: u32,
+    size: [u32; 3],
+) {
+    unsafe {
+        texture.bind(gl, target);
+    }
+
+    match target {
+        crate::gl::TEXTURE_2D | crate::gl::TEXTURE_CUBE_MAP => gl.tex_storage_2d(
+            target,
+            levels as i32,
+            format.gl_enum(),
+            size[0] as i32,
+            size[1] as i32,
+        ),
+        _ => gl.tex_storage_3d(
+            target,
+            levels as i32,
+            format.gl_enum(),
+            size[0] as i32,
+            size[1] as i32,
+            size[2] as i32,
+        ),
+    }
+}
+
+/// Copy pixel data into a typed array matching `pixel_type`, as required by WebGL
+fn pixel_view(data: &[u8], pixel_type: u32) -> Option<js_sys::Object> {
+    // The copy lives in a new buffer, so it is aligned for any view
+    let bytes = js_sys::Uint8Array::new_with_length(data.len() as u32);
+    bytes.copy_from(data);
+    let buffer = bytes.buffer();
+
+    Some(match pixel_type {
+        crate::gl::UNSIGNED_BYTE => bytes.into(),
+        crate::gl::UNSIGNED_SHORT => js_sys::Uint16Array::new(&buffer).into(),
+        crate::gl::FLOAT => js_sys::Float32Array::new(&buffer).into(),
+        crate::gl::INT => js_sys::Int32Array::new(&buffer).into(),
+        crate::gl::UNSIGNED_INT
+        | crate::gl::UNSIGNED_INT_2_10_10_10_REV
+        | crate::gl::UNSIGNED_INT_10F_11F_11F_REV
+        | crate::gl::UNSIGNED_INT_24_8 => js_sys::Uint32Array::new(&buffer).into(),
+        // FLOAT_32_UNSIGNED_INT_24_8_REV can't be uploaded from client data
+        _ => return None,
+    })
+}
+
+pub(super) fn sub_image(
+    gl: &crate::Context,
+    texture: &Texture,
+    target: u32,
+    region: &Region,
+    format: InternalFormat,
+    data: &[u8],
+) -> crate::Result<()> {
+    let view = pixel_view(data, format.pixel_type()).ok_or(crate::Error::TextureUploadFailed(
+        OpenGlErrorCode(crate::gl::INVALID_OPERATION),
+    ))?;
+
+    unsafe {
+        texture.bind(gl, target);
+    }
+
+    let result = match target {
+        crate::gl::TEXTURE_2D | crate::gl::TEXTURE_CUBE_MAP => {
+            // Cube map faces have their own targets
+            let target = if target == crate::gl::TEXTURE_CUBE_MAP {
+                crate::gl::TEXTURE_CUBE_MAP_POSITIVE_X + region.offset[2]
+            } else {
+                target
+            };
+
+            gl.tex_sub_image_2d_with_i32_and_i32_and_u32_and_type_and_opt_array_buffer_view(
+                target,
+                region.level as i32,
+                region.offset[0] as i32,
+                region.offset[1] as i32,
+                region.size[0] as i32,
+                region.size[1] as i32,
+                format.pixel_format(),
+                format.pixel_type(),
+                Some(&view),
+            )
+        }
+        _ => gl.tex_sub_image_3d_with_opt_array_buffer_view(
+            target,
+            region.level as i32,
+            region.offset[0] as i32,
+            region.offset[1] as i32,
+            region.offset[2] as i32,
+            region.size[0] as i32,
+            region.size[1] as i32,
+            region.size[2] as i32,
+            format.pixel_format(),
+            format.pixel_type(),
+            Some(&view),
+        ),
+    };
+
+    result.map_err(|_| crate::Error::TextureUploadFailed(OpenGlErrorCode(gl.get_error())))
+}
+
+pub(super) fn pixel_store(gl: &crate::Context, pname: u32, value: i32) {
+    gl.pixel_storei(pname, value);
+}
+
+pub(super) fn generate_mipmap(gl: &crate::Context, texture: &Texture, target: u32) {
+    unsafe {
+        texture.bind(gl, target);
+    }
+
+    gl.generate_mipmap(target);
+}
+
+pub(super) fn parameter_i(
+    gl: &crate::Context,
+    texture: &Texture,
+    target: u32,
+    pname: u32,
+    value: i32,
+) {
+    unsafe {
+        texture.bind(gl, target);
+    }
+
+    gl.tex_parameteri(target, pname, value);
+}
+
+pub(super) fn parameter_f(
+    gl: &crate::Context,
+    texture: &Texture,
+    target: u32,
+    pname: u32,
+    value: f32,
+) {
+    unsafe {
+        texture.bind(gl, target);
+    }
+
+    gl.tex_parameterf(target, pname, value);
+}
+
+pub(super) unsafe fn bind_unit(gl: &crate::Context, texture: &Texture, target: u32, unit: u32) {
+    gl.active_texture(crate::gl::TEXTURE0 + unit);
+    texture.bind(gl, target);
+}
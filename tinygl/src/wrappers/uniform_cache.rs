@@ -0,0 +1,34 @@
+/// Counters of a generated uniform cache
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct UniformCacheStats {
+    /// Number of values uploaded to the program
+    pub uploads: u64,
+    /// Number of uploads skipped because the value did not change
+    pub elided: u64,
+}
+
+impl UniformCacheStats {
+    /// Record a setter call
+    ///
+    /// # Parameters
+    ///
+    /// * `elided`: true if the value was unchanged and the upload was skipped
+    pub fn record(&mut self, elided: bool) {
+        if elided {
+            self.elided += 1;
+        } else {
+            self.uploads += 1;
+        }
+    }
+}
+
+impl std::ops::Add for UniformCacheStats {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        Self {
+            uploads: self.uploads + rhs.uploads,
+            elided: self.elided + rhs.elided,
+        }
+    }
+}
@@ -1,16 +1,19 @@
-use crate::gl;
+use std::marker::PhantomData;
 
-// TODO: We're using an unwrapped program name because otherwise we would need to borrow the
-// program object to keep the location around. Or, this is leads to borrowck problems. The runtime
-// program/uniform API being unsafe, this isn't too bad but it's still worth looking into later.
+use crate::gl;
+use crate::wrappers::ProgramCommon;
 
+/// Location of a uniform in a program
+///
+/// Locations borrow the program they were queried from, so they can't be used once it is deleted.
 #[derive(Clone)]
-pub struct UniformLocation {
+pub struct UniformLocation<'p> {
     program: crate::gl::Program,
     location: Option<crate::gl::UniformLocation>,
+    _program: PhantomData<&'p crate::gl::Program>,
 }
 
-impl std::fmt::Debug for UniformLocation {
+impl std::fmt::Debug for UniformLocation<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("UniformLocation")
             .field("program", &self.program)
@@ -19,9 +22,16 @@ impl std::fmt::Debug for UniformLocation {
     }
 }
 
-impl UniformLocation {
-    pub fn new(program: crate::gl::Program, location: Option<crate::gl::UniformLocation>) -> Self {
-        Self { program, location }
+impl<'p> UniformLocation<'p> {
+    pub fn new<P: ProgramCommon + ?Sized>(
+        program: &'p P,
+        location: Option<crate::gl::UniformLocation>,
+    ) -> Self {
+        Self {
+            program: program.name(),
+            location,
+            _program: PhantomData,
+        }
     }
 
     pub fn is_active(&self) -> bool {
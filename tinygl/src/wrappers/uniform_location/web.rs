@@ -1,11 +1,18 @@
+use std::marker::PhantomData;
 use std::mem::transmute;
 
+use crate::wrappers::ProgramCommon;
+
+/// Location of a uniform in a program
+///
+/// Locations borrow the program they were queried from, so they can't be used once it is deleted.
 #[derive(Clone)]
-pub struct UniformLocation {
+pub struct UniformLocation<'p> {
     location: Option<crate::gl::UniformLocation>,
+    _program: PhantomData<&'p crate::gl::Program>,
 }
 
-impl std::fmt::Debug for UniformLocation {
+impl std::fmt::Debug for UniformLocation<'_> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("UniformLocation")
             .field("location", &self.location)
@@ -13,9 +20,15 @@ impl std::fmt::Debug for UniformLocation {
     }
 }
 
-impl UniformLocation {
-    pub fn new(location: Option<crate::gl::UniformLocation>) -> Self {
-        Self { location }
+impl<'p> UniformLocation<'p> {
+    pub fn new<P: ProgramCommon + ?Sized>(
+        _program: &'p P,
+        location: Option<crate::gl::UniformLocation>,
+    ) -> Self {
+        Self {
+            location,
+            _program: PhantomData,
+        }
     }
 
     pub fn is_active(&self) -> bool {
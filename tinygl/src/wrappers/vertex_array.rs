@@ -19,3 +19,219 @@ impl VertexArray {
 }
 
 impl_ndrop!(VertexArray, delete_vertex_arrays, delete_vertex_array);
+
+/// Base type of a vertex shader input
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum VertexAttributeType {
+    Float,
+    Double,
+    Int,
+    UInt,
+}
+
+/// Description of a vertex shader input
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub struct VertexAttribute {
+    /// Name of the input, as declared in the shader
+    pub name: &'static str,
+    /// Location of the first column of the input
+    pub location: u32,
+    /// Base type of the input
+    pub ty: VertexAttributeType,
+    /// Number of components per location
+    pub components: u32,
+    /// Number of consecutive locations used by the input, two per column for double vectors of
+    /// more than 2 components
+    pub locations: u32,
+}
+
+/// Rust types that can be read from a buffer as a vertex attribute
+///
+/// # Safety
+///
+/// The type must be laid out as `LOCATIONS` consecutive groups of `COMPONENTS` values of the
+/// Rust primitive matching `TYPE`.
+pub unsafe trait VertexAttributeData {
+    const TYPE: VertexAttributeType;
+    const COMPONENTS: u32;
+    const LOCATIONS: u32;
+}
+
+macro_rules! impl_vertex_attribute_data {
+    ($t:ty => $ty:ident, $components:expr, $locations:expr) => {
+        unsafe impl VertexAttributeData for $t {
+            const TYPE: VertexAttributeType = VertexAttributeType::$ty;
+            const COMPONENTS: u32 = $components;
+            const LOCATIONS: u32 = $locations;
+        }
+    };
+
+    (vector $t:ty => $ty:ident) => {
+        impl_vertex_attribute_data!($t => $ty, 1, 1);
+        impl_vertex_attribute_data!([$t; 1] => $ty, 1, 1);
+        impl_vertex_attribute_data!([$t; 2] => $ty, 2, 1);
+        impl_vertex_attribute_data!([$t; 3] => $ty, 3, 1);
+        impl_vertex_attribute_data!([$t; 4] => $ty, 4, 1);
+    };
+
+    (matrix $t:ty => $ty:ident; $($columns:literal),*) => {
+        $(
+            impl_vertex_attribute_data!([[$t; 2]; $columns] => $ty, 2, $columns);
+            impl_vertex_attribute_data!([[$t; 3]; $columns] => $ty, 3, $columns);
+            impl_vertex_attribute_data!([[$t; 4]; $columns] => $ty, 4, $columns);
+        )*
+    };
+}
+
+impl_vertex_attribute_data!(vector f32 => Float);
+impl_vertex_attribute_data!(vector i32 => Int);
+impl_vertex_attribute_data!(vector u32 => UInt);
+impl_vertex_attribute_data!(matrix f32 => Float; 2, 3, 4);
+#[cfg(not(target_arch = "wasm32"))]
+impl_vertex_attribute_data!(vector f64 => Double);
+#[cfg(not(target_arch = "wasm32"))]
+impl_vertex_attribute_data!(matrix f64 => Double; 2, 3, 4);
+
+/// Source of a vertex attribute in a buffer
+pub struct VertexBufferBinding<'b, T: VertexAttributeData> {
+    buffer: &'b crate::gl::Buffer,
+    stride: i32,
+    offset: i32,
+    _data: std::marker::PhantomData<T>,
+}
+
+impl<'b, T: VertexAttributeData> VertexBufferBinding<'b, T> {
+    /// Create a new vertex buffer binding
+    ///
+    /// # Parameters
+    ///
+    /// * `buffer`: buffer holding the vertex data
+    /// * `stride`: offset in bytes between consecutive vertices, 0 for tightly packed values
+    /// * `offset`: offset in bytes of the first value in the buffer
+    pub fn new<B: super::Pod>(buffer: &'b super::Buffer<B>, stride: i32, offset: i32) -> Self {
+        Self {
+            buffer: &buffer.name,
+            stride,
+            offset,
+            _data: std::marker::PhantomData,
+        }
+    }
+
+    /// Name of the buffer holding the vertex data
+    pub fn buffer(&self) -> &'b crate::gl::Buffer {
+        self.buffer
+    }
+
+    pub fn stride(&self) -> i32 {
+        self.stride
+    }
+
+    pub fn offset(&self) -> i32 {
+        self.offset
+    }
+
+    /// Configure the currently bound vertex array to source `attribute` from this binding
+    ///
+    /// # Parameters
+    ///
+    /// * `gl`: GL context
+    /// * `attribute`: shader input to configure
+    ///
+    /// # Safety
+    ///
+    /// The vertex array to configure must be bound on `gl`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [crate::Error::VertexAttributeMismatch] if `T` does not match the type of the
+    /// shader input, or if it does not fit in the vertex stride.
+    pub unsafe fn configure(
+        &self,
+        gl: &crate::Context,
+        attribute: &VertexAttribute,
+    ) -> crate::Result<()> {
+        // Double vectors of more than 2 components take two locations
+        let column_locations = if T::TYPE == VertexAttributeType::Double && T::COMPONENTS > 2 {
+            2
+        } else {
+            1
+        };
+
+        if T::TYPE != attribute.ty
+            || T::COMPONENTS != attribute.components
+            || T::LOCATIONS * column_locations != attribute.locations
+        {
+            return Err(crate::Error::VertexAttributeMismatch(
+                attribute.name,
+                format!(
+                    "expected {} location(s) of {} {:?} component(s), got {} location(s) of {} {:?} component(s)",
+                    attribute.locations,
+                    attribute.components,
+                    attribute.ty,
+                    T::LOCATIONS * column_locations,
+                    T::COMPONENTS,
+                    T::TYPE
+                ),
+            ));
+        }
+
+        let size = std::mem::size_of::<T>() as i32;
+        if self.stride != 0 && size > self.stride {
+            return Err(crate::Error::VertexAttributeMismatch(
+                attribute.name,
+                format!("{} bytes do not fit in a stride of {}", size, self.stride),
+            ));
+        }
+
+        // Only the buffer name is kept by the binding, so bind it directly
+        (**gl).bind_buffer(crate::gl::ARRAY_BUFFER, make_name!(Option => *self.buffer));
+
+        // A stride of 0 means tightly packed values, which only holds for single columns
+        let stride = if self.stride == 0 { size } else { self.stride };
+        let column_size = size / T::LOCATIONS as i32;
+        for i in 0..T::LOCATIONS {
+            let location = attribute.location + i * column_locations;
+            let offset = self.offset + i as i32 * column_size;
+            let components = T::COMPONENTS as i32;
+
+            gl.enable_vertex_attrib_array(location);
+
+            match T::TYPE {
+                VertexAttributeType::Float => gl.vertex_attrib_pointer_f32(
+                    location,
+                    components,
+                    crate::gl::FLOAT,
+                    false,
+                    stride,
+                    offset,
+                ),
+                VertexAttributeType::Int => gl.vertex_attrib_pointer_i32(
+                    location,
+                    components,
+                    crate::gl::INT,
+                    stride,
+                    offset,
+                ),
+                VertexAttributeType::UInt => gl.vertex_attrib_pointer_i32(
+                    location,
+                    components,
+                    crate::gl::UNSIGNED_INT,
+                    stride,
+                    offset,
+                ),
+                #[cfg(not(target_arch = "wasm32"))]
+                VertexAttributeType::Double => gl.vertex_attrib_pointer_f64(
+                    location,
+                    components,
+                    crate::gl::DOUBLE,
+                    stride,
+                    offset,
+                ),
+                #[cfg(target_arch = "wasm32")]
+                VertexAttributeType::Double => unreachable!(),
+            }
+        }
+
+        Ok(())
+    }
+}